@@ -1,23 +1,48 @@
 use clap::Parser;
-use serde::{Deserialize, Serialize};
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
 use std::path::PathBuf;
+use std::io::Read;
+use std::str::FromStr;
+use std::time::Duration;
+use chrono::{NaiveDateTime, NaiveTime};
 use anyhow::Result;
+use unicode_width::UnicodeWidthStr;
+use crate::notifications::SoundKind;
+use crate::timer::{default_plan, PlanStep, SessionType};
 
 /// Command line arguments for the Pomodoro timer
 #[derive(Parser, Debug)]
 #[command(name = "rustdoro")]
+#[command(version, long_version = crate::version::long_version())]
 #[command(about = "A terminal-based Pomodoro timer written in Rust")]
 pub struct CliArgs {
     /// Work session duration in minutes
-    #[arg(short = 'w', long = "work-duration", default_value = "25")]
+    #[arg(
+        short = 'w',
+        long = "work-duration",
+        default_value = "25",
+        value_parser = clap::value_parser!(u64).range(1..=MAX_SESSION_MINUTES)
+    )]
     pub work_duration: u64,
 
     /// Short break duration in minutes
-    #[arg(short = 's', long = "short-break", default_value = "5")]
+    #[arg(
+        short = 's',
+        long = "short-break",
+        default_value = "5",
+        value_parser = clap::value_parser!(u64).range(1..=MAX_SESSION_MINUTES)
+    )]
     pub short_break: u64,
 
     /// Long break duration in minutes
-    #[arg(short = 'l', long = "long-break", default_value = "10")]
+    #[arg(
+        short = 'l',
+        long = "long-break",
+        default_value = "15",
+        value_parser = clap::value_parser!(u64).range(1..=MAX_SESSION_MINUTES)
+    )]
     pub long_break: u64,
 
     /// Disable sound notifications
@@ -32,18 +57,74 @@ pub struct CliArgs {
     #[arg(long = "focus")]
     pub focus: bool,
 
+    /// Block skip, reset and quit while a work session runs (press Esc 3 times to quit)
+    #[arg(long = "focus-lock")]
+    pub focus_lock: bool,
+
+    /// Block skip, reset and pausing during breaks so they are taken in full
+    #[arg(long = "strict-break")]
+    pub strict_break: bool,
+
     /// Number of pomodoros before a long break
     #[arg(long = "long-break-after", default_value = "4")]
     pub long_break_after: u8,
 
+    /// Technique preset; explicit duration flags still take priority
+    #[arg(long = "technique")]
+    pub technique: Option<Technique>,
+
+    /// Count work sessions up like a stopwatch until ended with Skip
+    #[arg(long = "count-up")]
+    pub count_up: bool,
+
     /// Path to configuration file
     #[arg(long = "path")]
     pub config_path: Option<PathBuf>,
 
+    /// Read the whole configuration as TOML from standard input instead of a file
+    #[arg(long = "config-stdin", conflicts_with = "config_path")]
+    pub config_stdin: bool,
+
+    /// Delete the session history after asking for confirmation, and exit
+    #[arg(long = "clear-history")]
+    pub clear_history: bool,
+
+    /// Write a heatmap of the last year's pomodoros to a file (SVG for .svg, text otherwise), and exit
+    #[arg(long = "heatmap", value_name = "PATH")]
+    pub heatmap: Option<PathBuf>,
+
+    /// Print the names of the audio output devices, for `audio_device`, and exit
+    #[arg(long = "list-audio-devices")]
+    pub list_audio_devices: bool,
+
+    /// Answer yes to confirmation prompts
+    #[arg(short = 'y', long = "yes")]
+    pub yes: bool,
+
     /// Generate a sample configuration file at the default location
     #[arg(long = "generate-config")]
     pub generate_config: bool,
 
+    /// Check the configuration, print the resolved settings and session plan, and exit
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Play the work, break and end sounds in turn to check the audio setup, and exit
+    #[arg(long = "test-sound")]
+    pub test_sound: bool,
+
+    /// Play a built-in notification sound once and exit
+    #[arg(long = "preview-sound", value_name = "SOUND")]
+    pub preview_sound: Option<SoundKind>,
+
+    /// Write a built-in notification sound (work, break or end) to a WAV file and exit
+    #[arg(long = "export-sound", num_args = 2, value_names = ["SOUND", "PATH"])]
+    pub export_sound: Option<Vec<String>>,
+
+    /// Increase log detail in the log file (-v info, -vv debug, -vvv trace)
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
     /// Audio volume (0.0 to 1.0)
     #[arg(long = "volume")]
     pub volume: Option<f32>,
@@ -51,22 +132,392 @@ pub struct CliArgs {
     /// Custom audio file path
     #[arg(long = "audio-file")]
     pub audio_file: Option<String>,
+
+    /// Seconds the end alarm plays before stopping on its own (0 loops until input)
+    #[arg(long = "alarm-seconds")]
+    pub alarm_seconds: Option<u64>,
+
+    /// Number of pomodoros to complete in this run
+    #[arg(long = "target")]
+    pub target: Option<u32>,
+
+    /// Run a single countdown of this many minutes without breaks, then exit
+    #[arg(
+        long = "once",
+        value_name = "MINUTES",
+        value_parser = clap::value_parser!(u64).range(1..=MAX_SESSION_MINUTES),
+        conflicts_with_all = ["sessions", "until", "tasks"]
+    )]
+    pub once: Option<u64>,
+
+    /// Work through the tasks in a file of `name:minutes` lines, one work session each
+    #[arg(long = "tasks", value_name = "PATH")]
+    pub tasks: Option<PathBuf>,
+
+    /// Run this many work sessions back to back, then show a summary
+    #[arg(long = "sessions", value_name = "COUNT")]
+    pub sessions: Option<u32>,
+
+    /// Make the first work session last until this local time
+    #[arg(long = "until", value_name = "HH:MM", value_parser = parse_clock_time)]
+    pub until: Option<NaiveTime>,
+
+    /// Fail instead of assuming tomorrow when the --until time has already passed
+    #[arg(long = "until-today", requires = "until")]
+    pub until_today: bool,
+
+    /// Quit after the break that follows the final target pomodoro
+    #[arg(long = "quit-on-goal")]
+    pub quit_on_goal: bool,
+
+    /// Start the UI immediately instead of waiting after the welcome banner
+    #[arg(long = "no-delay")]
+    pub no_delay: bool,
+
+    /// Don't print the welcome banner
+    #[arg(short = 'q', long = "quiet")]
+    pub quiet: bool,
+
+    /// Only show the session and the timer, for small terminals
+    #[arg(long = "minimal")]
+    pub minimal: bool,
+
+    /// Draw everything in the terminal's default colors
+    #[arg(long = "no-color")]
+    pub no_color: bool,
+
+    /// Start the first work session right away instead of waiting for a key
+    #[arg(long = "start")]
+    pub start: bool,
+
+    /// Show the current session in Discord (requires the `discord` feature)
+    #[arg(long = "discord")]
+    pub discord: bool,
+
+    /// Enable the D key that jumps to 10 seconds before the end, for demos and testing
+    #[arg(long = "debug")]
+    pub debug: bool,
+
+    /// Keep a one-line status in this file for status bars
+    #[arg(long = "status-file", value_name = "PATH")]
+    pub status_file: Option<String>,
+
+    /// Status line template with {session}, {time}, {count}, {state} and {emoji}
+    #[arg(long = "status-format", value_name = "TEMPLATE")]
+    pub status_format: Option<String>,
+}
+
+/// Built-in Pomodoro technique presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Technique {
+    /// 25 minute work, 5 minute short break, 15 minute long break after 4 pomodoros
+    Classic,
+    /// Open-ended work measured by a count-up timer, followed by a break of a fifth
+    /// of the time worked (15 minute long break after 4 pomodoros)
+    Flowtime,
+    /// 52 minute work, 17 minute break
+    #[value(name = "5217")]
+    #[serde(rename = "5217")]
+    FiftyTwoSeventeen,
+    /// 90 minute work, 20 minute short break, 30 minute long break after 2 pomodoros
+    #[value(name = "90min")]
+    #[serde(rename = "90min")]
+    NinetyMinutes,
+}
+
+impl Technique {
+    /// Get the (work, short break, long break) minutes and pomodoros per set
+    pub fn durations(&self) -> (u64, u64, u64, u8) {
+        match self {
+            Technique::Classic => (25, 5, 15, 4),
+            // Work time is open-ended; the work duration only seeds the session
+            Technique::Flowtime => (25, 5, 15, 4),
+            // Every break is the same length, so long breaks match short ones
+            Technique::FiftyTwoSeventeen => (52, 17, 17, 4),
+            Technique::NinetyMinutes => (90, 20, 30, 2),
+        }
+    }
+
+    /// Whether work sessions count up instead of down
+    pub fn counts_up(&self) -> bool {
+        *self == Technique::Flowtime
+    }
+
+    /// Apply the preset durations to the time configuration
+    pub fn apply(&self, time: &mut TimeConfig) {
+        let (work, short_break, long_break, per_set) = self.durations();
+        time.work_minutes = work;
+        time.small_break_minutes = short_break;
+        time.long_break_minutes = long_break;
+        time.tomatoes_per_set = per_set;
+    }
+}
+
+/// How session progress is visualized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressStyle {
+    /// Segmented bar inside the session ASCII art
+    #[default]
+    Ascii,
+    /// Smooth gauge widget with a percentage label
+    Gauge,
+    /// ASCII art with a gauge below it
+    Both,
+    /// ASCII art with a thin full-width bar below it
+    Line,
+}
+
+/// Which time the clock shows for sessions that count down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeDisplay {
+    /// Time left in the session
+    #[default]
+    Remaining,
+    /// Time spent in the session
+    Elapsed,
+    /// Time spent next to the session length, like "12:00 / 25:00"
+    Both,
+}
+
+/// Longest a session can be set to last, in minutes (a week)
+pub const MAX_SESSION_MINUTES: u64 = 7 * 24 * 60;
+
+/// Session a reset goes back to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetTarget {
+    /// The start of the plan, normally a work session
+    #[default]
+    Work,
+    /// The current session, started over at its full length
+    Last,
+}
+
+/// Which way the segmented progress bar moves as a session goes on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressDirection {
+    /// Fills up with the time spent
+    #[default]
+    Elapsed,
+    /// Empties with the time left
+    Remaining,
+}
+
+/// Art drawn above the clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtStyle {
+    /// Per-session picture with a segmented bar inside
+    #[default]
+    Session,
+    /// Tomato whose filling drains as the session elapses
+    Tomato,
+}
+
+/// Sounds available without an `audio_file`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuiltinSound {
+    /// Generated beeps
+    #[default]
+    Beep,
+    /// Bundled bright chime
+    Chime,
+    /// Bundled bell
+    Bell,
+}
+
+/// How the end alarm is picked from the files in `audio_dir`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SoundSelection {
+    /// A random file each time
+    #[default]
+    Random,
+    /// Each file in turn, in name order
+    RoundRobin,
 }
 
 /// General configuration section
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GeneralConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
     /// Whether to hide the clock display
     pub no_clock: bool,
+    /// Whether a hidden clock also hides the progress art, bars and anything else that shows how far the session is
+    pub hide_progress: bool,
     /// Whether to disable sound notifications
     pub no_sound: bool,
     /// Whether to show emoji in UI
     pub emoji: bool,
+    /// Whether to quit after the break that follows the final target pomodoro
+    #[serde(default)]
+    pub quit_on_goal: bool,
+    /// Whether to pause the running work session when the terminal loses focus
+    #[serde(default)]
+    pub pause_on_blur: bool,
+    /// Whether to resume a session paused by `pause_on_blur` when focus returns
+    #[serde(default)]
+    pub resume_on_focus: bool,
+    /// Whether `pause_on_blur` also pauses breaks
+    #[serde(default)]
+    pub pause_breaks_on_blur: bool,
+    /// Whether Space always starts or pauses the timer instead of running the focused menu item
+    #[serde(default)]
+    pub space_toggles_timer: bool,
+    /// How session progress is shown: "ascii", "gauge" or "both"
+    #[serde(default)]
+    pub progress_style: ProgressStyle,
+    /// Which ASCII art to draw: "session" or "tomato"
+    #[serde(default)]
+    pub art_style: ArtStyle,
+    /// Whether the segmented bar fills up ("elapsed") or empties ("remaining")
+    #[serde(default)]
+    pub progress_direction: ProgressDirection,
+    /// Which time the clock shows: "remaining", "elapsed" or "both"
+    #[serde(default)]
+    pub time_display: TimeDisplay,
+    /// Character for the filled part of the progress bar, one cell wide
+    #[serde(default = "default_fill_char")]
+    pub fill_char: String,
+    /// Character for the empty part of the progress bar, one cell wide
+    #[serde(default = "default_empty_char")]
+    pub empty_char: String,
+    /// Whether to only show the session and the timer
+    #[serde(default)]
+    pub minimal: bool,
+    /// Whether a line under the statistics lists the next few sessions
+    #[serde(default)]
+    pub show_upcoming: bool,
+    /// Draw everything in the default colors, telling states apart by text and style
+    #[serde(default)]
+    pub no_color: bool,
+    /// Whether the first work session starts as soon as the app launches
+    #[serde(default)]
+    pub start_on_launch: bool,
+    /// Whether the work session color shifts from `gradient_start` to `gradient_end`
+    #[serde(default)]
+    pub gradient: bool,
+    /// RGB color at the start of a work session
+    #[serde(default = "default_gradient_start")]
+    pub gradient_start: [u8; 3],
+    /// RGB color at the end of a work session
+    #[serde(default = "default_gradient_end")]
+    pub gradient_end: [u8; 3],
+    /// Seconds to wait after the welcome banner before starting the UI
+    #[serde(default = "default_startup_delay_secs")]
+    pub startup_delay_secs: u64,
+    /// Whether skip, reset and quit are blocked while a work session runs
+    #[serde(default)]
+    pub focus_lock: bool,
+    /// Whether debug keys are enabled, only ever set by `--debug`
+    #[serde(skip)]
+    pub debug: bool,
+    /// Whether skip, reset and pausing are blocked during breaks
+    #[serde(default)]
+    pub strict_break: bool,
+    /// Breaks skipped in a row before a reminder to take one is shown (0 disables it)
+    #[serde(default = "default_break_nag_after")]
+    pub break_nag_after: u32,
+    /// Whether an ended session waits for Enter before the next one starts
+    #[serde(default)]
+    pub confirm_transitions: bool,
+    /// Seconds the prompt waits before a `--sessions` run goes on by itself
+    #[serde(default = "default_confirm_timeout_secs")]
+    pub confirm_timeout_secs: u64,
+    /// Whether to ask for a one-line note after each completed work session
+    #[serde(default)]
+    pub prompt_notes: bool,
+    /// Whether to show the current session as Discord Rich Presence
+    #[serde(default)]
+    pub discord: bool,
+    /// Discord application ID used for Rich Presence
+    #[serde(default)]
+    pub discord_client_id: Option<String>,
+    /// Seconds without input before a running work session pauses itself (0 disables)
+    #[serde(default)]
+    pub idle_pause_secs: u64,
+    /// Whether a session paused for inactivity resumes on the next input
+    #[serde(default)]
+    pub idle_auto_resume: bool,
+    /// File rewritten with a one-line status for status bars
+    #[serde(default)]
+    pub status_file: Option<String>,
+    /// Unix socket that accepts STATUS, PAUSE, RESUME and SKIP (requires the `ipc` feature)
+    #[serde(default)]
+    pub ipc_socket: Option<String>,
+    /// Template for the status line
+    #[serde(default = "default_status_format")]
+    pub status_format: String,
+    /// Seconds added or removed per mouse wheel step over the timer
+    #[serde(default = "default_scroll_time_step_secs")]
+    pub scroll_time_step_secs: u64,
+    /// Volume change per mouse wheel step over the volume indicator
+    #[serde(default = "default_scroll_volume_step")]
+    pub scroll_volume_step: f32,
+    /// Whether to capture the mouse; disable it to keep native text selection
+    #[serde(default = "default_mouse")]
+    pub mouse: bool,
+}
+
+fn default_startup_delay_secs() -> u64 {
+    2
+}
+
+fn default_status_format() -> String {
+    crate::status::DEFAULT_STATUS_FORMAT.to_string()
+}
+
+fn default_break_nag_after() -> u32 {
+    3
+}
+
+fn default_confirm_timeout_secs() -> u64 {
+    30
+}
+
+fn default_scroll_time_step_secs() -> u64 {
+    60
+}
+
+fn default_scroll_volume_step() -> f32 {
+    0.05
+}
+
+fn default_mouse() -> bool {
+    true
+}
+
+fn default_fill_char() -> String {
+    "█".to_string()
+}
+
+fn default_empty_char() -> String {
+    "░".to_string()
+}
+
+fn default_gradient_start() -> [u8; 3] {
+    [0, 200, 0]
+}
+
+fn default_gradient_end() -> [u8; 3] {
+    [220, 0, 0]
 }
 
 /// Time configuration section
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TimeConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
     /// Number of pomodoros before a long break
     pub tomatoes_per_set: u8,
     /// Work session duration in minutes
@@ -75,46 +526,670 @@ pub struct TimeConfig {
     pub small_break_minutes: u64,
     /// Long break duration in minutes
     pub long_break_minutes: u64,
+    /// Number of pomodoros to complete in this run (0 means no goal)
+    #[serde(default)]
+    pub target_pomodoros: u32,
+    /// Number of pomodoros to complete each day, celebrated once a day (0 means no goal)
+    #[serde(default)]
+    pub daily_goal: u32,
+    /// Minutes the recorded work time is rounded to for reporting (0 means no rounding)
+    #[serde(default)]
+    pub rounding_minutes: u64,
+    /// Technique preset; its durations replace the ones in this section
+    #[serde(default)]
+    pub technique: Option<Technique>,
+    /// Whether work sessions count up until ended manually
+    #[serde(default)]
+    pub count_up: bool,
+    /// Whether the break after a count-up session scales with the time worked
+    #[serde(default)]
+    pub count_up_break: bool,
+    /// Fraction of the time worked that a count-up session earns as a break
+    #[serde(default = "default_break_ratio")]
+    pub break_ratio: f64,
+    /// Longest break a count-up session can earn, in minutes (0 means no limit)
+    #[serde(default = "default_max_break_minutes")]
+    pub max_break_minutes: u64,
+    /// Whether skipped work sessions still count as completed pomodoros
+    #[serde(default)]
+    pub count_skipped: bool,
+    /// Whether a work session keeps counting past its end until ended manually
+    #[serde(default)]
+    pub overtime: bool,
+    /// Seconds left in a work session when the timer turns red, 0 turns the warning off
+    #[serde(default)]
+    pub warn_threshold_secs: u64,
+    /// Whether the session plan starts over after its last step
+    #[serde(default = "default_loop_plan")]
+    pub loop_plan: bool,
+    /// Whether a run opens with a short planning break before the first work session
+    #[serde(default)]
+    pub break_first: bool,
+    /// Session a reset goes back to: the start of the plan or the current session
+    #[serde(default)]
+    pub reset_to: ResetTarget,
+    /// Minutes each counted work session adds to the next one, for focus training (0 means no ramp)
+    #[serde(default)]
+    pub ramp_step_minutes: u64,
+    /// Longest a ramped work session gets, in minutes (0 means no limit)
+    #[serde(default)]
+    pub ramp_max_minutes: u64,
+    /// Whether breaks grow along with the ramped work sessions, keeping their ratio to work
+    #[serde(default)]
+    pub ramp_breaks: bool,
+    /// Sequence of sessions to run; empty means work and short breaks with a long
+    /// break after every `tomatoes_per_set` pomodoros
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plan: Vec<PlanStep>,
+}
+
+fn default_loop_plan() -> bool {
+    true
+}
+
+fn default_break_ratio() -> f64 {
+    0.2
+}
+
+fn default_max_break_minutes() -> u64 {
+    30
 }
 
 /// Audio configuration section
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AudioConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
     /// Path to custom audio file for notifications
     pub audio_file: Option<String>,
+    /// Name of the output device to play on, see `--list-audio-devices` (the default device if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_device: Option<String>,
+    /// Directory of audio files the end alarm is picked from, overrides `audio_file` for it
+    #[serde(default)]
+    pub audio_dir: Option<String>,
+    /// How the end alarm is picked from `audio_dir`: "random" or "roundrobin"
+    #[serde(default)]
+    pub selection: SoundSelection,
     /// Audio volume (0.0 to 1.0)
     pub volume: f32,
+    /// Sound played when no `audio_file` is set: "beep", "chime" or "bell"
+    #[serde(default)]
+    pub default_sound: BuiltinSound,
+    /// Volume of the work start sound, overrides `volume`
+    #[serde(default)]
+    pub work_volume: Option<f32>,
+    /// Volume of the break start sound, overrides `volume`
+    #[serde(default)]
+    pub break_volume: Option<f32>,
+    /// Volume of the session end alarm, overrides `volume`
+    #[serde(default)]
+    pub end_volume: Option<f32>,
+    /// Seconds the end alarm loops before stopping on its own (0 loops until input)
+    #[serde(default)]
+    pub alarm_seconds: u64,
+    /// Times the work and break start beeps play in a row
+    #[serde(default = "default_beep_count")]
+    pub start_beep_count: u32,
+    /// Times the two-tone end alarm plays in a row before it loops
+    #[serde(default = "default_beep_count")]
+    pub end_beep_count: u32,
+    /// Minutes a snoozed end alarm waits before ringing again
+    #[serde(default = "default_snooze_minutes")]
+    pub snooze_minutes: u64,
+    /// Times the end alarm can be snoozed after a session (0 disables snoozing)
+    #[serde(default = "default_max_snoozes")]
+    pub max_snoozes: u32,
+    /// Whether a volume changed in the app is written back to the config file on exit
+    #[serde(default)]
+    pub save_volume_on_exit: bool,
+    /// Whether a quiet tick plays during work sessions, like a metronome
+    #[serde(default)]
+    pub tick_sound: bool,
+    /// Whether a soft tone plays once when a work session reaches `warn_threshold_secs`
+    #[serde(default)]
+    pub warn_sound: bool,
+    /// Seconds of work between two ticks
+    #[serde(default = "default_tick_interval_secs")]
+    pub tick_interval_secs: u64,
+    /// Volume of the tick (0.0 to 1.0), independent of `volume`
+    #[serde(default = "default_tick_volume")]
+    pub tick_volume: f32,
+    /// Sample rate of the generated tones in Hz
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: u32,
+    /// Number of output channels for the generated tones (1 = mono, 2 = stereo)
+    #[serde(default = "default_channels")]
+    pub channels: u16,
+    /// Whether the work start sound plays slightly left and the break start sound slightly right
+    #[serde(default)]
+    pub spatial_cues: bool,
+}
+
+impl AudioConfig {
+    /// Get the volume for a sound, preferring its own setting over the global volume
+    pub fn volume_for(&self, kind: SoundKind) -> f32 {
+        let specific = match kind {
+            SoundKind::Work => self.work_volume,
+            SoundKind::Break => self.break_volume,
+            SoundKind::End => self.end_volume,
+        };
+        specific.unwrap_or(self.volume)
+    }
+
+    /// Set the global volume, moving any per-sound volumes by the same step
+    pub fn set_volume(&mut self, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        let step = volume - self.volume;
+        for specific in [&mut self.work_volume, &mut self.break_volume, &mut self.end_volume]
+            .into_iter()
+            .flatten()
+        {
+            *specific = (*specific + step).clamp(0.0, 1.0);
+        }
+        self.volume = volume;
+    }
+
+    /// Get how many times a generated sound plays in a row
+    pub fn beep_count(&self, kind: SoundKind) -> u32 {
+        match kind {
+            SoundKind::Work | SoundKind::Break => self.start_beep_count,
+            SoundKind::End => self.end_beep_count,
+        }
+    }
+}
+
+fn default_beep_count() -> u32 {
+    1
+}
+
+fn default_snooze_minutes() -> u64 {
+    2
+}
+
+fn default_tick_interval_secs() -> u64 {
+    60
+}
+
+fn default_tick_volume() -> f32 {
+    0.1
+}
+
+fn default_max_snoozes() -> u32 {
+    3
+}
+
+fn default_sample_rate() -> u32 {
+    44100
+}
+
+fn default_channels() -> u16 {
+    1
+}
+
+/// Messages shown when a session ends
+/// `{count}` is replaced with the number of completed pomodoros.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessagesConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
+    /// Shown when a work session ends
+    #[serde(default = "default_completion_message")]
+    pub work_complete: String,
+    /// Shown when a short break ends
+    #[serde(default = "default_completion_message")]
+    pub break_complete: String,
+    /// Shown when a long break ends
+    #[serde(default = "default_completion_message")]
+    pub long_break_complete: String,
+}
+
+fn default_completion_message() -> String {
+    "🎉 Session completed!".to_string()
+}
+
+impl Default for MessagesConfig {
+    fn default() -> Self {
+        Self {
+            unknown: UnknownKeys::default(),
+            work_complete: default_completion_message(),
+            break_complete: default_completion_message(),
+            long_break_complete: default_completion_message(),
+        }
+    }
+}
+
+impl MessagesConfig {
+    /// Get the message for the session that just ended
+    /// Blank messages fall back to the default so the banner is never empty.
+    pub fn completion_message(&self, ended: SessionType, count: u32) -> String {
+        let template = match ended {
+            SessionType::Work => &self.work_complete,
+            SessionType::ShortBreak => &self.break_complete,
+            SessionType::LongBreak => &self.long_break_complete,
+        };
+        let template = if template.trim().is_empty() {
+            default_completion_message()
+        } else {
+            template.clone()
+        };
+        template.replace("{count}", &count.to_string())
+    }
+}
+
+/// Session names and emoji shown in the UI, status file and presence
+/// Blank entries fall back to the built-in English labels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LabelsConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
+    pub work: String,
+    pub short_break: String,
+    pub long_break: String,
+    pub work_emoji: String,
+    pub short_break_emoji: String,
+    pub long_break_emoji: String,
+}
+
+impl Default for LabelsConfig {
+    fn default() -> Self {
+        Self {
+            unknown: UnknownKeys::default(),
+            work: SessionType::Work.display_text().to_string(),
+            short_break: SessionType::ShortBreak.display_text().to_string(),
+            long_break: SessionType::LongBreak.display_text().to_string(),
+            work_emoji: SessionType::Work.emoji().to_string(),
+            short_break_emoji: SessionType::ShortBreak.emoji().to_string(),
+            long_break_emoji: SessionType::LongBreak.emoji().to_string(),
+        }
+    }
+}
+
+impl LabelsConfig {
+    /// Get the name shown for a session type
+    pub fn label(&self, session: SessionType) -> &str {
+        let label = match session {
+            SessionType::Work => &self.work,
+            SessionType::ShortBreak => &self.short_break,
+            SessionType::LongBreak => &self.long_break,
+        };
+        non_blank_or(label, session.display_text())
+    }
+
+    /// Get the emoji shown for a session type
+    pub fn emoji(&self, session: SessionType) -> &str {
+        let emoji = match session {
+            SessionType::Work => &self.work_emoji,
+            SessionType::ShortBreak => &self.short_break_emoji,
+            SessionType::LongBreak => &self.long_break_emoji,
+        };
+        non_blank_or(emoji, session.emoji())
+    }
+}
+
+/// URLs that session events are POSTed to (requires the `webhooks` feature)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhooksConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
+    /// Called when a work session starts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_work_start: Option<String>,
+    /// Called when a short or long break starts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_break_start: Option<String>,
+    /// Called when any session ends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<String>,
+}
+
+impl WebhooksConfig {
+    /// Check if no webhook is configured
+    pub fn is_empty(&self) -> bool {
+        self.on_work_start.is_none() && self.on_break_start.is_none() && self.on_complete.is_none()
+    }
+}
+
+/// Shell commands run on session events
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
+    /// Run when a work session starts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_work_start: Option<String>,
+    /// Run when a short or long break starts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_break_start: Option<String>,
+    /// Run when any session ends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<String>,
+}
+
+impl HooksConfig {
+    /// Check if no hook is configured
+    pub fn is_empty(&self) -> bool {
+        self.on_work_start.is_none() && self.on_break_start.is_none() && self.on_complete.is_none()
+    }
+}
+
+/// How insistently the desktop shows a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    /// Usually stays up until dismissed
+    Critical,
+}
+
+impl Urgency {
+    /// Get the name `notify-send --urgency` takes
+    pub fn name(self) -> &'static str {
+        match self {
+            Urgency::Low => "low",
+            Urgency::Normal => "normal",
+            Urgency::Critical => "critical",
+        }
+    }
+}
+
+/// Urgency of the desktop notification for each event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationUrgency {
+    pub work_start: Urgency,
+    pub break_start: Urgency,
+    pub complete: Urgency,
+    pub daily_goal: Urgency,
+}
+
+impl Default for NotificationUrgency {
+    fn default() -> Self {
+        Self {
+            work_start: Urgency::Normal,
+            break_start: Urgency::Low,
+            complete: Urgency::Critical,
+            daily_goal: Urgency::Normal,
+        }
+    }
+}
+
+/// Desktop notifications on session events
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Keys rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
+    /// Show a notification when a session starts or ends, not only for the daily goal
+    pub sessions: bool,
+    /// Milliseconds a notification stays up (0 leaves it to the desktop)
+    pub timeout_ms: u32,
+    /// Urgency of each event's notification
+    pub notification_urgency: NotificationUrgency,
+}
+
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        Config::default().general
+    }
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Config::default().time
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Config::default().audio
+    }
+}
+
+/// Names of the keys in a config section or file that weren't recognized
+/// They are left out when the config is saved again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnknownKeys(Vec<String>);
+
+impl UnknownKeys {
+    /// Get the unrecognized keys in the order they appeared
+    pub fn keys(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for UnknownKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct KeysVisitor;
+
+        impl<'de> Visitor<'de> for KeysVisitor {
+            type Value = UnknownKeys;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a table")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<UnknownKeys, A::Error> {
+                let mut keys = Vec::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    map.next_value::<IgnoredAny>()?;
+                    keys.push(key);
+                }
+                Ok(UnknownKeys(keys))
+            }
+        }
+
+        deserializer.deserialize_map(KeysVisitor)
+    }
+}
+
+/// Find the known name closest to a misspelled `key`, if any is close enough
+fn closest_key<'a>(key: &str, known: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    known
+        .into_iter()
+        .map(|candidate| (edit_distance(&key.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Count the single-character edits that turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Use `value` unless it is blank
+fn non_blank_or<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    if value.trim().is_empty() {
+        fallback
+    } else {
+        value
+    }
+}
+
+/// Where the loaded configuration came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// No config file was read, built-in defaults are used
+    DefaultsOnly,
+    /// The file passed with --path
+    FileAtPath(PathBuf),
+    /// The default config file in the home directory
+    DefaultFile(PathBuf),
+    /// TOML read from standard input with --config-stdin
+    Stdin,
+}
+
+impl ConfigSource {
+    /// Get the config file that was read, if any
+    pub fn path(&self) -> Option<&PathBuf> {
+        match self {
+            ConfigSource::DefaultsOnly | ConfigSource::Stdin => None,
+            ConfigSource::FileAtPath(path) | ConfigSource::DefaultFile(path) => Some(path),
+        }
+    }
+
+    /// Describe the source for the welcome banner
+    pub fn describe(&self) -> String {
+        match self {
+            ConfigSource::DefaultsOnly => "No config file loaded, using defaults".to_string(),
+            ConfigSource::Stdin => "Loaded config from standard input".to_string(),
+            ConfigSource::FileAtPath(path) | ConfigSource::DefaultFile(path) => {
+                format!("Loaded config from {}", path.display())
+            }
+        }
+    }
 }
 
 /// Configuration structure for the Pomodoro timer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    #[serde(rename = "General")]
+    #[serde(rename = "General", default)]
     pub general: GeneralConfig,
-    #[serde(rename = "Time")]
+    #[serde(rename = "Time", default)]
     pub time: TimeConfig,
-    #[serde(rename = "Audio")]
+    #[serde(rename = "Audio", default)]
     pub audio: AudioConfig,
+    #[serde(rename = "Messages", default)]
+    pub messages: MessagesConfig,
+    #[serde(rename = "Labels", default)]
+    pub labels: LabelsConfig,
+    #[serde(rename = "Webhooks", default)]
+    pub webhooks: WebhooksConfig,
+    #[serde(rename = "Hooks", default)]
+    pub hooks: HooksConfig,
+    #[serde(rename = "Notifications", default)]
+    pub notifications: NotificationsConfig,
+    /// Sections rustdoro doesn't know, only kept to warn about them
+    #[serde(flatten, skip_serializing)]
+    pub unknown: UnknownKeys,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             general: GeneralConfig {
+                unknown: UnknownKeys::default(),
                 no_clock: false,
+                hide_progress: false,
                 no_sound: false,
                 emoji: true,
+                quit_on_goal: false,
+                pause_on_blur: false,
+                resume_on_focus: false,
+                pause_breaks_on_blur: false,
+                space_toggles_timer: false,
+                progress_style: ProgressStyle::Ascii,
+                art_style: ArtStyle::Session,
+                time_display: TimeDisplay::Remaining,
+                progress_direction: ProgressDirection::Elapsed,
+                fill_char: default_fill_char(),
+                empty_char: default_empty_char(),
+                minimal: false,
+                show_upcoming: false,
+                no_color: false,
+                start_on_launch: false,
+                gradient: false,
+                gradient_start: default_gradient_start(),
+                gradient_end: default_gradient_end(),
+                startup_delay_secs: default_startup_delay_secs(),
+                focus_lock: false,
+                debug: false,
+                strict_break: false,
+                break_nag_after: default_break_nag_after(),
+                confirm_transitions: false,
+                confirm_timeout_secs: default_confirm_timeout_secs(),
+                prompt_notes: false,
+                discord: false,
+                discord_client_id: None,
+                idle_pause_secs: 0,
+                idle_auto_resume: false,
+                status_file: None,
+                ipc_socket: None,
+                status_format: default_status_format(),
+                scroll_time_step_secs: default_scroll_time_step_secs(),
+                scroll_volume_step: default_scroll_volume_step(),
+                mouse: default_mouse(),
             },
             time: TimeConfig {
+                unknown: UnknownKeys::default(),
                 tomatoes_per_set: 4,
                 work_minutes: 25,
                 small_break_minutes: 5,
-                long_break_minutes: 10,
+                long_break_minutes: 15,
+                target_pomodoros: 0,
+                daily_goal: 0,
+                rounding_minutes: 0,
+                technique: None,
+                count_up: false,
+                count_up_break: false,
+                break_ratio: default_break_ratio(),
+                max_break_minutes: default_max_break_minutes(),
+                count_skipped: false,
+                overtime: false,
+                warn_threshold_secs: 0,
+                loop_plan: default_loop_plan(),
+                break_first: false,
+                reset_to: ResetTarget::Work,
+                ramp_step_minutes: 0,
+                ramp_max_minutes: 0,
+                ramp_breaks: false,
+                plan: Vec::new(),
             },
             audio: AudioConfig {
+                unknown: UnknownKeys::default(),
                 audio_file: None,
+                audio_device: None,
+                audio_dir: None,
+                selection: SoundSelection::Random,
+                default_sound: BuiltinSound::Beep,
                 volume: 0.7,
+                work_volume: None,
+                break_volume: None,
+                end_volume: None,
+                alarm_seconds: 0,
+                save_volume_on_exit: false,
+                tick_sound: false,
+                warn_sound: false,
+                tick_interval_secs: default_tick_interval_secs(),
+                tick_volume: default_tick_volume(),
+                start_beep_count: default_beep_count(),
+                end_beep_count: default_beep_count(),
+                snooze_minutes: default_snooze_minutes(),
+                max_snoozes: default_max_snoozes(),
+                sample_rate: default_sample_rate(),
+                channels: default_channels(),
+                spatial_cues: false,
             },
+            messages: MessagesConfig::default(),
+            labels: LabelsConfig::default(),
+            webhooks: WebhooksConfig::default(),
+            hooks: HooksConfig::default(),
+            notifications: NotificationsConfig::default(),
+            unknown: UnknownKeys::default(),
         }
     }
 }
@@ -136,6 +1211,24 @@ impl Config {
     pub fn long_break_after_pomodoros(&self) -> u8 {
         self.time.tomatoes_per_set
     }
+
+    /// Get the length of a plan step in minutes
+    pub fn step_minutes(&self, step: &PlanStep) -> u64 {
+        step.minutes.unwrap_or(match step.session {
+            SessionType::Work => self.work_duration_minutes(),
+            SessionType::ShortBreak => self.short_break_duration_minutes(),
+            SessionType::LongBreak => self.long_break_duration_minutes(),
+        })
+    }
+
+    /// Get the session plan, falling back to the classic cycle when none is configured
+    pub fn plan(&self) -> Vec<PlanStep> {
+        if self.time.plan.is_empty() {
+            default_plan(self.long_break_after_pomodoros())
+        } else {
+            self.time.plan.clone()
+        }
+    }
     
     pub fn enable_sound(&self) -> bool {
         !self.general.no_sound
@@ -145,6 +1238,36 @@ impl Config {
         self.general.no_clock
     }
 
+    /// Get the filled and empty progress bar characters
+    pub fn progress_chars(&self) -> (char, char) {
+        (
+            self.general.fill_char.chars().next().unwrap_or('█'),
+            self.general.empty_char.chars().next().unwrap_or('░'),
+        )
+    }
+
+    pub fn target_pomodoros(&self) -> u32 {
+        self.time.target_pomodoros
+    }
+
+    /// Whether work sessions count up instead of down
+    pub fn count_up(&self) -> bool {
+        self.time.count_up || self.flowtime()
+    }
+
+    /// Whether breaks after count-up sessions scale with the time worked
+    pub fn count_up_break(&self) -> bool {
+        self.time.count_up_break || self.flowtime()
+    }
+
+    fn flowtime(&self) -> bool {
+        self.time.technique.is_some_and(|t| t.counts_up())
+    }
+
+    pub fn startup_delay_secs(&self) -> u64 {
+        self.general.startup_delay_secs
+    }
+
     /// Save configuration to file with comments
     pub fn save_to_file(&self, path: &PathBuf) -> Result<()> {
         let toml_string = toml::to_string_pretty(self)?;
@@ -163,13 +1286,70 @@ impl Config {
         Ok(())
     }
 
+    /// Write a new volume to a config file, keeping its other settings
+    /// A missing file is created with the default settings.
+    pub fn save_volume(path: &PathBuf, volume: f32) -> Result<()> {
+        let mut config = if path.exists() {
+            Self::load_from_file(path)?
+        } else {
+            Self::default()
+        };
+        config.audio.volume = volume;
+        config.save_to_file(path)
+    }
+
     /// Load configuration from file
     pub fn load_from_file(path: &PathBuf) -> Result<Self> {
         let contents = std::fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&contents)?;
+        Self::from_toml_str(&contents)
+    }
+
+    /// Parse configuration from TOML text
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        let config: Config = toml::from_str(contents)?;
         Ok(config)
     }
 
+    /// Describe every unrecognized section and key, suggesting the likely intended name
+    pub fn unknown_key_warnings(&self) -> Vec<String> {
+        // Section and key names as the default config writes them
+        let known = toml::Value::try_from(Config::default()).ok();
+        let known = known.as_ref().and_then(toml::Value::as_table);
+        let mut warnings = Vec::new();
+
+        for section in self.unknown.keys() {
+            let suggestion = known.and_then(|known| closest_key(section, known.keys().map(String::as_str)));
+            warnings.push(match suggestion {
+                Some(name) => format!("Unknown section [{}] is ignored, did you mean [{}]?", section, name),
+                None => format!("Unknown section [{}] is ignored", section),
+            });
+        }
+
+        let sections = [
+            ("General", &self.general.unknown),
+            ("Time", &self.time.unknown),
+            ("Audio", &self.audio.unknown),
+            ("Messages", &self.messages.unknown),
+            ("Labels", &self.labels.unknown),
+            ("Webhooks", &self.webhooks.unknown),
+            ("Hooks", &self.hooks.unknown),
+            ("Notifications", &self.notifications.unknown),
+        ];
+        for (section, unknown) in sections {
+            let known_keys = known
+                .and_then(|known| known.get(section))
+                .and_then(toml::Value::as_table);
+            for key in unknown.keys() {
+                let suggestion = known_keys.and_then(|keys| closest_key(key, keys.keys().map(String::as_str)));
+                warnings.push(match suggestion {
+                    Some(name) => format!("Unknown key `{}` in [{}] is ignored, did you mean `{}`?", key, section, name),
+                    None => format!("Unknown key `{}` in [{}] is ignored", key, section),
+                });
+            }
+        }
+        warnings
+    }
+
     /// Get the default config file path
     pub fn default_config_path() -> Result<PathBuf> {
         let mut path: PathBuf = dirs::home_dir()
@@ -181,42 +1361,95 @@ impl Config {
     /// Load configuration with fallback
     /// If default config file exists, use default file.
     /// If not, use default config.
-    pub fn load_with_fallback() -> Self {
+    pub fn load_with_fallback() -> (Self, ConfigSource) {
         if let Ok(config_path) = Self::default_config_path() {
             if config_path.exists() {
                 if let Ok(config) = Self::load_from_file(&config_path) {
-                    return config;
+                    return (config, ConfigSource::DefaultFile(config_path));
                 }
             }
         }
-        Self::default()
+        (Self::default(), ConfigSource::DefaultsOnly)
     }
 
     /// Load configuration from CLI args with config file support
     /// Configuration loading priority:
     /// 1. Command-line arguments
-    /// 2. Specified configuration file via --path flag
-    /// 3. Default configuration file
-    pub fn load_from_cli_args_with_config(args: CliArgs) -> Self {
-        // First, try to load from config file (either specified or default)
-        let mut config = if let Some(config_path) = &args.config_path {
+    /// 2. `RUSTDORO_*` environment variables
+    /// 3. Specified configuration file via --path flag
+    /// 4. Default configuration file
+    ///
+    /// Also returns which config file, if any, was actually read.
+    pub fn load_from_cli_args_with_config(args: CliArgs) -> (Self, ConfigSource) {
+        let (mut config, source) = Self::resolve_from_cli_args(args);
+
+        for warning in config.validate() {
+            tracing::warn!("{}", warning);
+        }
+
+        (config, source)
+    }
+
+    /// Load configuration like `load_from_cli_args_with_config`, but without validating it
+    pub fn resolve_from_cli_args(args: CliArgs) -> (Self, ConfigSource) {
+        let stdin = args.config_stdin.then(|| {
+            let mut contents = String::new();
+            std::io::stdin().read_to_string(&mut contents).map(|_| contents)
+        });
+        Self::resolve_with(args, stdin, |name| std::env::var(name).ok())
+    }
+
+    /// Load configuration from `stdin` if --config-stdin was given, looking up
+    /// environment overrides with `env`
+    fn resolve_with(
+        args: CliArgs,
+        stdin: Option<std::io::Result<String>>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> (Self, ConfigSource) {
+        // First, try to load from standard input or a config file (either specified or default)
+        let (mut config, source) = if let Some(stdin) = stdin {
+            match stdin.map_err(anyhow::Error::from).and_then(|contents| Self::from_toml_str(&contents)) {
+                Ok(config) => (config, ConfigSource::Stdin),
+                Err(e) => {
+                    tracing::warn!("Failed to load config from standard input: {}", e);
+                    tracing::warn!("Using default configuration...");
+                    (Self::default(), ConfigSource::DefaultsOnly)
+                }
+            }
+        } else if let Some(config_path) = &args.config_path {
             // Use specified config file
             if config_path.exists() {
-                Self::load_from_file(config_path).unwrap_or_else(|e| {
-                    eprintln!("Warning: Failed to load config file {:?}: {}", config_path, e);
-                    eprintln!("Using default configuration...");
-                    Self::default()
-                })
+                match Self::load_from_file(config_path) {
+                    Ok(config) => (config, ConfigSource::FileAtPath(config_path.clone())),
+                    Err(e) => {
+                        tracing::warn!("Failed to load config file {:?}: {}", config_path, e);
+                        tracing::warn!("Using default configuration...");
+                        (Self::default(), ConfigSource::DefaultsOnly)
+                    }
+                }
             } else {
-                eprintln!("Warning: Config file {:?} does not exist", config_path);
-                eprintln!("Using default configuration...");
-                Self::default()
+                tracing::warn!("Config file {:?} does not exist", config_path);
+                tracing::warn!("Using default configuration...");
+                (Self::default(), ConfigSource::DefaultsOnly)
             }
         } else {
             // Try default config file location
             Self::load_with_fallback()
         };
 
+        // Technique presets replace file durations but not explicit CLI flags
+        if let Some(technique) = args.technique {
+            config.time.technique = Some(technique);
+        }
+        if let Some(technique) = config.time.technique {
+            technique.apply(&mut config.time);
+        }
+
+        // Environment variables sit between the config file and the CLI
+        for warning in config.apply_env_overrides(env) {
+            tracing::warn!("{}", warning);
+        }
+
         // Override config with command line arguments
         // Only override if the CLI arg was explicitly provided (not default)
         if args.work_duration != 25 {
@@ -225,12 +1458,15 @@ impl Config {
         if args.short_break != 5 {
             config.time.small_break_minutes = args.short_break;
         }
-        if args.long_break != 10 {
+        if args.long_break != 15 {
             config.time.long_break_minutes = args.long_break;
         }
         if args.long_break_after != 4 {
             config.time.tomatoes_per_set = args.long_break_after;
         }
+        if args.count_up {
+            config.time.count_up = true;
+        }
         if args.no_sound {
             config.general.no_sound = true;
         }
@@ -238,18 +1474,234 @@ impl Config {
             config.general.no_clock = true;
         }
         if let Some(volume) = args.volume {
-            config.audio.volume = volume.clamp(0.0, 1.0);
+            config.audio.volume = volume;
         }
         if let Some(audio_file) = args.audio_file {
             config.audio.audio_file = Some(audio_file);
         }
+        if let Some(alarm_seconds) = args.alarm_seconds {
+            config.audio.alarm_seconds = alarm_seconds;
+        }
+        if let Some(target) = args.target {
+            config.time.target_pomodoros = target;
+        }
+        if args.quit_on_goal {
+            config.general.quit_on_goal = true;
+        }
+        if args.no_delay {
+            config.general.startup_delay_secs = 0;
+        }
+        if args.discord {
+            config.general.discord = true;
+        }
+        if args.focus_lock {
+            config.general.focus_lock = true;
+        }
+        if args.debug {
+            config.general.debug = true;
+        }
+        if args.strict_break {
+            config.general.strict_break = true;
+        }
+        if args.minimal {
+            config.general.minimal = true;
+        }
+        if args.no_color {
+            config.general.no_color = true;
+        }
+        if args.start {
+            config.general.start_on_launch = true;
+        }
+        if let Some(status_file) = args.status_file {
+            config.general.status_file = Some(status_file);
+        }
+        if let Some(status_format) = args.status_format {
+            config.general.status_format = status_format;
+        }
         if args.focus {
             // Focus mode overrides sound and clock settings
             config.general.no_sound = true;
             config.general.no_clock = true;
         }
 
-        config
+        (config, source)
+    }
+
+    /// Apply `RUSTDORO_*` environment overrides, looking values up with `env`
+    /// Values that can't be parsed are ignored; a warning is returned for each.
+    pub fn apply_env_overrides(&mut self, env: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if let Some(minutes) = env_value(&env, "RUSTDORO_WORK_MINUTES", &mut warnings) {
+            self.time.work_minutes = minutes;
+        }
+        if let Some(minutes) = env_value(&env, "RUSTDORO_SHORT_BREAK", &mut warnings) {
+            self.time.small_break_minutes = minutes;
+        }
+        if let Some(minutes) = env_value(&env, "RUSTDORO_LONG_BREAK", &mut warnings) {
+            self.time.long_break_minutes = minutes;
+        }
+        if let Some(count) = env_value(&env, "RUSTDORO_LONG_BREAK_AFTER", &mut warnings) {
+            self.time.tomatoes_per_set = count;
+        }
+        if let Some(target) = env_value(&env, "RUSTDORO_TARGET", &mut warnings) {
+            self.time.target_pomodoros = target;
+        }
+        if let Some(seconds) = env_value(&env, "RUSTDORO_ALARM_SECONDS", &mut warnings) {
+            self.audio.alarm_seconds = seconds;
+        }
+        if let Some(volume) = env_value(&env, "RUSTDORO_VOLUME", &mut warnings) {
+            self.audio.volume = volume;
+        }
+        if let Some(audio_file) = env_value(&env, "RUSTDORO_AUDIO_FILE", &mut warnings) {
+            self.audio.audio_file = Some(audio_file);
+        }
+        if let Some(status_file) = env_value(&env, "RUSTDORO_STATUS_FILE", &mut warnings) {
+            self.general.status_file = Some(status_file);
+        }
+
+        for (name, flag) in [
+            ("RUSTDORO_NO_SOUND", &mut self.general.no_sound),
+            ("RUSTDORO_NO_CLOCK", &mut self.general.no_clock),
+            ("RUSTDORO_COUNT_UP", &mut self.time.count_up),
+            ("RUSTDORO_FOCUS_LOCK", &mut self.general.focus_lock),
+            ("RUSTDORO_STRICT_BREAK", &mut self.general.strict_break),
+        ] {
+            let Some(value) = env(name) else {
+                continue;
+            };
+            match parse_env_bool(&value) {
+                Some(enabled) => *flag = enabled,
+                None => warnings.push(format!(
+                    "Ignoring {}={:?}, expected 1/0, true/false, yes/no or on/off",
+                    name, value
+                )),
+            }
+        }
+
+        // https://no-color.org: any non-empty value turns colors off
+        if env("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            self.general.no_color = true;
+        }
+
+        warnings
+    }
+
+    /// Fix out-of-range settings in place
+    /// Returns a warning for every value that had to be changed.
+    pub fn validate(&mut self) -> Vec<String> {
+        let mut warnings = self.unknown_key_warnings();
+
+        let audio = &mut self.audio;
+        clamp_volume("volume", &mut audio.volume, &mut warnings);
+        clamp_volume("tick_volume", &mut audio.tick_volume, &mut warnings);
+        for (name, volume) in [
+            ("work_volume", &mut audio.work_volume),
+            ("break_volume", &mut audio.break_volume),
+            ("end_volume", &mut audio.end_volume),
+        ] {
+            if let Some(volume) = volume {
+                clamp_volume(name, volume, &mut warnings);
+            }
+        }
+
+        for (name, count) in [
+            ("start_beep_count", &mut self.audio.start_beep_count),
+            ("end_beep_count", &mut self.audio.end_beep_count),
+        ] {
+            if *count == 0 {
+                warnings.push(format!("{} must be at least 1, using 1", name));
+                *count = 1;
+            }
+        }
+
+        if self.audio.snooze_minutes == 0 {
+            warnings.push(format!(
+                "snooze_minutes must be at least 1, using {}",
+                default_snooze_minutes()
+            ));
+            self.audio.snooze_minutes = default_snooze_minutes();
+        }
+
+        for (name, value, default) in [
+            ("fill_char", &mut self.general.fill_char, default_fill_char()),
+            ("empty_char", &mut self.general.empty_char, default_empty_char()),
+        ] {
+            // Anything wider than one cell would push the art out of shape
+            if value.chars().count() != 1 || value.width() != 1 {
+                warnings.push(format!(
+                    "{} must be a single character one cell wide, using \"{}\"",
+                    name, default
+                ));
+                *value = default;
+            }
+        }
+
+        let time = &mut self.time;
+        let plan_minutes = time.plan.iter_mut().filter_map(|step| step.minutes.as_mut());
+        let session_minutes = [
+            ("work_minutes", &mut time.work_minutes),
+            ("small_break_minutes", &mut time.small_break_minutes),
+            ("long_break_minutes", &mut time.long_break_minutes),
+            ("max_break_minutes", &mut time.max_break_minutes),
+            ("ramp_step_minutes", &mut time.ramp_step_minutes),
+            ("ramp_max_minutes", &mut time.ramp_max_minutes),
+            ("rounding_minutes", &mut time.rounding_minutes),
+            ("snooze_minutes", &mut self.audio.snooze_minutes),
+        ];
+        let plan_minutes = plan_minutes.map(|minutes| ("plan step minutes", minutes));
+        for (name, minutes) in session_minutes.into_iter().chain(plan_minutes) {
+            if *minutes > MAX_SESSION_MINUTES {
+                warnings.push(format!(
+                    "{} can be at most {} (a week), using {}",
+                    name, MAX_SESSION_MINUTES, MAX_SESSION_MINUTES
+                ));
+                *minutes = MAX_SESSION_MINUTES;
+            }
+        }
+
+        let defaults = Config::default().time;
+        let session_minutes = [
+            ("work_minutes", &mut self.time.work_minutes, defaults.work_minutes),
+            ("small_break_minutes", &mut self.time.small_break_minutes, defaults.small_break_minutes),
+            ("long_break_minutes", &mut self.time.long_break_minutes, defaults.long_break_minutes),
+        ];
+        for (name, minutes, default) in session_minutes {
+            if *minutes == 0 {
+                warnings.push(format!("{} must be at least 1, using {}", name, default));
+                *minutes = default;
+            }
+        }
+
+        if self.audio.tick_interval_secs == 0 {
+            warnings.push(format!(
+                "tick_interval_secs must be at least 1, using {}",
+                default_tick_interval_secs()
+            ));
+            self.audio.tick_interval_secs = default_tick_interval_secs();
+        }
+
+        let ratio = self.time.break_ratio;
+        if !(ratio.is_finite() && ratio >= 0.0) {
+            warnings.push(format!(
+                "break_ratio must be zero or more, using {}",
+                default_break_ratio()
+            ));
+            self.time.break_ratio = default_break_ratio();
+        }
+
+        for (index, step) in self.time.plan.iter_mut().enumerate() {
+            if step.minutes == Some(0) {
+                warnings.push(format!(
+                    "Plan step {} has a length of 0 minutes, using the {} duration",
+                    index + 1,
+                    step.session.display_text()
+                ));
+                step.minutes = None;
+            }
+        }
+
+        warnings
     }
 
     /// Create a sample configuration file at the default location
@@ -275,10 +1727,548 @@ impl Config {
 
 }
 
+/// Read and parse an environment variable, warning about values that don't parse
+fn env_value<T: FromStr>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    warnings: &mut Vec<String>,
+) -> Option<T> {
+    let value = env(name)?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            warnings.push(format!("Ignoring {}={:?}, it is not a valid value", name, value));
+            None
+        }
+    }
+}
+
+/// Parse a boolean environment value, accepting 1/0, true/false, yes/no and on/off
+fn parse_env_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a local clock time given as HH:MM
+pub fn parse_clock_time(value: &str) -> Result<NaiveTime, chrono::ParseError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+}
+
+/// Get the time from `now` until the clock next shows `until`
+/// A time that has already passed today means tomorrow, unless `today_only` is set.
+pub fn duration_until(until: NaiveTime, now: NaiveDateTime, today_only: bool) -> Result<Duration> {
+    let mut target = now.date().and_time(until);
+    if target <= now {
+        if today_only {
+            return Err(anyhow::anyhow!("{} has already passed today", until.format("%H:%M")));
+        }
+        target += chrono::Duration::days(1);
+    }
+    Ok((target - now).to_std()?)
+}
+
+/// Clamp a volume to 0.0..=1.0, recording a warning if it was out of range
+fn clamp_volume(name: &str, volume: &mut f32, warnings: &mut Vec<String>) {
+    let clamped = if volume.is_nan() { 1.0 } else { volume.clamp(0.0, 1.0) };
+    if clamped != *volume {
+        warnings.push(format!("{} must be between 0.0 and 1.0, using {}", name, clamped));
+        *volume = clamped;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_progress_style_parsing() {
+        let config: Config = toml::from_str(
+            "[General]\nno_clock = false\nno_sound = false\nemoji = true\nprogress_style = \"both\"\n\
+             [Time]\ntomatoes_per_set = 4\nwork_minutes = 25\nsmall_break_minutes = 5\nlong_break_minutes = 15\n\
+             [Audio]\nvolume = 0.7\n",
+        )
+        .unwrap();
+        assert_eq!(config.general.progress_style, ProgressStyle::Both);
+
+        // Older config files without the key keep the ASCII art
+        let config: Config = toml::from_str(
+            "[General]\nno_clock = false\nno_sound = false\nemoji = true\n\
+             [Time]\ntomatoes_per_set = 4\nwork_minutes = 25\nsmall_break_minutes = 5\nlong_break_minutes = 15\n\
+             [Audio]\nvolume = 0.7\n",
+        )
+        .unwrap();
+        assert_eq!(config.general.progress_style, ProgressStyle::Ascii);
+        assert!(config.general.mouse);
+    }
+
+    fn parse_args(args: &[&str]) -> CliArgs {
+        let mut argv = vec!["rustdoro", "--path", "/nonexistent/rustdoro-test.ini"];
+        argv.extend_from_slice(args);
+        CliArgs::parse_from(argv)
+    }
+
+    #[test]
+    fn test_technique_durations() {
+        let cases = [
+            ("classic", (25, 5, 15, 4), false),
+            ("flowtime", (25, 5, 15, 4), true),
+            ("5217", (52, 17, 17, 4), false),
+            ("90min", (90, 20, 30, 2), false),
+        ];
+
+        for (name, (work, short_break, long_break, per_set), count_up) in cases {
+            let config = Config::load_from_cli_args_with_config(parse_args(&["--technique", name])).0;
+            assert_eq!(config.work_duration_minutes(), work, "{}", name);
+            assert_eq!(config.short_break_duration_minutes(), short_break, "{}", name);
+            assert_eq!(config.long_break_duration_minutes(), long_break, "{}", name);
+            assert_eq!(config.long_break_after_pomodoros(), per_set, "{}", name);
+            assert_eq!(config.count_up(), count_up, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_explicit_durations_override_technique() {
+        let config = Config::load_from_cli_args_with_config(parse_args(&[
+            "--technique",
+            "5217",
+            "--work-duration",
+            "45",
+        ]))
+        .0;
+        assert_eq!(config.work_duration_minutes(), 45);
+        assert_eq!(config.short_break_duration_minutes(), 17);
+    }
+
+    #[test]
+    fn test_parse_env_bool() {
+        for value in ["1", "true", "Yes", " ON "] {
+            assert_eq!(parse_env_bool(value), Some(true), "{}", value);
+        }
+        for value in ["0", "FALSE", "no", "off"] {
+            assert_eq!(parse_env_bool(value), Some(false), "{}", value);
+        }
+        assert_eq!(parse_env_bool("maybe"), None);
+        assert_eq!(parse_env_bool(""), None);
+    }
+
+    #[test]
+    fn test_env_overrides_sit_between_file_and_cli() {
+        let path = std::env::temp_dir().join(format!("rustdoro-env-{}.ini", std::process::id()));
+        let mut file_config = Config::default();
+        file_config.time.work_minutes = 40;
+        file_config.time.small_break_minutes = 7;
+        file_config.time.long_break_minutes = 12;
+        file_config.save_to_file(&path).unwrap();
+
+        let env = |name: &str| match name {
+            "RUSTDORO_WORK_MINUTES" => Some("50".to_string()),
+            "RUSTDORO_SHORT_BREAK" => Some("soon".to_string()),
+            "RUSTDORO_LONG_BREAK" => Some("30".to_string()),
+            "RUSTDORO_NO_SOUND" => Some("yes".to_string()),
+            "RUSTDORO_FOCUS_LOCK" => Some("sometimes".to_string()),
+            _ => None,
+        };
+        let args = CliArgs::parse_from(["rustdoro", "--path", path.to_str().unwrap(), "-l", "20"]);
+        let (config, _) = Config::resolve_with(args, None, env);
+        let args = CliArgs::parse_from(["rustdoro", "--path", path.to_str().unwrap(), "-w", "30"]);
+        let (cli_config, _) = Config::resolve_with(args, None, env);
+        std::fs::remove_file(&path).unwrap();
+
+        // Environment beats the file, invalid values leave the file value alone
+        assert_eq!(config.time.work_minutes, 50);
+        assert_eq!(config.time.small_break_minutes, 7);
+        assert!(config.general.no_sound);
+        assert!(!config.general.focus_lock);
+        // Explicit flags beat the environment
+        assert_eq!(config.time.long_break_minutes, 20);
+        assert_eq!(cli_config.time.work_minutes, 30);
+
+        let mut config = Config::default();
+        assert_eq!(config.apply_env_overrides(env).len(), 2);
+        assert!(!config.general.no_color);
+
+        // NO_COLOR counts when it is set to anything but an empty string
+        let mut config = Config::default();
+        config.apply_env_overrides(|name: &str| (name == "NO_COLOR").then(String::new));
+        assert!(!config.general.no_color);
+        config.apply_env_overrides(|name: &str| (name == "NO_COLOR").then(|| "1".to_string()));
+        assert!(config.general.no_color);
+    }
+
+    #[test]
+    fn test_config_source_matches_loaded_file() {
+        let (_, source) = Config::load_from_cli_args_with_config(parse_args(&[]));
+        assert_eq!(source, ConfigSource::DefaultsOnly);
+
+        let path = std::env::temp_dir().join(format!("rustdoro-source-{}.ini", std::process::id()));
+        Config::default().save_to_file(&path).unwrap();
+        let args = CliArgs::parse_from(["rustdoro", "--path", path.to_str().unwrap()]);
+        let (_, source) = Config::load_from_cli_args_with_config(args);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(source, ConfigSource::FileAtPath(path.clone()));
+
+        // A file that fails to parse falls back to the defaults
+        std::fs::write(&path, "[Time]\nwork_minutes = \"soon\"\n").unwrap();
+        let args = CliArgs::parse_from(["rustdoro", "--path", path.to_str().unwrap()]);
+        let (_, source) = Config::load_from_cli_args_with_config(args);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(source, ConfigSource::DefaultsOnly);
+    }
+
+    #[test]
+    fn test_config_from_stdin() {
+        let stdin = "[General]\nno_clock = true\nno_sound = false\nemoji = true\n\
+                     [Time]\ntomatoes_per_set = 3\nwork_minutes = 45\nsmall_break_minutes = 9\nlong_break_minutes = 15\n\
+                     [Audio]\nvolume = 3.0\n";
+        let no_env = |_: &str| None;
+        let args = CliArgs::parse_from(["rustdoro", "--config-stdin", "-s", "4"]);
+        let (mut config, source) = Config::resolve_with(args, Some(Ok(stdin.to_string())), no_env);
+
+        assert_eq!(source, ConfigSource::Stdin);
+        assert_eq!(source.path(), None);
+        assert_eq!(config.time.work_minutes, 45);
+        assert_eq!(config.time.tomatoes_per_set, 3);
+        assert!(config.general.no_clock);
+        // Flags still beat the piped config, and it is validated like a file
+        assert_eq!(config.time.small_break_minutes, 4);
+        assert_eq!(config.validate().len(), 1);
+        assert_eq!(config.audio.volume, 1.0);
+
+        // Unreadable or invalid input falls back to the defaults
+        let args = CliArgs::parse_from(["rustdoro", "--config-stdin"]);
+        let (config, source) = Config::resolve_with(args, Some(Ok("not toml".to_string())), no_env);
+        assert_eq!(source, ConfigSource::DefaultsOnly);
+        assert_eq!(config.time.work_minutes, 25);
+        let args = CliArgs::parse_from(["rustdoro", "--config-stdin"]);
+        let failed = Err(std::io::Error::other("closed"));
+        assert_eq!(Config::resolve_with(args, Some(failed), no_env).1, ConfigSource::DefaultsOnly);
+
+        assert!(CliArgs::try_parse_from(["rustdoro", "--config-stdin", "--path", "a.ini"]).is_err());
+    }
+
+    #[test]
+    fn test_save_volume_keeps_other_settings() {
+        let path = std::env::temp_dir().join(format!("rustdoro-volume-{}.ini", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        Config::save_volume(&path, 0.3).unwrap();
+        assert_eq!(Config::load_from_file(&path).unwrap().audio.volume, 0.3);
+
+        let mut config = Config::default();
+        config.time.work_minutes = 45;
+        config.save_to_file(&path).unwrap();
+        Config::save_volume(&path, 0.9).unwrap();
+        let saved = Config::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(saved.audio.volume, 0.9);
+        assert_eq!(saved.time.work_minutes, 45);
+    }
+
+    #[test]
+    fn test_volume_per_sound() {
+        let mut config = Config::default();
+        config.audio.volume = 0.6;
+        config.audio.break_volume = Some(0.2);
+        config.audio.end_volume = Some(1.0);
+
+        assert_eq!(config.audio.volume_for(SoundKind::Work), 0.6);
+        assert_eq!(config.audio.volume_for(SoundKind::Break), 0.2);
+        assert_eq!(config.audio.volume_for(SoundKind::End), 1.0);
+
+        // Changing the volume moves the per-sound ones along with it
+        config.audio.set_volume(0.4);
+        assert_eq!(config.audio.volume_for(SoundKind::Work), 0.4);
+        assert!((config.audio.volume_for(SoundKind::Break) - 0.0).abs() < 1e-6);
+        assert!((config.audio.volume_for(SoundKind::End) - 0.8).abs() < 1e-6);
+        config.audio.set_volume(1.5);
+        assert_eq!(config.audio.volume, 1.0);
+        assert!((config.audio.volume_for(SoundKind::Break) - 0.6).abs() < 1e-6);
+        assert_eq!(config.audio.volume_for(SoundKind::End), 1.0);
+    }
+
+    #[test]
+    fn test_zero_length_sessions_are_rejected() {
+        let mut config = Config::default();
+        config.time.work_minutes = 0;
+        config.time.long_break_minutes = 0;
+        assert_eq!(
+            config.validate(),
+            ["work_minutes must be at least 1, using 25", "long_break_minutes must be at least 1, using 15"]
+        );
+        assert_eq!(config.time.work_minutes, 25);
+        assert_eq!(config.time.long_break_minutes, 15);
+
+        for flag in ["-w", "-s", "-l"] {
+            assert!(CliArgs::try_parse_from(["rustdoro", flag, "0"]).is_err(), "{}", flag);
+        }
+        assert!(CliArgs::try_parse_from(["rustdoro", "-w", "1"]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_clamps_volumes() {
+        let mut config = Config::default();
+        assert!(config.validate().is_empty());
+
+        config.audio.volume = 1.5;
+        config.audio.work_volume = Some(-0.3);
+        config.audio.end_volume = Some(0.9);
+        let warnings = config.validate();
+
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(config.audio.volume, 1.0);
+        assert_eq!(config.audio.work_volume, Some(0.0));
+        assert_eq!(config.audio.end_volume, Some(0.9));
+
+        // Loading without validation keeps the value as given
+        let (mut config, _) = Config::resolve_from_cli_args(parse_args(&["--volume", "2"]));
+        assert_eq!(config.audio.volume, 2.0);
+        assert_eq!(config.validate().len(), 1);
+
+        let mut config = Config::default();
+        config.time.plan = vec![PlanStep { session: SessionType::Work, minutes: Some(0) }];
+        assert_eq!(config.validate().len(), 1);
+        assert_eq!(config.time.plan[0].minutes, None);
+
+        let mut config = Config::default();
+        config.audio.snooze_minutes = 0;
+        config.audio.end_beep_count = 0;
+        config.audio.tick_interval_secs = 0;
+        config.audio.tick_volume = 3.0;
+        assert_eq!(config.validate().len(), 4);
+        assert_eq!(config.audio.tick_interval_secs, default_tick_interval_secs());
+        assert_eq!(config.audio.tick_volume, 1.0);
+        assert_eq!(config.audio.end_beep_count, 1);
+        assert_eq!(config.audio.snooze_minutes, default_snooze_minutes());
+
+        let mut config = Config::default();
+        config.general.fill_char = "■".to_string();
+        config.general.empty_char = "#".to_string();
+        assert!(config.validate().is_empty());
+        assert_eq!(config.progress_chars(), ('■', '#'));
+        // Emoji are two cells wide, and a string is more than one character
+        config.general.fill_char = "🍅".to_string();
+        config.general.empty_char = "--".to_string();
+        assert_eq!(config.validate().len(), 2);
+        assert_eq!(config.progress_chars(), ('█', '░'));
+
+        let mut config = Config::default();
+        config.time.work_minutes = u64::MAX;
+        config.time.long_break_minutes = MAX_SESSION_MINUTES;
+        config.time.rounding_minutes = u64::MAX;
+        config.audio.snooze_minutes = MAX_SESSION_MINUTES + 1;
+        config.time.plan = vec![PlanStep { session: SessionType::ShortBreak, minutes: Some(MAX_SESSION_MINUTES + 1) }];
+        let warnings = config.validate();
+        assert_eq!(
+            warnings,
+            [
+                "work_minutes can be at most 10080 (a week), using 10080",
+                "rounding_minutes can be at most 10080 (a week), using 10080",
+                "snooze_minutes can be at most 10080 (a week), using 10080",
+                "plan step minutes can be at most 10080 (a week), using 10080",
+            ]
+        );
+        assert_eq!(config.time.work_minutes, MAX_SESSION_MINUTES);
+        assert_eq!(config.time.plan[0].minutes, Some(MAX_SESSION_MINUTES));
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config = Config::from_toml_str("[Time]\nwork_minutes = 50\n").unwrap();
+        assert_eq!(config.time.work_minutes, 50);
+        assert_eq!(config.time.small_break_minutes, 5);
+        assert_eq!(config.time.tomatoes_per_set, 4);
+        assert!(config.general.emoji);
+        assert_eq!(config.audio.volume, Config::default().audio.volume);
+        assert!(config.unknown_key_warnings().is_empty());
+
+        let empty = Config::from_toml_str("").unwrap();
+        assert_eq!(empty.time.work_minutes, 25);
+        assert!(empty.unknown_key_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_reported() {
+        let mut config = Config::from_toml_str(
+            "[General]\nemoji = false\n\n\
+             [Time]\nwork_minute = 50\nsmall_break_minutes = 10\nfrobnicate = true\n\n\
+             [Audo]\nvolume = 0.5\n",
+        )
+        .unwrap();
+        // Known keys still load around the unknown ones
+        assert!(!config.general.emoji);
+        assert_eq!(config.time.small_break_minutes, 10);
+        assert_eq!(config.time.work_minutes, 25);
+
+        assert_eq!(
+            config.unknown_key_warnings(),
+            vec![
+                "Unknown section [Audo] is ignored, did you mean [Audio]?".to_string(),
+                "Unknown key `work_minute` in [Time] is ignored, did you mean `work_minutes`?".to_string(),
+                "Unknown key `frobnicate` in [Time] is ignored".to_string(),
+            ]
+        );
+        assert_eq!(config.validate().len(), 3);
+
+        // Saving the config again leaves them out
+        let saved = toml::to_string(&config).unwrap();
+        assert!(!saved.contains("work_minute "));
+        assert!(!saved.contains("frobnicate"));
+        assert!(!saved.contains("unknown"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("work_minute", "work_minutes"), 1);
+        assert_eq!(edit_distance("volume", "volume"), 0);
+        assert_eq!(edit_distance("emoij", "emoji"), 2);
+        assert_eq!(closest_key("Generl", ["General", "Time"]), Some("General"));
+        assert_eq!(closest_key("colour", ["General", "Time"]), None);
+    }
+
+    #[test]
+    fn test_plan_parsing() {
+        let config: Config = toml::from_str(
+            "[General]\nno_clock = false\nno_sound = false\nemoji = true\n\
+             [Time]\ntomatoes_per_set = 4\nwork_minutes = 25\nsmall_break_minutes = 5\nlong_break_minutes = 15\n\
+             loop_plan = false\n\
+             plan = [{ session = \"work\", minutes = 50 }, { session = \"long_break\" }]\n\
+             [Audio]\nvolume = 0.7\n",
+        )
+        .unwrap();
+        assert!(!config.time.loop_plan);
+        assert_eq!(
+            config.plan(),
+            vec![
+                PlanStep { session: SessionType::Work, minutes: Some(50) },
+                PlanStep::new(SessionType::LongBreak),
+            ]
+        );
+
+        // Without a plan the classic cycle is used and loops
+        assert!(Config::default().time.loop_plan);
+        assert_eq!(Config::default().plan(), default_plan(4));
+    }
+
+    #[test]
+    fn test_completion_messages() {
+        let messages = MessagesConfig {
+            work_complete: "Done #{count}, take a break".to_string(),
+            break_complete: "   ".to_string(),
+            long_break_complete: "Set {count} finished, {count}!".to_string(),
+            ..MessagesConfig::default()
+        };
+
+        assert_eq!(
+            messages.completion_message(SessionType::Work, 3),
+            "Done #3, take a break"
+        );
+        assert_eq!(
+            messages.completion_message(SessionType::ShortBreak, 3),
+            default_completion_message()
+        );
+        assert_eq!(
+            messages.completion_message(SessionType::LongBreak, 4),
+            "Set 4 finished, 4!"
+        );
+    }
+
+    #[test]
+    fn test_messages_section_is_optional() {
+        let config: Config = toml::from_str(
+            "[General]\nno_clock = false\nno_sound = false\nemoji = true\n\
+             [Time]\ntomatoes_per_set = 4\nwork_minutes = 25\nsmall_break_minutes = 5\nlong_break_minutes = 15\n\
+             [Audio]\nvolume = 0.7\n\
+             [Messages]\nwork_complete = \"Nice one\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.messages.work_complete, "Nice one");
+        assert_eq!(config.messages.break_complete, default_completion_message());
+    }
+
+    #[test]
+    fn test_duration_until_clock_time() {
+        use chrono::NaiveDate;
+
+        let now = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(13, 20, 30).unwrap();
+        let at = |text| parse_clock_time(text).unwrap();
+
+        assert_eq!(
+            duration_until(at("15:00"), now, false).unwrap(),
+            Duration::from_secs(99 * 60 + 30)
+        );
+        // A time that has passed rolls over to tomorrow
+        assert_eq!(
+            duration_until(at("09:00"), now, false).unwrap(),
+            Duration::from_secs((19 * 60 + 39) * 60 + 30)
+        );
+        assert!(duration_until(at("09:00"), now, true).is_err());
+        assert!(duration_until(at("23:59"), now, true).is_ok());
+
+        assert!(parse_clock_time("25:00").is_err());
+        assert!(parse_clock_time("3pm").is_err());
+        assert!(parse_args(&["--until", "15:00"]).until.is_some());
+        assert!(CliArgs::try_parse_from(["rustdoro", "--until-today"]).is_err());
+    }
+
+    #[test]
+    fn test_webhooks_section() {
+        let config: Config = toml::from_str(
+            "[General]\nno_clock = false\nno_sound = false\nemoji = true\n\
+             [Time]\ntomatoes_per_set = 4\nwork_minutes = 25\nsmall_break_minutes = 5\nlong_break_minutes = 15\n\
+             [Audio]\nvolume = 0.7\n\
+             [Webhooks]\non_complete = \"http://localhost:8123/done\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.webhooks.on_complete.as_deref(), Some("http://localhost:8123/done"));
+        assert_eq!(config.webhooks.on_work_start, None);
+        assert!(!config.webhooks.is_empty());
+        assert!(Config::default().webhooks.is_empty());
+    }
+
+    #[test]
+    fn test_notification_urgency_per_event() {
+        let config = Config::from_toml_str(
+            "[Notifications]\nsessions = true\ntimeout_ms = 5000\n\
+             notification_urgency = { work_start = \"low\", complete = \"normal\" }\n",
+        )
+        .unwrap();
+        assert!(config.notifications.sessions);
+        assert_eq!(config.notifications.timeout_ms, 5000);
+        let urgency = &config.notifications.notification_urgency;
+        assert_eq!(urgency.work_start, Urgency::Low);
+        assert_eq!(urgency.complete, Urgency::Normal);
+        // Events left out keep their defaults
+        assert_eq!(urgency.break_start, Urgency::Low);
+        assert_eq!(urgency.daily_goal, Urgency::Normal);
+        assert!(config.unknown_key_warnings().is_empty());
+        assert!(!Config::default().notifications.sessions);
+    }
+
+    #[test]
+    fn test_labels_override_defaults() {
+        let config: Config = toml::from_str(
+            "[General]\nno_clock = false\nno_sound = false\nemoji = true\n\
+             [Time]\ntomatoes_per_set = 4\nwork_minutes = 25\nsmall_break_minutes = 5\nlong_break_minutes = 15\n\
+             [Audio]\nvolume = 0.7\n\
+             [Labels]\nwork = \"Arbeit\"\nshort_break = \"  \"\nlong_break_emoji = \"🌴\"\n",
+        )
+        .unwrap();
+        let labels = &config.labels;
+        assert_eq!(labels.label(SessionType::Work), "Arbeit");
+        // Blank and missing entries use the built-in labels
+        assert_eq!(labels.label(SessionType::ShortBreak), "Short Break");
+        assert_eq!(labels.label(SessionType::LongBreak), "Long Break");
+        assert_eq!(labels.emoji(SessionType::Work), "🍅");
+        assert_eq!(labels.emoji(SessionType::LongBreak), "🌴");
+
+        let timer = crate::timer::Timer::new(config);
+        assert_eq!(crate::status::format_status("{emoji} {session}", &timer), "🍅 Arbeit");
+    }
+
     #[test]
     fn test_home_directory() {
         // Get home directory
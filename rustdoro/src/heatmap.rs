@@ -0,0 +1,253 @@
+use crate::history::DailyCount;
+use chrono::{Datelike, NaiveDate};
+use std::fmt::Write;
+
+/// Intensity levels a day can have, 0 meaning no pomodoros
+pub const LEVELS: u8 = 5;
+
+/// Characters the text grid uses for each intensity level
+const TEXT_CELLS: [char; LEVELS as usize] = ['·', '░', '▒', '▓', '█'];
+
+/// Fill colors the SVG uses for each intensity level
+const SVG_COLORS: [&str; LEVELS as usize] = ["#ebedf0", "#fcc9b9", "#f79274", "#e8552f", "#b8300f"];
+
+/// Size of a day square in the SVG, with the gap after it
+const SVG_CELL: u32 = 11;
+const SVG_GAP: u32 = 2;
+/// Room left of the grid for the weekday labels
+const SVG_LABEL_WIDTH: u32 = 28;
+
+/// Map a day's pomodoros to an intensity level relative to the busiest day
+/// Any pomodoro at all is at least level 1, and the busiest day is always the top level.
+pub fn intensity(count: u64, max: u64) -> u8 {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+    let top = u64::from(LEVELS - 1);
+    (count.min(max) * top).div_ceil(max).clamp(1, top) as u8
+}
+
+/// Pomodoros per day laid out as weeks of Monday to Sunday
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    /// Intensity of each day, None before the first and after the last day
+    weeks: Vec<[Option<u8>; 7]>,
+    /// Pomodoros over all days
+    total: u64,
+}
+
+impl Heatmap {
+    /// Build the grid from consecutive daily counts, oldest first
+    pub fn from_counts(counts: &[DailyCount]) -> Self {
+        let max = counts.iter().map(|day| day.pomodoros).max().unwrap_or(0);
+        let offset = counts
+            .first()
+            .map_or(0, |day| day.date.weekday().num_days_from_monday() as usize);
+
+        let mut weeks = vec![[None; 7]; (offset + counts.len()).div_ceil(7)];
+        for (index, day) in counts.iter().enumerate() {
+            let cell = offset + index;
+            weeks[cell / 7][cell % 7] = Some(intensity(day.pomodoros, max));
+        }
+
+        Self {
+            weeks,
+            total: counts.iter().map(|day| day.pomodoros).sum(),
+        }
+    }
+
+    /// Get the intensity of each day, one column per week
+    pub fn weeks(&self) -> &[[Option<u8>; 7]] {
+        &self.weeks
+    }
+
+    /// Draw the grid with one character per day and a row per weekday
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (weekday, label) in weekday_labels().iter().enumerate() {
+            text.push_str(&format!("{:<4}", label));
+            for week in &self.weeks {
+                text.push(week[weekday].map_or(' ', |level| TEXT_CELLS[level as usize]));
+            }
+            text.push('\n');
+        }
+        let legend: String = TEXT_CELLS.iter().map(|cell| format!("{} ", cell)).collect();
+        text.push_str(&format!("\n    Less {}More\n", legend));
+        text.push_str(&format!("    {} pomodoros\n", self.total));
+        text
+    }
+
+    /// Draw the grid as an SVG image with a colored square per day
+    pub fn to_svg(&self) -> String {
+        let step = SVG_CELL + SVG_GAP;
+        let width = SVG_LABEL_WIDTH + self.weeks.len().max(1) as u32 * step;
+        let height = 7 * step;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"9\">",
+            width, height
+        );
+        let _ = writeln!(svg, "<title>{} pomodoros</title>", self.total);
+        for (weekday, label) in weekday_labels().iter().enumerate() {
+            if !label.is_empty() {
+                let y = weekday as u32 * step + SVG_CELL - 2;
+                let _ = writeln!(svg, "<text x=\"0\" y=\"{}\" fill=\"#767676\">{}</text>", y, label);
+            }
+        }
+        for (column, week) in self.weeks.iter().enumerate() {
+            for (weekday, level) in week.iter().enumerate() {
+                let Some(level) = level else {
+                    continue;
+                };
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\"/>",
+                    SVG_LABEL_WIDTH + column as u32 * step,
+                    weekday as u32 * step,
+                    SVG_CELL,
+                    SVG_CELL,
+                    SVG_COLORS[*level as usize]
+                );
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Day in a month calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarDay {
+    /// Day of the month, from 1
+    pub day: u32,
+    /// Pomodoros completed on the day, None for days still to come
+    pub pomodoros: Option<u64>,
+    /// Whether the day met the daily goal
+    pub goal_met: bool,
+}
+
+/// Lay out the month of `today` as weeks of Monday to Sunday, None outside the month
+/// `counts` are looked up by date, so any range covering the month so far works.
+/// A `goal` of 0 means no day meets it.
+pub fn month_calendar(today: NaiveDate, counts: &[DailyCount], goal: u64) -> Vec<[Option<CalendarDay>; 7]> {
+    let first = today.with_day(1).unwrap_or(today);
+    let offset = first.weekday().num_days_from_monday() as usize;
+    let days: Vec<NaiveDate> = first.iter_days().take_while(|date| date.month() == first.month()).collect();
+
+    let mut weeks = vec![[None; 7]; (offset + days.len()).div_ceil(7)];
+    for (index, date) in days.iter().enumerate() {
+        let pomodoros = (*date <= today).then(|| {
+            counts
+                .iter()
+                .find(|count| count.date == *date)
+                .map_or(0, |count| count.pomodoros)
+        });
+        let cell = offset + index;
+        weeks[cell / 7][cell % 7] = Some(CalendarDay {
+            day: date.day(),
+            pomodoros,
+            goal_met: goal > 0 && pomodoros.is_some_and(|pomodoros| pomodoros >= goal),
+        });
+    }
+    weeks
+}
+
+/// Labels for the weekday rows, leaving every other one blank like GitHub does
+fn weekday_labels() -> [&'static str; 7] {
+    ["Mon", "", "Wed", "", "Fri", "", "Sun"]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn counts(first: NaiveDate, pomodoros: &[u64]) -> Vec<DailyCount> {
+        pomodoros
+            .iter()
+            .enumerate()
+            .map(|(offset, &pomodoros)| DailyCount {
+                date: first + chrono::Days::new(offset as u64),
+                pomodoros,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_intensity_buckets() {
+        assert_eq!(intensity(0, 8), 0);
+        assert_eq!(intensity(1, 8), 1);
+        assert_eq!(intensity(2, 8), 1);
+        assert_eq!(intensity(3, 8), 2);
+        assert_eq!(intensity(4, 8), 2);
+        assert_eq!(intensity(6, 8), 3);
+        assert_eq!(intensity(7, 8), 4);
+        assert_eq!(intensity(8, 8), 4);
+        // A single pomodoro on the busiest day is still the top level
+        assert_eq!(intensity(1, 1), 4);
+        assert_eq!(intensity(0, 0), 0);
+        assert_eq!(intensity(9, 8), 4);
+    }
+
+    #[test]
+    fn test_grid_starts_on_monday() {
+        // 2026-10-14 is a Wednesday
+        let wednesday = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let heatmap = Heatmap::from_counts(&counts(wednesday, &[4, 0, 1, 2, 0, 0, 0, 3]));
+
+        assert_eq!(
+            heatmap.weeks(),
+            &[
+                [None, None, Some(4), Some(0), Some(1), Some(2), Some(0)],
+                [Some(0), Some(0), Some(3), None, None, None, None],
+            ]
+        );
+        let text = heatmap.to_text();
+        assert!(text.starts_with("Mon  ·\n     ·\nWed █▓\n"), "{}", text);
+        assert!(text.ends_with("10 pomodoros\n"));
+        assert_eq!(heatmap.to_svg().matches("<rect").count(), 8);
+    }
+
+    #[test]
+    fn test_month_calendar() {
+        // February 2026 starts on a Sunday and has 28 days
+        let today = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap();
+        let first = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        let weeks = month_calendar(today, &counts(first, &[2, 0, 4, 1, 0, 0, 0, 0, 3, 5]), 4);
+
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][..6], [None; 6]);
+        let day = |week: usize, weekday: usize| weeks[week][weekday].unwrap();
+        assert_eq!(day(0, 6), CalendarDay { day: 1, pomodoros: Some(2), goal_met: false });
+        assert_eq!(day(1, 1), CalendarDay { day: 3, pomodoros: Some(4), goal_met: true });
+        assert_eq!(day(2, 0), CalendarDay { day: 9, pomodoros: Some(3), goal_met: false });
+        assert_eq!(day(2, 1), CalendarDay { day: 10, pomodoros: Some(5), goal_met: true });
+        // Days after today have no count yet
+        assert_eq!(day(2, 2), CalendarDay { day: 11, pomodoros: None, goal_met: false });
+        assert_eq!(day(4, 5), CalendarDay { day: 28, pomodoros: None, goal_met: false });
+        assert_eq!(weeks[4][6], None);
+    }
+
+    #[test]
+    fn test_month_calendar_without_data() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 31).unwrap();
+        let weeks = month_calendar(today, &[], 0);
+        let days: Vec<CalendarDay> = weeks.iter().flatten().flatten().copied().collect();
+        assert_eq!(days.len(), 31);
+        assert!(days.iter().all(|day| day.pomodoros == Some(0) && !day.goal_met));
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let heatmap = Heatmap::from_counts(&counts(monday, &[0; 14]));
+        assert!(heatmap.weeks().iter().flatten().all(|day| *day == Some(0)));
+        assert!(heatmap.to_text().ends_with("0 pomodoros\n"));
+
+        let nothing = Heatmap::from_counts(&[]);
+        assert!(nothing.weeks().is_empty());
+        assert!(nothing.to_svg().ends_with("</svg>\n"));
+    }
+}
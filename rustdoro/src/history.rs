@@ -0,0 +1,416 @@
+use anyhow::Result;
+use chrono::{DateTime, Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// A completed work session as stored in the history file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub completed_at: DateTime<Local>,
+    pub minutes: u64,
+    /// Note entered after the session, blank if the prompt was skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Seconds actually spent working, excluding pauses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_secs: Option<u64>,
+    /// Task the session was spent on, if one was set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    /// Pomodoros added or removed by hand, set instead of a completed session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjustment: Option<i32>,
+    /// Seconds worked past the end of the session in overtime
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overtime_secs: Option<u64>,
+    /// `net_secs` rounded to the configured `rounding_minutes`, for billing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounded_secs: Option<u64>,
+    /// Tag the session was filed under, without its `#`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl HistoryRecord {
+    /// Create a record for a work session of `minutes` that ended at `completed_at`
+    pub fn new(completed_at: DateTime<Local>, minutes: u64) -> Self {
+        Self {
+            completed_at,
+            minutes,
+            note: None,
+            net_secs: None,
+            task: None,
+            adjustment: None,
+            overtime_secs: None,
+            rounded_secs: None,
+            tag: None,
+        }
+    }
+
+    /// Create a record of pomodoros credited (or taken back) by hand at `at`
+    pub fn adjustment(at: DateTime<Local>, delta: i32) -> Self {
+        Self {
+            adjustment: Some(delta),
+            ..Self::new(at, 0)
+        }
+    }
+
+    /// Get how many pomodoros the record counts for
+    fn pomodoros(&self) -> i64 {
+        self.adjustment.map_or(1, i64::from)
+    }
+}
+
+/// Round `secs` to the nearest multiple of `increment` seconds, halfway values rounding up
+/// An `increment` of zero leaves the value as it is.
+pub fn round_to_increment(secs: u64, increment: u64) -> u64 {
+    if increment == 0 {
+        return secs;
+    }
+    (secs * 2 + increment) / (increment * 2) * increment
+}
+
+/// Pomodoros completed on a single day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub pomodoros: u64,
+}
+
+/// Pomodoros filed under a single tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagCount {
+    pub tag: String,
+    pub pomodoros: u64,
+}
+
+/// The day daily counts are shown for, moved on when the app runs past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Today {
+    date: NaiveDate,
+}
+
+impl Today {
+    /// Start on the day of `now`
+    pub fn new(now: DateTime<Local>) -> Self {
+        Self {
+            date: now.date_naive(),
+        }
+    }
+
+    /// Get the current day
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// Move on to the day of `now`, returning true if a new day has started
+    pub fn update(&mut self, now: DateTime<Local>) -> bool {
+        let date = now.date_naive();
+        let changed = date != self.date;
+        self.date = date;
+        changed
+    }
+}
+
+/// Completed sessions, persisted as one JSON object per line
+#[derive(Debug, Default)]
+pub struct History {
+    path: Option<PathBuf>,
+    records: Vec<HistoryRecord>,
+}
+
+/// Get the path of the history file
+pub fn history_path() -> Result<PathBuf> {
+    let mut path: PathBuf = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+    path.push("rustdoro");
+    path.push("history.jsonl");
+    Ok(path)
+}
+
+impl History {
+    /// Load history from a file, starting empty if it does not exist yet
+    /// Lines that fail to parse are skipped with a warning so one bad line doesn't lose the rest.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut records = Vec::new();
+
+        if path.exists() {
+            let reader = BufReader::new(File::open(path)?);
+            for (index, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(&line) {
+                    Ok(record) => records.push(record),
+                    Err(e) => tracing::warn!(
+                        "Skipping history line {} in {}: {}",
+                        index + 1,
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            records,
+        })
+    }
+
+    /// Load history from the default location, falling back to in-memory history
+    pub fn load_default() -> Self {
+        match history_path().and_then(|path| Self::load(&path)) {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!("History is not persisted: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Append a record and write it to the history file
+    pub fn record(&mut self, record: HistoryRecord) -> Result<()> {
+        let result = match &self.path {
+            Some(path) => append_record(path, &record),
+            None => Ok(()),
+        };
+        self.records.push(record);
+        result
+    }
+
+    /// Count pomodoros per day for the `days` days ending with `today`, oldest first
+    pub fn daily_counts(&self, today: NaiveDate, days: u64) -> Vec<DailyCount> {
+        (0..days)
+            .rev()
+            .filter_map(|offset| today.checked_sub_days(Days::new(offset)))
+            .map(|date| DailyCount {
+                date,
+                pomodoros: count_pomodoros(
+                    self.records
+                        .iter()
+                        .filter(|record| record.completed_at.date_naive() == date),
+                ),
+            })
+            .collect()
+    }
+
+    /// Total number of recorded pomodoros, including manual adjustments
+    pub fn total(&self) -> u64 {
+        count_pomodoros(self.records.iter())
+    }
+
+    /// Count pomodoros per tag, busiest first and then by name
+    /// Untagged records and tags that add up to nothing are left out.
+    pub fn tag_counts(&self) -> Vec<TagCount> {
+        let mut tags: Vec<&str> = self.records.iter().filter_map(|record| record.tag.as_deref()).collect();
+        tags.sort_unstable();
+        tags.dedup();
+
+        let mut counts: Vec<TagCount> = tags
+            .into_iter()
+            .map(|tag| TagCount {
+                tag: tag.to_string(),
+                pomodoros: count_pomodoros(self.records.iter().filter(|record| record.tag.as_deref() == Some(tag))),
+            })
+            .filter(|count| count.pomodoros > 0)
+            .collect();
+        counts.sort_by(|a, b| b.pomodoros.cmp(&a.pomodoros).then_with(|| a.tag.cmp(&b.tag)));
+        counts
+    }
+
+    /// Number of lines in the history, sessions and adjustments alike
+    pub fn record_count(&self) -> u64 {
+        self.records.len() as u64
+    }
+}
+
+/// Add up the pomodoros of some records, never going below zero
+fn count_pomodoros<'a>(records: impl Iterator<Item = &'a HistoryRecord>) -> u64 {
+    records.map(HistoryRecord::pomodoros).sum::<i64>().max(0) as u64
+}
+
+/// Delete the history file, returning how many records it held
+/// A missing file means there is nothing to clear.
+pub fn clear_history(path: &Path) -> Result<u64> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let removed = History::load(path)?.record_count();
+    std::fs::remove_file(path)?;
+    Ok(removed)
+}
+
+/// Append a single record to the history file, creating it if needed
+fn append_record(path: &Path, record: &HistoryRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record_at(year: i32, month: u32, day: u32, hour: u32) -> HistoryRecord {
+        HistoryRecord::new(Local.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap(), 25)
+    }
+
+    fn tagged(tag: Option<&str>, record: HistoryRecord) -> HistoryRecord {
+        HistoryRecord {
+            tag: tag.map(str::to_string),
+            ..record
+        }
+    }
+
+    #[test]
+    fn test_tag_counts() {
+        let at = Local.with_ymd_and_hms(2026, 10, 14, 9, 0, 0).unwrap();
+        let mut history = History::default();
+        for tag in [Some("client-a"), Some("writing"), Some("client-a"), None, Some("admin")] {
+            history.record(tagged(tag, HistoryRecord::new(at, 25))).unwrap();
+        }
+        history.record(tagged(Some("client-a"), HistoryRecord::adjustment(at, 2))).unwrap();
+        // Taking back the only pomodoro of a tag drops it
+        history.record(tagged(Some("admin"), HistoryRecord::adjustment(at, -1))).unwrap();
+
+        let count = |tag: &str, pomodoros| TagCount {
+            tag: tag.to_string(),
+            pomodoros,
+        };
+        assert_eq!(history.tag_counts(), [count("client-a", 4), count("writing", 1)]);
+        assert!(History::default().tag_counts().is_empty());
+    }
+
+    #[test]
+    fn test_round_to_increment() {
+        let quarter = 15 * 60;
+        // 22.5 minutes is exactly halfway and rounds up
+        assert_eq!(round_to_increment(1350, quarter), 30 * 60);
+        assert_eq!(round_to_increment(1349, quarter), 15 * 60);
+        assert_eq!(round_to_increment(7 * 60 + 29, quarter), 0);
+        assert_eq!(round_to_increment(7 * 60 + 30, quarter), 15 * 60);
+        assert_eq!(round_to_increment(25 * 60, quarter), 30 * 60);
+        assert_eq!(round_to_increment(45 * 60, quarter), 45 * 60);
+        // Odd increments still split at the exact half
+        assert_eq!(round_to_increment(3, 7), 0);
+        assert_eq!(round_to_increment(4, 7), 7);
+        assert_eq!(round_to_increment(1350, 0), 1350);
+    }
+
+    #[test]
+    fn test_daily_counts_covers_last_days() {
+        let mut history = History::default();
+        history.record(record_at(2024, 3, 1, 9)).unwrap();
+        history.record(record_at(2024, 3, 1, 23)).unwrap();
+        history.record(record_at(2024, 3, 3, 0)).unwrap();
+        // Outside the window
+        history.record(record_at(2024, 2, 20, 12)).unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2024, 3, 3).unwrap();
+        let counts = history.daily_counts(today, 7);
+
+        assert_eq!(counts.len(), 7);
+        assert_eq!(counts[0].date, NaiveDate::from_ymd_opt(2024, 2, 26).unwrap());
+        assert_eq!(counts[6].date, today);
+        let pomodoros: Vec<u64> = counts.iter().map(|day| day.pomodoros).collect();
+        assert_eq!(pomodoros, vec![0, 0, 0, 0, 2, 0, 1]);
+        assert_eq!(history.total(), 4);
+    }
+
+    #[test]
+    fn test_adjustments_change_counts() {
+        let mut history = History::default();
+        history.record(record_at(2024, 3, 1, 9)).unwrap();
+        let at = |day, hour| Local.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap();
+        history.record(HistoryRecord::adjustment(at(1, 12), 2)).unwrap();
+        history.record(HistoryRecord::adjustment(at(2, 12), -1)).unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let pomodoros: Vec<u64> = history.daily_counts(today, 2).iter().map(|day| day.pomodoros).collect();
+        // A day taken below zero shows as none
+        assert_eq!(pomodoros, vec![3, 0]);
+        assert_eq!(history.total(), 2);
+        assert_eq!(history.record_count(), 3);
+
+        // Adjustments are marked as such in the file
+        let line = serde_json::to_string(&HistoryRecord::adjustment(at(2, 12), -1)).unwrap();
+        assert!(line.contains("\"adjustment\":-1"), "{}", line);
+        assert!(!serde_json::to_string(&record_at(2024, 3, 1, 9)).unwrap().contains("adjustment"));
+    }
+
+    #[test]
+    fn test_today_rolls_over_at_midnight() {
+        // A clock that can be moved by hand
+        let mut now = Local.with_ymd_and_hms(2024, 3, 1, 23, 50, 0).unwrap();
+        let mut today = Today::new(now);
+        let mut history = History::default();
+        history.record(HistoryRecord::new(now, 25)).unwrap();
+        let today_count = |history: &History, today: &Today| history.daily_counts(today.date(), 1)[0].pomodoros;
+        assert_eq!(today_count(&history, &today), 1);
+
+        now += chrono::Duration::minutes(5);
+        assert!(!today.update(now));
+
+        // A session finished at 00:05 counts for the new day
+        now += chrono::Duration::minutes(10);
+        assert!(today.update(now));
+        assert_eq!(today.date(), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+        assert_eq!(today_count(&history, &today), 0);
+        history.record(HistoryRecord::new(now, 25)).unwrap();
+        assert_eq!(today_count(&history, &today), 1);
+        assert_eq!(history.daily_counts(today.date(), 2)[0].pomodoros, 1);
+        assert!(!today.update(now));
+    }
+
+    #[test]
+    fn test_history_round_trips_through_file() {
+        let path = std::env::temp_dir().join(format!("rustdoro-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut history = History::load(&path).unwrap();
+        assert_eq!(history.total(), 0);
+        history.record(record_at(2024, 3, 1, 9)).unwrap();
+        let mut with_note = record_at(2024, 3, 2, 9);
+        with_note.note = Some("Wrote the intro, with \"quotes\"".to_string());
+        with_note.net_secs = Some(1200);
+        with_note.task = Some("Report".to_string());
+        history.record(with_note).unwrap();
+        // A corrupt line must not take the valid records down with it
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "not json"))
+            .unwrap();
+
+        let reloaded = History::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(reloaded.records, history.records);
+    }
+
+    #[test]
+    fn test_clear_history_counts_records() {
+        let path = std::env::temp_dir().join(format!("rustdoro-clear-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // Nothing to clear yet
+        assert_eq!(clear_history(&path).unwrap(), 0);
+
+        let mut history = History::load(&path).unwrap();
+        history.record(record_at(2024, 3, 1, 9)).unwrap();
+        history.record(record_at(2024, 3, 1, 10)).unwrap();
+        history.record(record_at(2024, 3, 2, 9)).unwrap();
+
+        assert_eq!(clear_history(&path).unwrap(), 3);
+        assert!(!path.exists());
+        assert_eq!(History::load(&path).unwrap().total(), 0);
+    }
+}
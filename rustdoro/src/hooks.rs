@@ -0,0 +1,176 @@
+use anyhow::Result;
+use std::process::{Child, Command, Stdio};
+use rustdoro::config::HooksConfig;
+use rustdoro::timer::SessionType;
+
+/// Session event a hook runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    WorkStart,
+    BreakStart,
+    Complete,
+}
+
+impl HookEvent {
+    /// Get the name passed to hooks in `RUSTDORO_EVENT`
+    fn name(&self) -> &'static str {
+        match self {
+            HookEvent::WorkStart => "work_start",
+            HookEvent::BreakStart => "break_start",
+            HookEvent::Complete => "complete",
+        }
+    }
+}
+
+/// Runs the shell commands in the `[Hooks]` config section
+pub struct Hooks {
+    config: HooksConfig,
+}
+
+impl Hooks {
+    /// Create the runner, or None if no hook is configured
+    pub fn new(config: HooksConfig) -> Option<Self> {
+        if config.is_empty() {
+            return None;
+        }
+        Some(Self { config })
+    }
+
+    /// Run the hook for the start of a session
+    pub fn session_started(&self, session_type: SessionType, count: u32) {
+        let (event, command) = match session_type {
+            SessionType::Work => (HookEvent::WorkStart, &self.config.on_work_start),
+            SessionType::ShortBreak | SessionType::LongBreak => {
+                (HookEvent::BreakStart, &self.config.on_break_start)
+            }
+        };
+        run(command, event, session_type, count);
+    }
+
+    /// Run the hook for the end of a session
+    pub fn session_completed(&self, session_type: SessionType, count: u32) {
+        run(&self.config.on_complete, HookEvent::Complete, session_type, count);
+    }
+}
+
+/// Start the hook without waiting for it, logging any failure
+fn run(command: &Option<String>, event: HookEvent, session_type: SessionType, count: u32) {
+    let Some(command) = command else {
+        return;
+    };
+    match spawn_hook(command, event, session_type, count) {
+        Ok(child) => reap(command.clone(), child),
+        Err(e) => tracing::warn!("Failed to run hook `{}`: {}", command, e),
+    }
+}
+
+/// Start the command in a shell with the session described in its environment
+/// Its output is discarded so it can't draw over the UI.
+fn spawn_hook(command: &str, event: HookEvent, session_type: SessionType, count: u32) -> Result<Child> {
+    let child = shell_command(command)
+        .env("RUSTDORO_EVENT", event.name())
+        .env("RUSTDORO_SESSION", session_type.key())
+        .env("RUSTDORO_COUNT", count.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(child)
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// Wait for the hook on a background thread so it doesn't linger once it exits
+fn reap(command: String, mut child: Child) {
+    std::thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => {
+            tracing::warn!("Hook `{}` exited with {}", command, status)
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to wait for hook `{}`: {}", command, e),
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    /// Hook that writes its environment to `marker`
+    fn marker_hook(marker: &Path) -> String {
+        format!(
+            "printf '%s %s %s' \"$RUSTDORO_EVENT\" \"$RUSTDORO_SESSION\" \"$RUSTDORO_COUNT\" > '{}'",
+            marker.display()
+        )
+    }
+
+    /// Wait for a hook running in the background to write `marker`
+    fn read_marker(marker: &Path) -> String {
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_secs(5) {
+            if let Ok(contents) = std::fs::read_to_string(marker) {
+                if !contents.is_empty() {
+                    return contents;
+                }
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        panic!("hook never wrote {}", marker.display());
+    }
+
+    #[test]
+    fn test_hook_gets_session_environment() {
+        let marker = std::env::temp_dir().join(format!("rustdoro-hook-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+
+        let status = spawn_hook(&marker_hook(&marker), HookEvent::BreakStart, SessionType::LongBreak, 4)
+            .unwrap()
+            .wait()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(std::fs::read_to_string(&marker).unwrap(), "break_start long_break 4");
+
+        // Through the config, without waiting for the command
+        std::fs::remove_file(&marker).unwrap();
+        let hooks = Hooks::new(HooksConfig {
+            on_complete: Some(marker_hook(&marker)),
+            ..HooksConfig::default()
+        })
+        .unwrap();
+        hooks.session_completed(SessionType::Work, 3);
+        assert_eq!(read_marker(&marker), "complete work 3");
+        std::fs::remove_file(&marker).unwrap();
+    }
+
+    #[test]
+    fn test_failing_hook_is_only_logged() {
+        let hooks = Hooks::new(HooksConfig {
+            on_work_start: Some("exit 3".to_string()),
+            on_break_start: Some("command-that-does-not-exist".to_string()),
+            ..HooksConfig::default()
+        })
+        .unwrap();
+        hooks.session_started(SessionType::Work, 0);
+        hooks.session_started(SessionType::ShortBreak, 1);
+        // No hook for this event
+        hooks.session_completed(SessionType::Work, 1);
+    }
+
+    #[test]
+    fn test_no_runner_without_commands() {
+        assert!(Hooks::new(HooksConfig::default()).is_none());
+    }
+}
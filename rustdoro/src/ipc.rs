@@ -0,0 +1,235 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+use rustdoro::status::state_name;
+use rustdoro::timer::{SessionType, Timer};
+
+/// Command sent to the control socket, one per line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Status,
+    Snapshot,
+    Pause,
+    Resume,
+    Skip,
+}
+
+/// Parse a command, ignoring case and surrounding whitespace
+pub fn parse_command(line: &str) -> Result<Command> {
+    let line = line.trim();
+    match line.to_ascii_uppercase().as_str() {
+        "STATUS" => Ok(Command::Status),
+        "SNAPSHOT" => Ok(Command::Snapshot),
+        "PAUSE" => Ok(Command::Pause),
+        "RESUME" => Ok(Command::Resume),
+        "SKIP" => Ok(Command::Skip),
+        "" => Err(anyhow!("empty command")),
+        _ => Err(anyhow!(
+            "unknown command '{}', expected STATUS, SNAPSHOT, PAUSE, RESUME or SKIP",
+            line
+        )),
+    }
+}
+
+/// Timer state returned by STATUS
+#[derive(Debug, Clone, Serialize)]
+struct StatusReply {
+    session: SessionType,
+    state: &'static str,
+    remaining_secs: u64,
+    display_time: String,
+    pomodoros: u32,
+}
+
+/// Describe the timer as a single line of JSON
+pub fn status_json(timer: &Timer) -> String {
+    let reply = StatusReply {
+        session: timer.get_session_type(),
+        state: state_name(timer.state),
+        remaining_secs: timer.remaining_time.as_secs(),
+        display_time: timer.get_display_time(),
+        pomodoros: timer.get_pomodoros_completed(),
+    };
+    serde_json::to_string(&reply).unwrap_or_else(|e| format!("ERR {}", e))
+}
+
+/// A command waiting for the main loop to carry it out
+pub struct IpcRequest {
+    pub command: Command,
+    reply: oneshot::Sender<String>,
+}
+
+impl IpcRequest {
+    /// Send the answer back to the client
+    pub fn respond(self, reply: impl Into<String>) {
+        // The client may already have hung up
+        let _ = self.reply.send(reply.into());
+    }
+}
+
+/// Control socket that hands parsed commands to the main loop
+/// The socket file is removed again when the server is dropped.
+pub struct IpcServer {
+    path: PathBuf,
+    requests: mpsc::Receiver<IpcRequest>,
+}
+
+impl IpcServer {
+    /// Listen on `path`, replacing a socket file left behind by a crashed run
+    /// Anything at `path` that is not a socket is left alone and reported as an error.
+    pub fn bind(path: &Path) -> Result<Self> {
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(anyhow!("{} exists and is not a socket", path.display()));
+            }
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(anyhow!("{} is already used by another rustdoro", path.display()));
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        let (sender, requests) = mpsc::channel(8);
+        tokio::spawn(accept_connections(listener, sender));
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            requests,
+        })
+    }
+
+    /// Wait for the next command from any client
+    pub async fn recv(&mut self) -> Option<IpcRequest> {
+        self.requests.recv().await
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Serve every client in its own task so a stuck one can't block the others
+async fn accept_connections(listener: UnixListener, sender: mpsc::Sender<IpcRequest>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(stream, sender).await {
+                        tracing::debug!("Control socket client failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                tracing::warn!("Control socket stopped accepting clients: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// Answer each line from a client until it hangs up
+async fn serve_connection(stream: UnixStream, sender: mpsc::Sender<IpcRequest>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let reply = answer(&line, &sender).await;
+        writer.write_all(format!("{}\n", reply).as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Parse a line and wait for the main loop to answer it
+async fn answer(line: &str, sender: &mpsc::Sender<IpcRequest>) -> String {
+    let command = match parse_command(line) {
+        Ok(command) => command,
+        Err(e) => return format!("ERR {}", e),
+    };
+    let (reply, response) = oneshot::channel();
+    if sender.send(IpcRequest { command, reply }).await.is_err() {
+        return "ERR rustdoro is shutting down".to_string();
+    }
+    response
+        .await
+        .unwrap_or_else(|_| "ERR no reply".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoro::config::Config;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("STATUS").unwrap(), Command::Status);
+        assert_eq!(parse_command("snapshot").unwrap(), Command::Snapshot);
+        assert_eq!(parse_command("pause\n").unwrap(), Command::Pause);
+        assert_eq!(parse_command("  Resume ").unwrap(), Command::Resume);
+        assert_eq!(parse_command("skip\r\n").unwrap(), Command::Skip);
+
+        assert_eq!(parse_command("").unwrap_err().to_string(), "empty command");
+        let error = parse_command("STOP now").unwrap_err().to_string();
+        assert!(error.starts_with("unknown command 'STOP now'"), "{}", error);
+    }
+
+    #[test]
+    fn test_status_json() {
+        let mut timer = Timer::new(Config::default());
+        timer.start();
+        timer.remaining_time = std::time::Duration::from_secs(24 * 60 + 13);
+
+        let status: serde_json::Value = serde_json::from_str(&status_json(&timer)).unwrap();
+        assert_eq!(status["session"], "work");
+        assert_eq!(status["state"], "running");
+        assert_eq!(status["remaining_secs"], 24 * 60 + 13);
+        assert_eq!(status["display_time"], "24:13");
+        assert_eq!(status["pomodoros"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_socket_round_trip() {
+        let path = std::env::temp_dir().join(format!("rustdoro-ipc-{}.sock", std::process::id()));
+        let mut server = IpcServer::bind(&path).unwrap();
+        // A second instance must not take over the socket
+        assert!(IpcServer::bind(&path).is_err());
+
+        let client = tokio::spawn({
+            let path = path.clone();
+            async move {
+                let stream = UnixStream::connect(&path).await.unwrap();
+                let (reader, mut writer) = stream.into_split();
+                writer.write_all(b"pause\nbogus\n").await.unwrap();
+                let mut lines = BufReader::new(reader).lines();
+                let first = lines.next_line().await.unwrap().unwrap();
+                let second = lines.next_line().await.unwrap().unwrap();
+                (first, second)
+            }
+        });
+
+        let request = server.recv().await.unwrap();
+        assert_eq!(request.command, Command::Pause);
+        request.respond("OK");
+
+        let (first, second) = client.await.unwrap();
+        assert_eq!(first, "OK");
+        assert!(second.starts_with("ERR unknown command 'bogus'"), "{}", second);
+
+        drop(server);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_bind_keeps_other_files() {
+        let path = std::env::temp_dir().join(format!("rustdoro-ipc-{}.txt", std::process::id()));
+        std::fs::write(&path, "notes").unwrap();
+        let error = IpcServer::bind(&path).err().unwrap().to_string();
+        assert!(error.ends_with("is not a socket"), "{}", error);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "notes");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,15 @@
+//! Pomodoro timer state machine and configuration
+//!
+//! The `rustdoro` binary builds its terminal UI on top of these modules, and
+//! other frontends can drive [`timer::Timer`] the same way: call
+//! [`timer::Timer::tick`] regularly and react to the session it reports.
+
+pub mod config;
+pub mod heatmap;
+pub mod history;
+pub mod notifications;
+pub mod state;
+pub mod status;
+pub mod tasks;
+pub mod timer;
+pub mod version;
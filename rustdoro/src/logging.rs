@@ -0,0 +1,56 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+
+/// Get the directory log files are written to
+pub fn log_dir() -> Result<PathBuf> {
+    let mut path: PathBuf = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+    path.push("rustdoro");
+    path.push("logs");
+    Ok(path)
+}
+
+/// Map the number of `-v` flags to the maximum log level
+pub fn level_for_verbosity(verbosity: u8) -> Level {
+    match verbosity {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Initialize logging to a daily rotated file under the config directory
+/// The returned guard flushes pending log lines when dropped, so keep it alive until exit.
+pub fn init_logging(verbosity: u8) -> Result<WorkerGuard> {
+    let dir = log_dir()?;
+    std::fs::create_dir_all(&dir)?;
+
+    let appender = tracing_appender::rolling::daily(&dir, "rustdoro.log");
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(level_for_verbosity(verbosity))
+        .try_init()
+        .map_err(|e| anyhow::anyhow!("Failed to initialize logging: {}", e))?;
+
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_for_verbosity() {
+        assert_eq!(level_for_verbosity(0), Level::WARN);
+        assert_eq!(level_for_verbosity(1), Level::INFO);
+        assert_eq!(level_for_verbosity(2), Level::DEBUG);
+        assert_eq!(level_for_verbosity(3), Level::TRACE);
+        assert_eq!(level_for_verbosity(200), Level::TRACE);
+    }
+}
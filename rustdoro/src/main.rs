@@ -1,56 +1,240 @@
-mod config;
-mod timer;
+mod hooks;
+mod logging;
 mod ui;
-mod notifications;
+#[cfg(feature = "discord")]
+mod presence;
+#[cfg(feature = "webhooks")]
+mod webhooks;
+#[cfg(all(feature = "ipc", unix))]
+mod ipc;
+
+use rustdoro::{config, heatmap, history, notifications, state, status, tasks, timer};
 
 use anyhow::Result;
-use clap::Parser;
-use std::time::Duration;
+use clap::{Parser, ValueEnum};
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use tokio::time::interval;
 
-use config::{CliArgs, Config};
-use timer::{SessionType, Timer};
-use ui::AppUI;
-use notifications::NotificationManager;
+use config::{CliArgs, Config, ConfigSource, MessagesConfig, NotificationsConfig};
+use heatmap::Heatmap;
+use history::{History, HistoryRecord, Today};
+use hooks::Hooks;
+use state::DailyGoal;
+use timer::{minutes_to_duration, SessionType, Timer};
+use ui::{AppUI, RunSummary};
+use notifications::{DesktopNotification, NotificationEvent, NotificationManager, SoundKind, ToneFormat};
+#[cfg(feature = "discord")]
+use presence::DiscordPresence;
+#[cfg(feature = "webhooks")]
+use webhooks::Webhooks;
+#[cfg(all(feature = "ipc", unix))]
+use ipc::{Command, IpcRequest, IpcServer};
+
+/// Builds without the control socket never have a server or a request
+#[cfg(not(all(feature = "ipc", unix)))]
+type IpcServer = std::convert::Infallible;
+#[cfg(not(all(feature = "ipc", unix)))]
+type IpcRequest = std::convert::Infallible;
 
 /// Main application structure
 struct App {
     timer: Timer,
     ui: AppUI,
     notifications: NotificationManager,
+    history: History,
     last_session_type: SessionType,
-    show_completion_message: bool,
+    /// Message for the completion popup, shown until the next input
+    completion_message: Option<String>,
+    /// Whether the completion popup should announce the reached goal
+    show_goal_message: bool,
+    /// Whether to quit once the break after the goal has finished
+    quit_on_goal: bool,
+    /// Configured completion messages
+    messages: MessagesConfig,
+    /// Whether to ask for a note after each completed work session
+    prompt_notes: bool,
+    /// Work session record waiting for its note before it is saved
+    pending_record: Option<HistoryRecord>,
+    /// File kept up to date with a one-line status
+    status_file: Option<PathBuf>,
+    /// Template for the status line
+    status_format: String,
+    /// Last line written to the status file
+    last_status: Option<String>,
+    /// Whether the last status file write failed
+    status_failing: bool,
+    /// Config file that a volume changed in the app is saved to on exit
+    save_volume_to: Option<PathBuf>,
+    /// Volume last set in the app, if it was changed
+    changed_volume: Option<f32>,
+    /// Snoozing of the end alarm for the session that just ended
+    snooze: Snooze,
+    /// How long a snooze holds off the end alarm
+    snooze_length: Duration,
+    /// Snoozes allowed per ended session
+    max_snoozes: u32,
+    /// Work sessions run back to back with `--sessions`
+    run: Option<SessionRun>,
+    /// Whether the next session starts on its own once the loop has recorded the last one
+    start_next: bool,
+    /// Whether an ended session waits for Enter before the next one starts
+    confirm_transitions: bool,
+    /// How long the prompt waits before a `--sessions` run goes on by itself
+    confirm_timeout: Duration,
+    /// Whether the loop should ask before the next session once it has recorded the last one
+    prompt_next: bool,
+    /// Breaks skipped in a row before the reminder is shown, 0 if it is off
+    break_nag_after: u32,
+    /// Skipped breaks seen by the last reminder check
+    last_break_skips: u32,
+    /// Control socket, handed to the main loop when it starts
+    ipc: Option<IpcServer>,
+    #[cfg(feature = "discord")]
+    presence: Option<DiscordPresence>,
+    #[cfg(feature = "webhooks")]
+    webhooks: Option<Webhooks>,
+    /// Shell commands run on session events
+    hooks: Option<Hooks>,
+    /// Urgency and timeout of desktop notifications, and whether sessions show them
+    desktop_notifications: NotificationsConfig,
+    /// Day the daily counts are shown for
+    today: Today,
+    /// Pomodoros to complete each day, and whether that was celebrated today
+    daily_goal: DailyGoal,
+    /// Seconds recorded work time is rounded to for reporting, 0 if it isn't
+    rounding_secs: u64,
 }
 
 impl App {
     /// Create a new application instance
-    fn new(config: Config) -> Result<Self> {
-        let timer = Timer::new(config.clone());
-        let ui = AppUI::new(config.hide_clock())?;
+    fn new(config: Config, config_source: &ConfigSource) -> Result<Self> {
+        let timer = launch_timer(config.clone());
+        // Set up audio before the UI takes over the terminal so warnings stay visible
         let notifications = NotificationManager::new(config.clone())?;
+        #[cfg(feature = "discord")]
+        let presence = create_discord_presence(&config);
+        #[cfg(not(feature = "discord"))]
+        if config.general.discord {
+            tracing::warn!("Discord presence requested but rustdoro was built without the `discord` feature");
+        }
+        #[cfg(feature = "webhooks")]
+        let webhooks = Webhooks::new(config.webhooks.clone());
+        #[cfg(not(feature = "webhooks"))]
+        if !config.webhooks.is_empty() {
+            tracing::warn!("Webhooks configured but rustdoro was built without the `webhooks` feature");
+        }
+        let ipc = create_ipc_server(&config);
+        let history = History::load_default();
+        let mut ui = AppUI::new(&config)?;
+        let daily_goal = DailyGoal::load_default(u64::from(config.time.daily_goal));
+        ui.set_goal_met(daily_goal.is_met(chrono::Local::now().date_naive()));
         let last_session_type = timer.get_session_type();
+        let save_volume_to = if config.audio.save_volume_on_exit {
+            config_source.path().cloned().or_else(|| Config::default_config_path().ok())
+        } else {
+            None
+        };
 
         Ok(Self {
             timer,
             ui,
             notifications,
+            history,
             last_session_type,
-            show_completion_message: false,
+            completion_message: None,
+            show_goal_message: false,
+            quit_on_goal: config.general.quit_on_goal,
+            status_file: config.general.status_file.as_ref().map(PathBuf::from),
+            status_format: config.general.status_format.clone(),
+            last_status: None,
+            messages: config.messages.clone(),
+            prompt_notes: config.general.prompt_notes,
+            pending_record: None,
+            status_failing: false,
+            save_volume_to,
+            changed_volume: None,
+            snooze: Snooze::default(),
+            snooze_length: minutes_to_duration(config.audio.snooze_minutes),
+            max_snoozes: config.audio.max_snoozes,
+            run: None,
+            start_next: false,
+            confirm_transitions: config.general.confirm_transitions,
+            confirm_timeout: Duration::from_secs(config.general.confirm_timeout_secs),
+            prompt_next: false,
+            break_nag_after: config.general.break_nag_after,
+            last_break_skips: 0,
+            ipc,
+            #[cfg(feature = "discord")]
+            presence,
+            #[cfg(feature = "webhooks")]
+            webhooks,
+            hooks: Hooks::new(config.hooks.clone()),
+            desktop_notifications: config.notifications.clone(),
+            today: Today::new(chrono::Local::now()),
+            daily_goal,
+            rounding_secs: minutes_to_duration(config.time.rounding_minutes).as_secs(),
         })
     }
 
     /// Run the main application loop
     async fn run(&mut self) -> Result<()> {
         let mut tick_interval = interval(Duration::from_secs(1));
+        // Owned by the loop so waiting for a command doesn't hold on to `self`,
+        // the socket file is removed when it goes out of scope
+        let mut ipc = self.ipc.take();
         
         loop {
             tokio::select! {
                 // Handle timer ticks
                 _ = tick_interval.tick() => {
                     let session_completed = self.timer.tick();
+
+                    // Running past midnight starts the daily counts over
+                    if self.today.update(chrono::Local::now()) {
+                        tracing::info!("A new day has started: {}", self.today.date());
+                        self.ui.set_today(self.today.date());
+                        self.ui.set_goal_met(self.daily_goal.is_met(self.today.date()));
+                    }
+
+                    // Stop an unattended end alarm after `alarm_seconds`
+                    self.notifications.stop_alarm_if_expired(Instant::now());
+
+                    // Ring again once a snooze runs out
+                    if self.snooze.take_expired(Instant::now()) {
+                        if let Err(e) = self.notifications.play_end_sound() {
+                            tracing::warn!("Failed to play end sound: {}", e);
+                        }
+                    }
+
+                    // Stop counting work time once the user has walked away
+                    self.ui.pause_if_idle(&mut self.timer, Instant::now());
+
+                    // A --sessions run doesn't wait for Enter forever
+                    self.ui.start_if_prompt_expired(&mut self.timer, Instant::now());
                     
+                    if self.timer.take_overtime_started() {
+                        // A short cue rather than the end alarm, which could ring until a key is pressed
+                        if let Err(e) = self.notifications.play_break_start_sound() {
+                            tracing::warn!("Failed to play overtime sound: {}", e);
+                        }
+                    }
+
+                    if self.timer.take_low_time_warning() {
+                        if let Err(e) = self.notifications.play_low_time_warning() {
+                            tracing::warn!("Failed to play low-time warning: {}", e);
+                        }
+                    }
+
                     if session_completed {
                         self.handle_session_completion().await?;
+                    } else if self.timer.is_running() && self.timer.get_session_type() == SessionType::Work {
+                        // The tick stops with the session so it never sounds over the end alarm
+                        let worked = self.timer.get_net_work_duration();
+                        if let Err(e) = self.notifications.play_tick_if_due(worked) {
+                            tracing::warn!("Failed to play tick sound: {}", e);
+                        }
                     }
                     
                     // Check if session type changed (for notifications)
@@ -64,29 +248,119 @@ impl App {
                 // Handle user input (non-blocking)
                 _ = async {
                     // Handle input synchronously for now
-                    if let Ok(input_handled) = self.ui.handle_input(&mut self.timer) {
+                    // Quitting is picked up through `should_quit` after the select
+                    if self.ui.handle_input(&mut self.timer).is_ok() {
                         // Stop audio when user interacts with timer controls
                         if self.ui.should_stop_audio_on_input() {
-                            self.notifications.stop_audio();
-                            // Hide completion message when user starts interacting
-                            self.show_completion_message = false;
-                        }
-                        
-                        if input_handled {
-                            return;
+                            self.dismiss_alarm();
                         }
                     }
                 } => {}
+
+                // Handle commands from the control socket
+                Some(request) = next_ipc_request(&mut ipc) => {
+                    self.handle_ipc_request(request);
+                }
+            }
+
+            // Only the completion popup can be snoozed
+            if self.ui.take_snooze_request() && self.completion_message.is_some() {
+                if self.snooze.start(Instant::now(), self.snooze_length, self.max_snoozes) {
+                    self.notifications.stop_audio();
+                } else {
+                    tracing::info!("No snoozes left for this session");
+                }
+            }
+
+            if self.notifications.take_audio_lost() {
+                self.show_audio_lost_notice();
+            }
+
+            if let Some(delta) = self.ui.take_count_adjustment() {
+                let mut record = HistoryRecord::adjustment(chrono::Local::now(), delta);
+                record.tag = self.timer.tag().map(str::to_string);
+                self.save_record(record);
+            }
+
+            if let Some(muted) = self.ui.take_mute_change() {
+                self.notifications.set_muted(muted);
+            }
+
+            if let Some(volume) = self.ui.take_volume_change() {
+                self.notifications.set_volume(volume);
+                self.changed_volume = Some(volume);
+            }
+
+            if self.timer.take_plan_finished() {
+                self.completion_message = Some("🏁 Plan complete — press R to start over".to_string());
+            }
+
+            if let Some(record) = undone_work_record(&mut self.timer, chrono::Local::now()) {
+                self.save_record(record);
+            }
+            // Work sessions can be counted by a tick or by skipping
+            if self.timer.take_completed_work() {
+                self.record_completed_work();
+            }
+            if self.timer.take_queue_finished() {
+                self.show_notice(format!(
+                    "📋 Task list done, back to {} minute work sessions",
+                    self.timer.work_duration.as_secs() / 60
+                ));
+            }
+            if self.timer.take_estimate_reached() {
+                self.show_estimate_notice();
+            }
+            // A --sessions run keeps going on its own until the last work session
+            let run_finished = self.run.as_ref().is_some_and(SessionRun::is_finished);
+            let auto_start = std::mem::take(&mut self.start_next) && !run_finished;
+            if std::mem::take(&mut self.prompt_next) && !run_finished {
+                let deadline = auto_start.then(|| Instant::now() + self.confirm_timeout);
+                self.ui.show_transition_prompt(deadline);
+            } else if auto_start {
+                self.timer.start();
+            }
+            self.check_break_nag();
+            if let Some(note) = self.ui.take_submitted_note() {
+                if !note.is_empty() {
+                    self.ui.push_toast("📝 Note saved");
+                }
+                self.save_pending_record(note);
             }
 
             // Update UI focus based on timer state
             self.ui.update_focus_based_on_timer_state(&self.timer);
+
+            // Reflect session transitions and pauses in Discord
+            #[cfg(feature = "discord")]
+            if let Some(presence) = &mut self.presence {
+                presence.update(&self.timer);
+            }
             
-            // Draw the UI
-            self.ui.draw(&self.timer, self.show_completion_message)?;
+            self.update_status_file();
+
+            // Draw the UI, without the toasts whose time is up
+            self.ui.expire_toasts(Instant::now());
+            let message = self.completion_message.as_ref().map(|message| {
+                match self.snooze.remaining(Instant::now()) {
+                    Some(left) => format!(
+                        "{} (snoozed, rings again in {}:{:02})",
+                        message,
+                        left.as_secs() / 60,
+                        left.as_secs() % 60
+                    ),
+                    None => message.clone(),
+                }
+            });
+            self.ui.draw(
+                &self.timer,
+                &self.history,
+                message.as_deref(),
+                self.show_goal_message,
+            )?;
 
             // Check if we should quit
-            if self.ui.should_quit {
+            if self.ui.should_quit || self.goal_run_finished() {
                 break;
             }
 
@@ -94,18 +368,60 @@ impl App {
             tokio::time::sleep(Duration::from_millis(16)).await;
         }
 
+        // Keep a session whose note prompt was still open
+        if self.pending_record.is_some() {
+            self.save_pending_record(String::new());
+        }
+
+        let (drawn, skipped) = self.ui.frame_counts();
+        tracing::debug!("Drew {} frames, skipped {} unchanged ones", drawn, skipped);
+
+        if let (Some(path), Some(volume)) = (&self.save_volume_to, self.changed_volume) {
+            if let Err(e) = Config::save_volume(path, volume) {
+                tracing::warn!("Failed to save volume to {}: {}", path.display(), e);
+            }
+        }
+
         Ok(())
     }
 
     /// Handle session completion
     async fn handle_session_completion(&mut self) -> Result<()> {
+        tracing::info!(
+            "Session completed, next up: {} ({} pomodoros so far)",
+            self.timer.labels().label(self.timer.get_session_type()),
+            self.timer.get_pomodoros_completed()
+        );
+
+        // Each ended session gets its own snoozes
+        self.snooze.clear();
+        self.start_next = self.run.is_some();
+        self.prompt_next = self.confirm_transitions;
+
         // Play session end sound continuously until user interaction
         if let Err(e) = self.notifications.play_end_sound() {
-            eprintln!("Warning: Failed to play end sound: {}", e);
+            tracing::warn!("Failed to play end sound: {}", e);
         }
 
-        // Show completion message in UI
-        self.show_completion_message = true;
+        // Show the message for the session that just ended
+        let ended = self.timer.last_ended_session().unwrap_or(SessionType::Work);
+        #[cfg(feature = "webhooks")]
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.session_completed(ended, self.timer.get_pomodoros_completed());
+        }
+        if let Some(hooks) = &self.hooks {
+            hooks.session_completed(ended, self.timer.get_pomodoros_completed());
+        }
+        let message = self
+            .messages
+            .completion_message(ended, self.timer.get_pomodoros_completed());
+        if self.desktop_notifications.sessions {
+            self.notify_desktop(NotificationEvent::Complete, &message);
+        }
+        self.completion_message = Some(message);
+        if self.timer.take_goal_reached() {
+            self.show_goal_message = true;
+        }
         
         // Note: Audio will continue playing until user interacts with the timer
         // The audio stopping is handled in the main loop when user input is detected
@@ -113,17 +429,235 @@ impl App {
         Ok(())
     }
 
+    /// Show a desktop notification for `event` with its configured urgency
+    fn notify_desktop(&self, event: NotificationEvent, message: &str) {
+        DesktopNotification::new(&self.desktop_notifications, event, "Rustdoro", message).send();
+    }
+
+    /// Let the user know the task has used up its estimate
+    fn show_estimate_notice(&mut self) {
+        let Some(task) = self.timer.task() else {
+            return;
+        };
+        let notice = format!(
+            "🎯 {} has reached its estimate of {} pomodoros",
+            task.name, task.estimate
+        );
+        tracing::info!("{}", notice);
+        self.show_notice(notice);
+    }
+
+    /// Pause and tell the user once the audio output is gone for good
+    /// Otherwise the end of the session would pass without a sound.
+    fn show_audio_lost_notice(&mut self) {
+        self.timer.pause();
+        self.show_notice("🔇 Lost the audio output, sounds are off. Press P to continue.".to_string());
+    }
+
+    /// Show a notice in the message popup, below any message already there
+    fn show_notice(&mut self, notice: String) {
+        self.completion_message = Some(match self.completion_message.take() {
+            Some(message) => format!("{}\n\n{}", message, notice),
+            None => notice,
+        });
+    }
+
+    /// Remind the user to take a break for every skip past the threshold
+    fn check_break_nag(&mut self) {
+        let skips = self.timer.get_consecutive_break_skips();
+        if skips == self.last_break_skips {
+            return;
+        }
+        self.last_break_skips = skips;
+        if self.break_nag_after > 0 && skips >= self.break_nag_after {
+            self.ui.show_break_nag(skips);
+        }
+    }
+
+    /// Stop the alarm and hide the completion message once the user acts
+    fn dismiss_alarm(&mut self) {
+        self.notifications.stop_audio();
+        self.completion_message = None;
+        self.show_goal_message = false;
+        self.snooze.clear();
+    }
+
+    /// Carry out a command from the control socket and answer it
+    /// Focus lock and strict break mode apply just like they do to keys.
+    #[cfg(all(feature = "ipc", unix))]
+    fn handle_ipc_request(&mut self, request: IpcRequest) {
+        let locked = self.ui.is_focus_locked(&self.timer) || self.ui.is_break_locked(&self.timer);
+        let reply = match request.command {
+            Command::Status => ipc::status_json(&self.timer),
+            Command::Snapshot => self.timer.to_json().unwrap_or_else(|e| format!("ERR {}", e)),
+            Command::Pause if self.ui.is_break_locked(&self.timer) => {
+                "ERR pausing is locked during breaks".to_string()
+            }
+            Command::Pause => {
+                self.timer.pause();
+                "OK".to_string()
+            }
+            Command::Resume => {
+                if !self.timer.is_running() {
+                    self.timer.start();
+                }
+                self.dismiss_alarm();
+                "OK".to_string()
+            }
+            Command::Skip if locked => "ERR skipping is locked right now".to_string(),
+            Command::Skip => {
+                self.timer.skip_session();
+                self.dismiss_alarm();
+                "OK".to_string()
+            }
+        };
+        request.respond(reply);
+    }
+
+    #[cfg(not(all(feature = "ipc", unix)))]
+    fn handle_ipc_request(&mut self, request: IpcRequest) {
+        match request {}
+    }
+
+    /// Rewrite the status file when the status line changes
+    fn update_status_file(&mut self) {
+        let Some(path) = &self.status_file else {
+            return;
+        };
+
+        let line = status::format_status(&self.status_format, &self.timer);
+        if self.last_status.as_ref() == Some(&line) {
+            return;
+        }
+        match status::write_status_file(path, &line) {
+            Ok(()) => self.status_failing = false,
+            Err(e) => {
+                // Warn once per failure streak rather than on every tick
+                if !self.status_failing {
+                    tracing::warn!("Failed to write status file {}: {}", path.display(), e);
+                }
+                self.status_failing = true;
+            }
+        }
+        self.last_status = Some(line);
+    }
+
+    /// Append the work session that just ended to the history file
+    fn record_completed_work(&mut self) {
+        // Count-up sessions keep their elapsed time until the break ends
+        let worked = if self.timer.is_count_up_mode() {
+            self.timer.get_elapsed()
+        } else {
+            self.timer.last_ended_length().unwrap_or(self.timer.work_duration)
+        };
+        let net = self.timer.get_net_work_duration();
+        let mut record = HistoryRecord::new(chrono::Local::now(), worked.as_secs() / 60);
+        record.net_secs = Some(net.as_secs());
+        if self.rounding_secs > 0 {
+            record.rounded_secs = Some(history::round_to_increment(net.as_secs(), self.rounding_secs));
+        }
+        record.task = self.timer.last_completed_task().map(str::to_string);
+        record.tag = self.timer.tag().map(str::to_string);
+        let overtime = self.timer.last_overtime();
+        record.overtime_secs = (!overtime.is_zero()).then_some(overtime.as_secs());
+
+        if let Some(run) = &mut self.run {
+            if run.record(net) {
+                self.ui.show_summary(RunSummary {
+                    pomodoros: run.completed,
+                    focus: run.focus,
+                    interruptions: self.timer.get_interruptions(),
+                });
+            }
+        }
+
+        if self.prompt_notes {
+            // Save a still-pending record before prompting for the next one
+            if self.pending_record.is_some() {
+                self.save_pending_record(String::new());
+            }
+            self.pending_record = Some(record);
+            self.ui.open_note_prompt();
+        } else {
+            self.save_record(record);
+        }
+    }
+
+    /// Attach the note to the record waiting for it and save it
+    fn save_pending_record(&mut self, note: String) {
+        if let Some(mut record) = self.pending_record.take() {
+            record.note = Some(note);
+            self.save_record(record);
+        }
+    }
+
+    /// Append a record to the history file
+    fn save_record(&mut self, record: HistoryRecord) {
+        if let Err(e) = self.history.record(record) {
+            tracing::warn!("Failed to save session history: {}", e);
+        }
+        self.check_daily_goal();
+    }
+
+    /// Celebrate the daily goal the first time today's count reaches it
+    fn check_daily_goal(&mut self) {
+        let today = self.today.date();
+        let count = self.history.daily_counts(today, 1).iter().map(|day| day.pomodoros).sum();
+        if self.daily_goal.check(today, count) {
+            let message = format!("🎉 Daily goal met: {} pomodoros today!", count);
+            self.notify_desktop(NotificationEvent::DailyGoal, &message);
+            self.show_notice(message);
+        }
+        self.ui.set_goal_met(self.daily_goal.is_met(today));
+    }
+
+    /// Check if the break following the final target pomodoro is over
+    fn goal_run_finished(&self) -> bool {
+        self.quit_on_goal
+            && self.timer.is_goal_reached()
+            && self.timer.get_session_type() == SessionType::Work
+    }
+
+    /// Announce a session that is already running at launch
+    /// Only changes of the session type are picked up by the loop, so the first
+    /// session would otherwise start without its sound.
+    async fn announce_launch(&mut self) -> Result<()> {
+        if !self.timer.is_running() {
+            return Ok(());
+        }
+        self.ui.update_focus_based_on_timer_state(&self.timer);
+        self.handle_session_start(self.timer.get_session_type()).await
+    }
+
     /// Handle session start
     async fn handle_session_start(&mut self, session_type: SessionType) -> Result<()> {
+        tracing::info!("{} session started", self.timer.labels().label(session_type));
+        #[cfg(feature = "webhooks")]
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.session_started(session_type, self.timer.get_pomodoros_completed());
+        }
+        if let Some(hooks) = &self.hooks {
+            hooks.session_started(session_type, self.timer.get_pomodoros_completed());
+        }
+
+        if self.desktop_notifications.sessions {
+            let event = match session_type {
+                SessionType::Work => NotificationEvent::WorkStart,
+                SessionType::ShortBreak | SessionType::LongBreak => NotificationEvent::BreakStart,
+            };
+            let message = format!("{} started", self.timer.labels().label(session_type));
+            self.notify_desktop(event, &message);
+        }
+
         match session_type {
             SessionType::Work => {
                 if let Err(e) = self.notifications.play_work_start_sound() {
-                    eprintln!("Warning: Failed to play work start sound: {}", e);
+                    tracing::warn!("Failed to play work start sound: {}", e);
                 }
             }
             SessionType::ShortBreak | SessionType::LongBreak => {
                 if let Err(e) = self.notifications.play_break_start_sound() {
-                    eprintln!("Warning: Failed to play break start sound: {}", e);
+                    tracing::warn!("Failed to play break start sound: {}", e);
                 }
             }
         }
@@ -132,11 +666,134 @@ impl App {
     }
 }
 
+/// Fail with a readable message when standard output is not a terminal
+fn require_terminal(stdout_is_terminal: bool) -> Result<()> {
+    if stdout_is_terminal {
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "rustdoro needs an interactive terminal, but standard output is not a TTY.\n\
+         Run it directly in a terminal instead of piping its output. \
+         --dry-run, --preview-sound and --export-sound work without one."
+    ))
+}
+
+/// Build the record that takes back a work session undone after it was recorded
+fn undone_work_record(timer: &mut Timer, now: chrono::DateTime<chrono::Local>) -> Option<HistoryRecord> {
+    if !timer.take_undone_work() {
+        return None;
+    }
+    let mut record = HistoryRecord::adjustment(now, -1);
+    record.tag = timer.tag().map(str::to_string);
+    Some(record)
+}
+
+/// Create the timer, already running if the config asks to start on launch
+fn launch_timer(config: Config) -> Timer {
+    let start = config.general.start_on_launch;
+    let mut timer = Timer::new(config);
+    if start {
+        timer.start();
+    }
+    timer
+}
+
+/// Progress of a `--sessions` run
+#[derive(Debug)]
+struct SessionRun {
+    /// Work sessions to run
+    limit: u32,
+    /// Work sessions completed so far
+    completed: u32,
+    /// Net work time of the completed sessions
+    focus: Duration,
+}
+
+impl SessionRun {
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            completed: 0,
+            focus: Duration::ZERO,
+        }
+    }
+
+    /// Count a completed work session
+    /// Returns true for the session that reaches the limit.
+    fn record(&mut self, net: Duration) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.completed += 1;
+        self.focus += net;
+        self.is_finished()
+    }
+
+    /// Check if all work sessions of the run are done
+    fn is_finished(&self) -> bool {
+        self.completed >= self.limit
+    }
+}
+
+/// Snooze state of the end alarm
+/// Snoozing only delays the alarm: the ended session has already been counted and
+/// the next one still waits to be started.
+#[derive(Debug, Default)]
+struct Snooze {
+    /// When the snoozed alarm rings again
+    until: Option<Instant>,
+    /// Snoozes used since the session ended
+    used: u32,
+}
+
+impl Snooze {
+    /// Hold off the alarm for `length`, unless `max` snoozes were already used
+    /// Returns true if the alarm was snoozed.
+    fn start(&mut self, now: Instant, length: Duration, max: u32) -> bool {
+        if self.used >= max {
+            return false;
+        }
+        self.used += 1;
+        self.until = Some(now + length);
+        true
+    }
+
+    /// Return true once when a snooze has run out
+    fn take_expired(&mut self, now: Instant) -> bool {
+        match self.until {
+            Some(until) if now >= until => {
+                self.until = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Time left on a running snooze
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.until.map(|until| until.saturating_duration_since(now))
+    }
+
+    /// Drop a running snooze and give back the used snoozes
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
 /// Main function
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command line arguments
     let args = CliArgs::parse();
+
+    // Start logging before the UI takes over the terminal
+    let _log_guard = match logging::init_logging(args.verbose) {
+        Ok(guard) => Some(guard),
+        Err(e) => {
+            eprintln!("Warning: Logging is disabled: {}", e);
+            None
+        }
+    };
     
     // Handle config file generation if requested
     if args.generate_config {
@@ -153,36 +810,123 @@ async fn main() -> Result<()> {
         }
     }
     
+    if args.clear_history {
+        run_clear_history(args.yes);
+    }
+    if let Some(path) = &args.heatmap {
+        run_export_heatmap(path);
+    }
+    if args.list_audio_devices {
+        run_list_audio_devices();
+    }
+
+    if args.dry_run {
+        let explicit_path = args.config_path.is_some() || args.config_stdin;
+        let (mut config, config_source) = Config::resolve_from_cli_args(args);
+        let mut problems = config.validate();
+        if explicit_path && config_source == ConfigSource::DefaultsOnly {
+            problems.insert(0, "The config given with --path or --config-stdin could not be read".to_string());
+        }
+        print_dry_run(&config, &config_source, &problems);
+        std::process::exit(if problems.is_empty() { 0 } else { 1 });
+    }
+
     // Create configuration from CLI arguments with config file support
-    let config = Config::load_from_cli_args_with_config(args);
+    let quiet = args.quiet;
+    let preview_sound = args.preview_sound;
+    let test_sound = args.test_sound;
+    let sessions = args.sessions;
+    let until = args.until;
+    let until_today = args.until_today;
+    let export_sound = args.export_sound.clone();
+    let tasks_path = args.tasks.clone();
+    let once = args.once;
+    let (config, config_source) = Config::load_from_cli_args_with_config(args);
+    tracing::info!("{}", config_source.describe());
+
+    if test_sound {
+        run_test_sound(config);
+    }
+    if let Some(kind) = preview_sound {
+        run_preview_sound(kind, &config);
+        return Ok(());
+    }
+    if let Some(export) = export_sound {
+        run_export_sound(&export, &config);
+        return Ok(());
+    }
     
-    // Print welcome message and current configuration
-    println!("🍅 Welcome to Rustdoro - A Terminal Pomodoro Timer");
-    println!("Configuration:");
-    println!("  Work session: {} minutes", config.work_duration_minutes());
-    println!("  Short break: {} minutes", config.short_break_duration_minutes());
-    println!("  Long break: {} minutes", config.long_break_duration_minutes());
-    println!("  Long break after: {} pomodoros", config.long_break_after_pomodoros());
-    println!("  Sound enabled: {}", config.enable_sound());
-    println!("  Hide clock: {}", config.hide_clock());
-    if let Some(audio_file) = &config.audio.audio_file {
-        println!("  Custom audio file: {}", audio_file);
+    // Work out the first session's length before the banner so a bad time fails right away
+    let first_session = match until {
+        Some(until) => {
+            match config::duration_until(until, chrono::Local::now().naive_local(), until_today) {
+                Ok(length) => Some(length),
+                Err(e) => {
+                    eprintln!("Invalid --until: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let tasks = match tasks_path {
+        Some(path) => match tasks::load_task_list(&path) {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                eprintln!("Invalid --tasks: {:#}", e);
+                std::process::exit(1);
+            }
+        },
+        None => Vec::new(),
+    };
+
+    // The UI can't take over a pipe, so say so instead of failing inside crossterm
+    if let Err(e) = require_terminal(std::io::stdout().is_terminal()) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+
+    if let Some(minutes) = once {
+        return run_once(minutes_to_duration(minutes), config).await;
     }
-    println!("  Audio volume: {:.1}", config.audio.volume);
-    println!();
-    println!("Press 'h' or '?' for help once the application starts.");
-    println!("Starting in 2 seconds...\n");
-    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let startup_delay = config.startup_delay_secs();
+    if !quiet {
+        print_welcome_banner(&config, &config_source, startup_delay);
+    }
+    if startup_delay > 0 {
+        tokio::time::sleep(Duration::from_secs(startup_delay)).await;
+    }
+
+    tracing::debug!("Resolved configuration: {:?}", config);
 
     // Create and run the application
-    let mut app = App::new(config)?;
+    let mut app = App::new(config, &config_source)?;
+    if !tasks.is_empty() {
+        app.timer.set_task_queue(tasks);
+    }
+    if first_session.is_some() || sessions.is_some_and(|count| count > 0) {
+        // These runs start with work, so there is no planning break to take first
+        app.timer.skip_planning_break();
+    }
+    if let Some(length) = first_session {
+        // Counted from after the startup delay and started right away, so it ends on time
+        app.timer.set_session_length(length.saturating_sub(Duration::from_secs(startup_delay)));
+        app.timer.start();
+    }
+    if let Some(count) = sessions.filter(|&count| count > 0) {
+        app.run = Some(SessionRun::new(count));
+        app.timer.start();
+    }
+    app.announce_launch().await?;
     
     // Setup proper cleanup on exit
     let result = app.run().await;
     
     // Restore terminal state
     if let Err(e) = app.ui.restore_terminal() {
-        eprintln!("Warning: Failed to restore terminal: {}", e);
+        tracing::warn!("Failed to restore terminal: {}", e);
     }
 
     // Handle any errors that occurred during execution
@@ -201,8 +945,421 @@ async fn main() -> Result<()> {
 
 // Additional helper functions for better application structure
 
+/// Run a single countdown for --once, ring the end alarm and exit on the next key
+async fn run_once(length: Duration, mut config: Config) -> Result<()> {
+    config.labels.work = "Timer".to_string();
+    config.labels.work_emoji = "⏰".to_string();
+    let mut notifications = NotificationManager::new(config.clone())?;
+    let mut ui = AppUI::new(&config)?;
+    let mut timer = Timer::one_shot(config, length);
+    let history = History::default();
+    timer.start();
+
+    let mut tick_interval = interval(Duration::from_secs(1));
+    let mut message = None;
+    let result = loop {
+        tokio::select! {
+            _ = tick_interval.tick() => {
+                timer.tick();
+                notifications.stop_alarm_if_expired(Instant::now());
+            }
+            _ = async {
+                if let Err(e) = ui.handle_input(&mut timer) {
+                    tracing::warn!("Failed to read input: {}", e);
+                }
+            } => {}
+        }
+
+        // Running out and skipping both end the countdown
+        if timer.take_plan_finished() && message.is_none() {
+            if let Err(e) = notifications.play_end_sound() {
+                tracing::warn!("Failed to play end sound: {}", e);
+            }
+            message = Some("⏰ Time's up! Press any key to exit".to_string());
+            // Only keys pressed after the alarm end the run
+            ui.take_key_press();
+        }
+
+        if ui.take_key_press() && message.is_some() {
+            break Ok(());
+        }
+        if let Some(muted) = ui.take_mute_change() {
+            notifications.set_muted(muted);
+        }
+        if let Some(volume) = ui.take_volume_change() {
+            notifications.set_volume(volume);
+        }
+        ui.update_focus_based_on_timer_state(&timer);
+        if let Err(e) = ui.draw(&timer, &history, message.as_deref(), false) {
+            break Err(e);
+        }
+        if ui.should_quit {
+            break Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(16)).await;
+    };
+
+    notifications.stop_audio();
+    if let Err(e) = ui.restore_terminal() {
+        tracing::warn!("Failed to restore terminal: {}", e);
+    }
+    result
+}
+
+/// Longest time --test-sound lets each sound play
+const TEST_SOUND_SECONDS: u64 = 4;
+
+/// Play each notification sound with a prompt for --test-sound, then exit
+fn run_test_sound(mut config: Config) -> ! {
+    // The test is explicit, so it plays even with sounds turned off
+    let no_sound = std::mem::take(&mut config.general.no_sound);
+    let mut notifications = match NotificationManager::new(config) {
+        Ok(notifications) => notifications,
+        Err(e) => {
+            eprintln!("Failed to set up audio: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if !notifications.has_output_device() {
+        eprintln!("No audio output device is available, so Rustdoro can't play any sound.");
+        eprintln!("Check that your speakers or headphones are connected and not in use by another program.");
+        std::process::exit(1);
+    }
+
+    let sounds = [
+        (SoundKind::Work, "work start"),
+        (SoundKind::Break, "break start"),
+        (SoundKind::End, "session end"),
+    ];
+    let mut missed = Vec::new();
+    for (index, (kind, name)) in sounds.iter().enumerate() {
+        println!("Playing sound {} ({})...", index + 1, name);
+        let played = match kind {
+            SoundKind::Work => notifications.play_work_start_sound(),
+            SoundKind::Break => notifications.play_break_start_sound(),
+            SoundKind::End => notifications.play_end_sound(),
+        };
+        if let Err(e) = played {
+            eprintln!("Failed to play the {} sound: {}", name, e);
+            missed.push(*name);
+            continue;
+        }
+        // The end alarm loops, so every sound is cut off after a few seconds
+        let started = Instant::now();
+        while notifications.is_playing() && started.elapsed() < Duration::from_secs(TEST_SOUND_SECONDS) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        notifications.stop_audio();
+
+        if !ask(&format!("Did you hear sound {}? [Y/n] ", index + 1), true) {
+            missed.push(*name);
+        }
+    }
+
+    if missed.is_empty() {
+        println!("\nSound works.");
+    } else {
+        println!("\nNot heard: {}", missed.join(", "));
+        println!("Check the volume settings and `audio_file` in your config, and your system volume.");
+    }
+    if no_sound {
+        println!("Note: sound is turned off with `no_sound`, so sessions will stay silent.");
+    }
+    std::process::exit(if missed.is_empty() { 0 } else { 1 });
+}
+
+/// Ask a yes/no question on the console, using `default` for an empty answer
+/// Input that can't be read counts as no.
+fn ask(question: &str, default: bool) -> bool {
+    use std::io::Write;
+
+    print!("{}", question);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    is_yes(&answer, default)
+}
+
+/// Check if a console answer means yes
+fn is_yes(answer: &str, default: bool) -> bool {
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "" => default,
+        "y" | "yes" => true,
+        _ => false,
+    }
+}
+
+/// Delete the history file for --clear-history, then exit
+fn run_clear_history(yes: bool) -> ! {
+    let result = history::history_path().and_then(|path| {
+        let records = History::load(&path)?.record_count();
+        if records == 0 {
+            println!("No history to clear.");
+            return Ok(());
+        }
+
+        let question = format!("Delete {} records from {}? [y/N] ", records, path.display());
+        if !yes && !ask(&question, false) {
+            println!("History kept.");
+            return Ok(());
+        }
+        let removed = history::clear_history(&path)?;
+        println!("Removed {} records.", removed);
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        eprintln!("Failed to clear history: {}", e);
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+/// Days of history the heatmap covers
+const HEATMAP_DAYS: u64 = 365;
+
+/// Write the pomodoros per day as a heatmap for --heatmap, then exit
+fn run_export_heatmap(path: &std::path::Path) -> ! {
+    let history = History::load_default();
+    let counts = history.daily_counts(chrono::Local::now().date_naive(), HEATMAP_DAYS);
+    let heatmap = Heatmap::from_counts(&counts);
+    let svg = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+    let contents = if svg { heatmap.to_svg() } else { heatmap.to_text() };
+
+    if let Err(e) = std::fs::write(path, contents) {
+        eprintln!("Failed to write heatmap to {}: {}", path.display(), e);
+        std::process::exit(1);
+    }
+    println!("Wrote a heatmap of the last {} days to {}", HEATMAP_DAYS, path.display());
+    std::process::exit(0);
+}
+
+/// Print the audio output devices for --list-audio-devices, and exit
+fn run_list_audio_devices() -> ! {
+    match notifications::output_device_names() {
+        Ok(names) if names.is_empty() => println!("No audio output devices found"),
+        Ok(names) => {
+            for name in names {
+                println!("{}", name);
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to list audio devices: {}", e);
+            std::process::exit(1);
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Play a built-in sound once for --preview-sound
+fn run_preview_sound(kind: SoundKind, config: &Config) {
+    let format = ToneFormat::from_config(&config.audio);
+    let device = config.audio.audio_device.as_deref();
+    if let Err(e) = notifications::preview_sound(kind, format, config.audio.beep_count(kind), config.audio.volume_for(kind), device) {
+        eprintln!("Failed to play sound: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Write a built-in sound to a WAV file for --export-sound
+fn run_export_sound(export: &[String], config: &Config) {
+    let kind = match SoundKind::from_str(&export[0], true) {
+        Ok(kind) => kind,
+        Err(_) => {
+            eprintln!("Unknown sound {:?}, expected one of: work, break, end", export[0]);
+            std::process::exit(1);
+        }
+    };
+
+    let path = PathBuf::from(&export[1]);
+    let format = ToneFormat::from_config(&config.audio);
+    match notifications::export_sound_wav(kind, format, config.audio.beep_count(kind), &path) {
+        Ok(()) => println!("Exported {} sound to {}", export[0], path.display()),
+        Err(e) => {
+            eprintln!("Failed to export sound: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Create the Discord presence client if enabled in the configuration
+#[cfg(feature = "discord")]
+fn create_discord_presence(config: &Config) -> Option<DiscordPresence> {
+    if !config.general.discord {
+        return None;
+    }
+
+    match &config.general.discord_client_id {
+        Some(client_id) => Some(DiscordPresence::new(client_id)),
+        None => {
+            tracing::warn!("Discord presence needs `discord_client_id` in the [General] config section");
+            None
+        }
+    }
+}
+
+/// Open the control socket if one is configured
+#[cfg(all(feature = "ipc", unix))]
+fn create_ipc_server(config: &Config) -> Option<IpcServer> {
+    let path = config.general.ipc_socket.as_ref()?;
+    match IpcServer::bind(std::path::Path::new(path)) {
+        Ok(server) => Some(server),
+        Err(e) => {
+            tracing::warn!("Failed to open control socket {}: {}", path, e);
+            None
+        }
+    }
+}
+
+#[cfg(not(all(feature = "ipc", unix)))]
+fn create_ipc_server(config: &Config) -> Option<IpcServer> {
+    if config.general.ipc_socket.is_some() {
+        tracing::warn!("Control socket configured but rustdoro was built without the `ipc` feature");
+    }
+    None
+}
+
+/// Wait for the next control socket command, forever if there is no socket
+#[cfg(all(feature = "ipc", unix))]
+async fn next_ipc_request(server: &mut Option<IpcServer>) -> Option<IpcRequest> {
+    match server {
+        Some(server) => server.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(all(feature = "ipc", unix)))]
+async fn next_ipc_request(_server: &mut Option<IpcServer>) -> Option<IpcRequest> {
+    std::future::pending().await
+}
+
+/// Number of sessions listed by --dry-run
+const DRY_RUN_SESSIONS: usize = 8;
+
+/// Get the first `count` sessions of the plan with their length in minutes
+/// Count-up work sessions have no length. A plan that doesn't loop ends early.
+fn planned_sessions(config: &Config, count: usize) -> Vec<(SessionType, Option<u64>)> {
+    let plan = config.plan();
+    let count = if config.time.loop_plan { count } else { count.min(plan.len()) };
+
+    plan.iter()
+        .cycle()
+        .take(count)
+        .map(|step| {
+            let minutes = if config.count_up() && step.session == SessionType::Work {
+                None
+            } else {
+                Some(config.step_minutes(step))
+            };
+            (step.session, minutes)
+        })
+        .collect()
+}
+
+/// Print the resolved configuration and session plan for --dry-run
+fn print_dry_run(config: &Config, source: &ConfigSource, problems: &[String]) {
+    println!("{}", source.describe());
+    println!("Durations:");
+    println!("  Work session: {} minutes{}", config.work_duration_minutes(), if config.count_up() { " (counts up)" } else { "" });
+    println!("  Short break: {} minutes", config.short_break_duration_minutes());
+    println!("  Long break: {} minutes", config.long_break_duration_minutes());
+    println!("Cycle:");
+    if config.time.plan.is_empty() {
+        println!("  Long break after: {} pomodoros", config.long_break_after_pomodoros());
+    }
+    println!("  Plan length: {} sessions{}", config.plan().len(), if config.time.loop_plan { ", repeating" } else { "" });
+    println!("  Target: {}", match config.target_pomodoros() {
+        0 => "none".to_string(),
+        target => format!("{} pomodoros", target),
+    });
+    println!("Audio:");
+    println!("  Sound enabled: {}", config.enable_sound());
+    println!("  Volume: {:.2}", config.audio.volume);
+    println!("  Audio file: {}", config.audio.audio_file.as_deref().unwrap_or("built-in tones"));
+    if let Some(dir) = &config.audio.audio_dir {
+        println!("  Alarm directory: {} ({:?})", dir, config.audio.selection);
+    }
+    println!("  Alarm: {}", match config.audio.alarm_seconds {
+        0 => "loops until input".to_string(),
+        seconds => format!("{} seconds", seconds),
+    });
+    println!("Display:");
+    println!("  Progress style: {:?}", config.general.progress_style);
+    println!("  Art style: {:?}", config.general.art_style);
+    println!("  Hide clock: {}", config.hide_clock());
+
+    println!("First sessions:");
+    for (index, (session, minutes)) in planned_sessions(config, DRY_RUN_SESSIONS).iter().enumerate() {
+        match minutes {
+            Some(minutes) => println!("  {}. {} — {} min", index + 1, config.labels.label(*session), minutes),
+            None => println!("  {}. {} — open-ended", index + 1, config.labels.label(*session)),
+        }
+    }
+
+    if problems.is_empty() {
+        println!("\nConfiguration OK");
+    } else {
+        println!("\nConfiguration problems:");
+        for problem in problems {
+            println!("  - {}", problem);
+        }
+    }
+}
+
+/// Print welcome message and current configuration
+fn print_welcome_banner(config: &Config, source: &ConfigSource, startup_delay: u64) {
+    println!("🍅 Welcome to Rustdoro - A Terminal Pomodoro Timer");
+    println!("{}", source.describe());
+    for warning in config.unknown_key_warnings() {
+        println!("⚠️  {}", warning);
+    }
+    println!("Configuration:");
+    if config.count_up() {
+        println!("  Work session: open-ended (flowtime)");
+    } else {
+        println!("  Work session: {} minutes", config.work_duration_minutes());
+    }
+    println!("  Short break: {} minutes", config.short_break_duration_minutes());
+    println!("  Long break: {} minutes", config.long_break_duration_minutes());
+    if config.time.plan.is_empty() {
+        println!("  Long break after: {} pomodoros", config.long_break_after_pomodoros());
+    } else {
+        println!(
+            "  Plan: {} sessions{}",
+            config.time.plan.len(),
+            if config.time.loop_plan { ", repeating" } else { "" }
+        );
+    }
+    println!("  Sound enabled: {}", config.enable_sound());
+    println!("  Hide clock: {}", config.hide_clock());
+    if config.target_pomodoros() > 0 {
+        println!("  Target: {} pomodoros", config.target_pomodoros());
+    }
+    if let Some(audio_file) = &config.audio.audio_file {
+        println!("  Custom audio file: {}", audio_file);
+    }
+    println!("  Audio volume: {:.1}", config.audio.volume);
+    println!();
+    println!("Press 'h' or '?' for help once the application starts.");
+    if startup_delay > 0 {
+        println!("Starting in {} seconds...\n", startup_delay);
+    }
+}
+
 impl Drop for App {
     fn drop(&mut self) {
+        // Don't leave a stale status behind for status bars
+        if let Some(path) = &self.status_file {
+            let _ = std::fs::remove_file(path);
+        }
+
+        #[cfg(feature = "discord")]
+        if let Some(presence) = &mut self.presence {
+            presence.shutdown();
+        }
+
         // Ensure terminal is restored even if the app panics
         let _ = self.ui.restore_terminal();
     }
@@ -224,6 +1381,135 @@ mod tests {
         assert!(!config.general.no_clock);
     }
 
+    #[test]
+    fn test_require_terminal() {
+        assert!(require_terminal(true).is_ok());
+        let message = require_terminal(false).unwrap_err().to_string();
+        assert!(message.contains("not a TTY"));
+    }
+
+    #[test]
+    fn test_start_on_launch() {
+        let mut config = Config::default();
+        assert!(launch_timer(config.clone()).is_stopped());
+
+        config.general.start_on_launch = true;
+        let timer = launch_timer(config);
+        assert!(timer.is_running());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+    }
+
+    #[test]
+    fn test_planned_sessions() {
+        let mut config = Config::default();
+        config.time.tomatoes_per_set = 2;
+        let sessions = planned_sessions(&config, 5);
+        assert_eq!(
+            sessions,
+            vec![
+                (SessionType::Work, Some(25)),
+                (SessionType::ShortBreak, Some(5)),
+                (SessionType::Work, Some(25)),
+                (SessionType::LongBreak, Some(15)),
+                (SessionType::Work, Some(25)),
+            ]
+        );
+
+        config.time.loop_plan = false;
+        config.time.count_up = true;
+        let sessions = planned_sessions(&config, 8);
+        assert_eq!(sessions.len(), 4);
+        assert_eq!(sessions[0], (SessionType::Work, None));
+    }
+
+    #[test]
+    fn test_undone_work_is_taken_back_in_history() {
+        let now = chrono::Local::now();
+        let today_count = |history: &History| -> u64 {
+            history.daily_counts(now.date_naive(), 1).iter().map(|day| day.pomodoros).sum()
+        };
+        let mut history = History::default();
+        let mut timer = Timer::new(Config::default());
+
+        timer.start();
+        timer.set_remaining(Duration::ZERO);
+        timer.tick();
+        assert!(timer.take_completed_work());
+        history.record(HistoryRecord::new(now, 25)).unwrap();
+        assert_eq!(today_count(&history), 1);
+
+        assert!(timer.undo_last_transition());
+        history.record(undone_work_record(&mut timer, now).unwrap()).unwrap();
+        assert_eq!(today_count(&history), 0);
+        assert!(undone_work_record(&mut timer, now).is_none());
+
+        // An undo before the session was recorded has nothing to take back
+        timer.start();
+        timer.set_remaining(Duration::ZERO);
+        timer.tick();
+        assert!(timer.undo_last_transition());
+        assert!(!timer.take_completed_work());
+        assert!(undone_work_record(&mut timer, now).is_none());
+        assert_eq!(today_count(&history), 0);
+    }
+
+    #[test]
+    fn test_session_run_reaches_limit() {
+        let mut run = SessionRun::new(3);
+        assert!(!run.is_finished());
+        assert!(!run.record(Duration::from_secs(1500)));
+        assert!(!run.record(Duration::from_secs(1200)));
+        // Only the session that reaches the limit reports it
+        assert!(run.record(Duration::from_secs(1500)));
+        assert!(run.is_finished());
+        assert!(!run.record(Duration::from_secs(1500)));
+        assert_eq!(run.completed, 3);
+        assert_eq!(run.focus, Duration::from_secs(4200));
+
+        assert!(SessionRun::new(1).record(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_snooze_transitions() {
+        let start = Instant::now();
+        let length = Duration::from_secs(120);
+        let mut snooze = Snooze::default();
+        assert_eq!(snooze.remaining(start), None);
+        assert!(!snooze.take_expired(start + length));
+
+        assert!(snooze.start(start, length, 2));
+        assert_eq!(snooze.remaining(start + Duration::from_secs(30)), Some(Duration::from_secs(90)));
+        assert!(!snooze.take_expired(start + Duration::from_secs(119)));
+        // The alarm rings again exactly once
+        assert!(snooze.take_expired(start + length));
+        assert!(!snooze.take_expired(start + length * 2));
+        assert_eq!(snooze.remaining(start + length), None);
+
+        // The limit holds until the next session ends
+        let later = start + length;
+        assert!(snooze.start(later, length, 2));
+        assert!(!snooze.start(later, length, 2));
+        snooze.clear();
+        assert_eq!(snooze.remaining(later), None);
+        assert!(snooze.start(later, length, 2));
+
+        // Zero snoozes disables snoozing
+        assert!(!Snooze::default().start(start, length, 0));
+    }
+
+    #[test]
+    fn test_console_answers() {
+        for answer in ["\n", "y\n", "Yes", " YES "] {
+            assert!(is_yes(answer, true), "{:?}", answer);
+        }
+        for answer in ["n\n", "no", "nope", "maybe"] {
+            assert!(!is_yes(answer, true), "{:?}", answer);
+        }
+        // Destructive prompts default to no
+        assert!(!is_yes("\n", false));
+        assert!(is_yes("y", false));
+    }
+
     #[test]
     fn test_timer_creation() {
         let config = Config::default();
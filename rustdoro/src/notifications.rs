@@ -1,35 +1,379 @@
-use anyhow::Result;
-use rodio::{source::Source, Decoder, OutputStream, OutputStreamHandle, Sink};
+use anyhow::{Context, Result};
+use rodio::{
+    cpal::traits::HostTrait,
+    source::{ChannelVolume, Source},
+    Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, StreamError,
+};
 use std::fs::File;
-use std::io::BufReader;
-use std::time::Duration;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::sync::Arc;
-use crate::config::Config;
+use crate::config::{
+    AudioConfig, BuiltinSound, Config, NotificationUrgency, NotificationsConfig, SoundSelection, Urgency,
+};
+
+/// Bundled chime sample used by `default_sound = "chime"`
+const CHIME_WAV: &[u8] = include_bytes!("../assets/sounds/chime.wav");
+/// Bundled bell sample used by `default_sound = "bell"`
+const BELL_WAV: &[u8] = include_bytes!("../assets/sounds/bell.wav");
+
+/// Built-in generated notification sounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SoundKind {
+    /// Work session start beep
+    Work,
+    /// Break start beep
+    Break,
+    /// Two-tone session end alarm
+    End,
+}
+
+impl SoundKind {
+    /// Generate the interleaved samples for this sound in the given format, `count` times in a row
+    pub fn generate_samples(&self, format: ToneFormat, count: u32) -> Vec<i16> {
+        match self {
+            // Lower frequency for work
+            SoundKind::Work => repeat_with_gaps(&generate_beep_sound(600.0, 0.2, format), count, format),
+            // Higher frequency for break
+            SoundKind::Break => repeat_with_gaps(&generate_beep_sound(900.0, 0.2, format), count, format),
+            SoundKind::End => generate_notification_sound(format, count),
+        }
+    }
+
+    /// Stereo position of this sound with `spatial_cues`, from -1.0 (left) to 1.0 (right)
+    pub fn pan(&self) -> f32 {
+        match self {
+            SoundKind::Work => -SPATIAL_PAN,
+            SoundKind::Break => SPATIAL_PAN,
+            SoundKind::End => 0.0,
+        }
+    }
+}
+
+/// How far `spatial_cues` moves the start sounds off center
+const SPATIAL_PAN: f32 = 0.6;
+
+/// Left and right channel gains for a stereo position, keeping the near side at full volume
+fn pan_gains(pan: f32) -> (f32, f32) {
+    let pan = pan.clamp(-1.0, 1.0);
+    ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
+}
+
+/// Scale the left and right channels of an interleaved stereo buffer for a stereo position
+fn apply_pan(samples: &mut [i16], pan: f32) {
+    let (left, right) = pan_gains(pan);
+    for frame in samples.chunks_exact_mut(2) {
+        frame[0] = (frame[0] as f32 * left) as i16;
+        frame[1] = (frame[1] as f32 * right) as i16;
+    }
+}
+
+/// Generate a sound, panned in stereo when `spatial` is set and the sound has a position
+fn generate_positioned_samples(kind: SoundKind, format: ToneFormat, count: u32, spatial: bool) -> (Vec<i16>, ToneFormat) {
+    if !spatial || kind.pan() == 0.0 {
+        return (kind.generate_samples(format, count), format);
+    }
+    let stereo = ToneFormat { channels: 2, ..format };
+    let mut samples = kind.generate_samples(stereo, count);
+    apply_pan(&mut samples, kind.pan());
+    (samples, stereo)
+}
+
+/// Sample rate and channel layout of the generated tones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToneFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl Default for ToneFormat {
+    fn default() -> Self {
+        Self {
+            sample_rate: 44100,
+            channels: 1,
+        }
+    }
+}
+
+impl ToneFormat {
+    /// Read the tone format from the audio configuration
+    /// Zero values fall back to the mono 44.1kHz default.
+    pub fn from_config(audio: &AudioConfig) -> Self {
+        let default = Self::default();
+        Self {
+            sample_rate: if audio.sample_rate == 0 { default.sample_rate } else { audio.sample_rate },
+            channels: if audio.channels == 0 { default.channels } else { audio.channels },
+        }
+    }
+}
+
+/// Event a desktop notification is shown for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    WorkStart,
+    BreakStart,
+    Complete,
+    DailyGoal,
+}
+
+impl NotificationEvent {
+    /// Get the urgency the config gives this event
+    pub fn urgency(self, urgency: &NotificationUrgency) -> Urgency {
+        match self {
+            NotificationEvent::WorkStart => urgency.work_start,
+            NotificationEvent::BreakStart => urgency.break_start,
+            NotificationEvent::Complete => urgency.complete,
+            NotificationEvent::DailyGoal => urgency.daily_goal,
+        }
+    }
+}
+
+/// Desktop notification, shown through the platform's notification command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopNotification {
+    pub summary: String,
+    pub body: String,
+    pub urgency: Urgency,
+    /// How long it stays up, None leaving it to the desktop
+    pub timeout: Option<Duration>,
+}
+
+impl DesktopNotification {
+    /// Build the notification for `event` with its configured urgency and timeout
+    pub fn new(config: &NotificationsConfig, event: NotificationEvent, summary: &str, body: &str) -> Self {
+        Self {
+            summary: summary.to_string(),
+            body: body.to_string(),
+            urgency: event.urgency(&config.notification_urgency),
+            timeout: (config.timeout_ms > 0).then(|| Duration::from_millis(u64::from(config.timeout_ms))),
+        }
+    }
+
+    /// Get the arguments `notify-send` shows it with
+    fn notify_send_args(&self) -> Vec<String> {
+        let mut args = vec![format!("--urgency={}", self.urgency.name())];
+        if let Some(timeout) = self.timeout {
+            args.push(format!("--expire-time={}", timeout.as_millis()));
+        }
+        args.push(self.summary.clone());
+        args.push(self.body.clone());
+        args
+    }
+
+    /// Build the command that shows it here, if there is one
+    /// `osascript` has no urgency or timeout, so macOS ignores both.
+    fn command(&self) -> Option<std::process::Command> {
+        if cfg!(target_os = "macos") {
+            let script = format!("display notification {:?} with title {:?}", self.body, self.summary);
+            let mut command = std::process::Command::new("osascript");
+            command.arg("-e").arg(script);
+            Some(command)
+        } else if cfg!(unix) {
+            let mut command = std::process::Command::new("notify-send");
+            command.args(self.notify_send_args());
+            Some(command)
+        } else {
+            None
+        }
+    }
+
+    /// Show it without waiting, logging any failure
+    /// Uses `notify-send` on Linux and `osascript` on macOS; elsewhere it only logs.
+    pub fn send(&self) {
+        let Some(mut command) = self.command() else {
+            tracing::info!("Desktop notifications aren't supported here: {}", self.summary);
+            return;
+        };
+
+        let spawned = command
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+        match spawned {
+            // Reap it in the background so it doesn't linger once it exits
+            Ok(mut child) => {
+                std::thread::spawn(move || child.wait());
+            }
+            Err(e) => tracing::warn!("Failed to show desktop notification: {}", e),
+        }
+    }
+}
+
+/// Get the names of the available audio output devices
+pub fn output_device_names() -> Result<Vec<String>> {
+    let devices = rodio::cpal::default_host().output_devices()?;
+    Ok(devices.filter_map(|device| device.name().ok()).collect())
+}
+
+/// Find the device name that best matches `wanted`
+/// An exact name wins, then one that differs only in case, then the first that
+/// contains it, so `speakers` finds `Built-in Speakers`.
+fn match_device_name(names: &[String], wanted: &str) -> Option<usize> {
+    let wanted = wanted.trim();
+    if wanted.is_empty() {
+        return None;
+    }
+    let lower = wanted.to_lowercase();
+    names
+        .iter()
+        .position(|name| name == wanted)
+        .or_else(|| names.iter().position(|name| name.to_lowercase() == lower))
+        .or_else(|| names.iter().position(|name| name.to_lowercase().contains(&lower)))
+}
+
+/// Open the output device named `device`, or the default one without a name
+/// A name that matches no device, or a device that fails to open, falls back to
+/// the default device with a warning.
+fn open_output_stream(device: Option<&str>) -> std::result::Result<(OutputStream, OutputStreamHandle), StreamError> {
+    let Some(wanted) = device else {
+        return OutputStream::try_default();
+    };
+    let devices: Vec<rodio::Device> = match rodio::cpal::default_host().output_devices() {
+        Ok(devices) => devices.collect(),
+        Err(e) => {
+            tracing::warn!("Couldn't list audio devices, using the default one: {}", e);
+            return OutputStream::try_default();
+        }
+    };
+    let names: Vec<String> = devices.iter().map(|device| device.name().unwrap_or_default()).collect();
+    match match_device_name(&names, wanted) {
+        Some(index) => match OutputStream::try_from_device(&devices[index]) {
+            Ok(stream) => {
+                tracing::info!("Playing sounds on {}", names[index]);
+                return Ok(stream);
+            }
+            Err(e) => tracing::warn!("Couldn't open audio device {}, using the default one: {}", names[index], e),
+        },
+        None => tracing::warn!("No audio device matches {:?}, using the default one", wanted),
+    }
+    OutputStream::try_default()
+}
+
+/// Play a built-in sound once and wait for it to finish
+/// It plays on the output device named `device`, or the default one.
+pub fn preview_sound(kind: SoundKind, format: ToneFormat, count: u32, volume: f32, device: Option<&str>) -> Result<()> {
+    let (_stream, stream_handle) = open_output_stream(device)
+        .map_err(|e| anyhow::anyhow!("No audio output device available: {}", e))?;
+
+    let sink = Sink::try_new(&stream_handle)?;
+    sink.set_volume(volume);
+    sink.append(SineWaveSource::new(kind.generate_samples(format, count), format));
+    sink.sleep_until_end();
+
+    Ok(())
+}
+
+/// Write a built-in sound to a 16-bit WAV file
+pub fn export_sound_wav(kind: SoundKind, format: ToneFormat, count: u32, path: &Path) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: format.channels,
+        sample_rate: format.sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| anyhow::anyhow!("Failed to create WAV file {}: {}", path.display(), e))?;
+    for sample in kind.generate_samples(format, count) {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+
+    Ok(())
+}
 
 /// Audio notification manager
 pub struct NotificationManager {
-    _stream: OutputStream,
-    stream_handle: OutputStreamHandle,
+    _stream: Option<OutputStream>,
+    stream_handle: Option<OutputStreamHandle>,
     config: Config,
     current_sink: Option<Arc<Sink>>,
+    /// Whether an audio output device was found at startup
+    audio_available: bool,
+    /// When the looping end alarm started, if it is playing
+    alarm_started: Option<Instant>,
+    /// Format of the generated tones
+    tone_format: ToneFormat,
+    /// Whether the configured `audio_file` could be decoded at startup
+    custom_audio_ok: bool,
+    /// Picks the end alarm from the files in `audio_dir`
+    alarm_picker: AlarmPicker,
+    /// Decides when the work session tick is due
+    metronome: Metronome,
+    /// Whether the output device was lost and couldn't be reopened since the last check
+    audio_lost: bool,
+    /// Whether sound was muted at runtime, independent of `no_sound`
+    muted: bool,
 }
 
 impl NotificationManager {
     /// Create a new notification manager
+    /// Falls back to a silent manager if no audio output device is available.
     pub fn new(config: Config) -> Result<Self> {
-        let (_stream, stream_handle) = OutputStream::try_default()?;
-        
-        Ok(Self {
-            _stream,
-            stream_handle,
+        match open_output_stream(config.audio.audio_device.as_deref()) {
+            Ok((stream, stream_handle)) => Ok(Self {
+                _stream: Some(stream),
+                stream_handle: Some(stream_handle),
+                tone_format: ToneFormat::from_config(&config.audio),
+                custom_audio_ok: check_custom_audio(&config.audio),
+                alarm_picker: AlarmPicker::from_config(&config.audio),
+                metronome: Metronome::new(config.audio.tick_interval_secs),
+                config,
+                current_sink: None,
+                audio_available: true,
+                alarm_started: None,
+                audio_lost: false,
+                muted: false,
+            }),
+            Err(e) => {
+                tracing::warn!("No audio output device available, sound is disabled: {}", e);
+                Ok(Self::without_audio(config))
+            }
+        }
+    }
+
+    /// Create a notification manager without an audio output device
+    /// All `play_*` methods become no-ops.
+    pub fn without_audio(config: Config) -> Self {
+        Self {
+            _stream: None,
+            stream_handle: None,
+            tone_format: ToneFormat::from_config(&config.audio),
+            custom_audio_ok: check_custom_audio(&config.audio),
+            alarm_picker: AlarmPicker::from_config(&config.audio),
+            metronome: Metronome::new(config.audio.tick_interval_secs),
             config,
             current_sink: None,
-        })
+            audio_available: false,
+            alarm_started: None,
+            audio_lost: false,
+            muted: false,
+        }
+    }
+
+    /// Check if an audio output device was found at startup
+    pub fn has_output_device(&self) -> bool {
+        self.audio_available
+    }
+
+    /// Check if a sound is still playing
+    pub fn is_playing(&self) -> bool {
+        self.current_sink.as_ref().is_some_and(|sink| !sink.empty())
     }
 
     /// Check if sound notifications are enabled
     pub fn is_enabled(&self) -> bool {
-        !self.config.general.no_sound
+        self.audio_available && !self.config.general.no_sound && !self.muted
+    }
+
+    /// Mute or unmute all sounds for now, cutting off whatever is playing when muting
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        if muted {
+            self.stop_audio();
+        }
     }
 
     /// Stop any currently playing audio
@@ -38,12 +382,107 @@ impl NotificationManager {
             sink.stop();
         }
         self.current_sink = None;
+        self.alarm_started = None;
+    }
+
+    /// Change the global volume, including the sound that is playing right now
+    /// Per-sound volumes move by the same step, so the playing sound keeps its own level.
+    pub fn set_volume(&mut self, volume: f32) {
+        let before = self.config.audio.volume;
+        self.config.audio.set_volume(volume);
+        let step = self.config.audio.volume - before;
+        if let Some(sink) = &self.current_sink {
+            sink.set_volume((sink.volume() + step).clamp(0.0, 1.0));
+        }
     }
 
+    /// Stop the end alarm once it has looped for `alarm_seconds`
+    /// Returns true if the alarm was stopped.
+    pub fn stop_alarm_if_expired(&mut self, now: Instant) -> bool {
+        match self.alarm_started {
+            Some(started) if alarm_expired(started, now, self.config.audio.alarm_seconds) => {
+                self.stop_audio();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Return true once after the output device was lost for good and sound turned off
+    pub fn take_audio_lost(&mut self) -> bool {
+        std::mem::take(&mut self.audio_lost)
+    }
+
+    /// Reopen the output device after the old stream stopped working
+    /// Bluetooth headphones that disconnect take their stream with them.
+    pub fn reinit_stream(&mut self) -> Result<()> {
+        let (stream, stream_handle) = open_output_stream(self.config.audio.audio_device.as_deref())?;
+        self.stop_audio();
+        self._stream = Some(stream);
+        self.stream_handle = Some(stream_handle);
+        self.audio_available = true;
+        tracing::info!("Reconnected to the audio output device");
+        Ok(())
+    }
 
+    /// Run `play`, reopening the output device and trying once more if its stream is gone
+    /// Gives up by turning sound off, so a missing device doesn't fail every sound after.
+    fn play_with_reconnect(&mut self, play: fn(&mut Self) -> Result<()>) -> Result<()> {
+        let result = retry_after_reinit(self, play, Self::reinit_stream);
+        if result.as_ref().is_err_and(is_stream_lost) {
+            self.audio_available = false;
+            self.audio_lost = true;
+        }
+        result
+    }
+
+    /// Play the metronome tick if `worked` has reached the next tick interval
+    /// Never plays over another sound, so the end alarm and start beeps are left alone.
+    pub fn play_tick_if_due(&mut self, worked: Duration) -> Result<()> {
+        if !self.config.audio.tick_sound || !self.metronome.is_due(worked.as_secs()) {
+            return Ok(());
+        }
+        if !self.is_enabled() || self.alarm_started.is_some() || self.is_playing() {
+            return Ok(());
+        }
+        self.play_with_reconnect(Self::start_tick)
+    }
+
+    fn start_tick(&mut self) -> Result<()> {
+        let sink = self.create_sink(SoundKind::Work)?;
+        sink.set_volume(self.config.audio.tick_volume);
+        sink.append(SineWaveSource::new(generate_tick_sound(self.tone_format), self.tone_format));
+        // The tick is too short to need stopping, so it isn't kept as the current sound
+        sink.detach();
+
+        Ok(())
+    }
+
+    /// Play the soft low-time tone if `warn_sound` is set
+    /// Like the tick, it never plays over another sound.
+    pub fn play_low_time_warning(&mut self) -> Result<()> {
+        if !self.config.audio.warn_sound || !self.is_enabled() || self.alarm_started.is_some() || self.is_playing() {
+            return Ok(());
+        }
+        self.play_with_reconnect(Self::start_low_time_warning)
+    }
+
+    fn start_low_time_warning(&mut self) -> Result<()> {
+        let sink = self.create_sink(SoundKind::Work)?;
+        sink.set_volume(self.config.audio.volume_for(SoundKind::Work) * WARNING_VOLUME);
+        sink.append(SineWaveSource::new(generate_warning_sound(self.tone_format), self.tone_format));
+        // Short enough to play out on its own, like the tick
+        sink.detach();
+
+        Ok(())
+    }
 
     /// Play session end sound with continuous looping until stopped
     pub fn play_end_sound(&mut self) -> Result<()> {
+        self.play_with_reconnect(Self::start_end_sound)
+    }
+
+    fn start_end_sound(&mut self) -> Result<()> {
         if !self.is_enabled() {
             return Ok(());
         }
@@ -51,20 +490,37 @@ impl NotificationManager {
         // Stop any currently playing audio first
         self.stop_audio();
 
-        // Clone the audio file path to avoid borrowing issues
-        let audio_file = self.config.audio.audio_file.clone();
+        if let Some(file_path) = self.pick_alarm_file() {
+            // A broken file in the directory shouldn't leave the alarm silent
+            match self.play_custom_audio_file_continuous(&file_path) {
+                Ok(()) => {
+                    self.alarm_started = Some(Instant::now());
+                    return Ok(());
+                }
+                Err(e) => tracing::warn!("{}, using the next sound instead", e),
+            }
+        }
+
+        let audio_file = self.custom_audio_file();
         
         if let Some(file_path) = audio_file {
             self.play_custom_audio_file_continuous(&file_path)?;
+        } else if let Some(data) = embedded_wav(self.config.audio.default_sound) {
+            self.play_embedded_sound(data, SoundKind::End, true)?;
         } else {
             self.play_default_end_sound_continuous()?;
         }
+        self.alarm_started = Some(Instant::now());
         
         Ok(())
     }
 
     /// Play work session start sound
     pub fn play_work_start_sound(&mut self) -> Result<()> {
+        self.play_with_reconnect(Self::start_work_sound)
+    }
+
+    fn start_work_sound(&mut self) -> Result<()> {
         if !self.is_enabled() {
             return Ok(());
         }
@@ -72,14 +528,14 @@ impl NotificationManager {
         // Stop any currently playing audio first
         self.stop_audio();
 
-        // Clone the audio file path to avoid borrowing issues
-        let audio_file = self.config.audio.audio_file.clone();
+        let audio_file = self.custom_audio_file();
         
         if let Some(file_path) = audio_file {
-            self.play_custom_audio_file_once(&file_path)?;
+            self.play_custom_audio_file_once(&file_path, SoundKind::Work)?;
+        } else if let Some(data) = embedded_wav(self.config.audio.default_sound) {
+            self.play_embedded_sound(data, SoundKind::Work, false)?;
         } else {
-            let sound_data = generate_beep_sound(600.0, 0.2); // Lower frequency for work
-            self.play_sound_data_non_blocking(sound_data)?;
+            self.play_sound_non_blocking(SoundKind::Work)?;
         }
         
         Ok(())
@@ -87,6 +543,10 @@ impl NotificationManager {
 
     /// Play break start sound
     pub fn play_break_start_sound(&mut self) -> Result<()> {
+        self.play_with_reconnect(Self::start_break_sound)
+    }
+
+    fn start_break_sound(&mut self) -> Result<()> {
         if !self.is_enabled() {
             return Ok(());
         }
@@ -94,14 +554,14 @@ impl NotificationManager {
         // Stop any currently playing audio first
         self.stop_audio();
 
-        // Clone the audio file path to avoid borrowing issues
-        let audio_file = self.config.audio.audio_file.clone();
+        let audio_file = self.custom_audio_file();
         
         if let Some(file_path) = audio_file {
-            self.play_custom_audio_file_once(&file_path)?;
+            self.play_custom_audio_file_once(&file_path, SoundKind::Break)?;
+        } else if let Some(data) = embedded_wav(self.config.audio.default_sound) {
+            self.play_embedded_sound(data, SoundKind::Break, false)?;
         } else {
-            let sound_data = generate_beep_sound(900.0, 0.2); // Higher frequency for break
-            self.play_sound_data_non_blocking(sound_data)?;
+            self.play_sound_non_blocking(SoundKind::Break)?;
         }
         
         Ok(())
@@ -111,17 +571,48 @@ impl NotificationManager {
 
 
 
+    /// Pick the end alarm from `audio_dir`, listing it again so new files are picked up
+    fn pick_alarm_file(&mut self) -> Option<String> {
+        let dir = self.config.audio.audio_dir.as_ref()?;
+        let files = match list_audio_files(Path::new(dir)) {
+            Ok(files) => files,
+            Err(e) => {
+                tracing::debug!("{}", e);
+                return None;
+            }
+        };
+        self.alarm_picker
+            .pick(&files)
+            .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    /// Get the custom audio file, unless it failed to decode at startup
+    fn custom_audio_file(&self) -> Option<String> {
+        self.config
+            .audio
+            .audio_file
+            .clone()
+            .filter(|_| self.custom_audio_ok)
+    }
+
+    /// Create a new sink on the output stream with the volume configured for `kind`
+    fn create_sink(&self, kind: SoundKind) -> Result<Sink> {
+        let stream_handle = self
+            .stream_handle
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No audio output device available"))?;
+
+        // Fails once the device behind the stream is gone
+        let sink = Sink::try_new(stream_handle).context(StreamLost)?;
+        sink.set_volume(self.config.audio.volume_for(kind));
+        Ok(sink)
+    }
+
     /// Play custom audio file once (for session start sounds)
-    fn play_custom_audio_file_once(&mut self, file_path: &str) -> Result<()> {
-        let file = File::open(file_path)
-            .map_err(|e| anyhow::anyhow!("Failed to open audio file {}: {}", file_path, e))?;
-        let buf_reader = BufReader::new(file);
-        
-        let source = Decoder::new(buf_reader)
-            .map_err(|e| anyhow::anyhow!("Failed to decode audio file {}: {}", file_path, e))?;
+    fn play_custom_audio_file_once(&mut self, file_path: &str, kind: SoundKind) -> Result<()> {
+        let source = decode_audio_file(file_path)?;
 
-        let sink = Sink::try_new(&self.stream_handle)?;
-        sink.set_volume(self.config.audio.volume);
+        let sink = self.create_sink(kind)?;
         sink.append(source);
 
         // Store the sink reference but don't wait for completion
@@ -132,15 +623,9 @@ impl NotificationManager {
 
     /// Play custom audio file with continuous looping until stopped
     fn play_custom_audio_file_continuous(&mut self, file_path: &str) -> Result<()> {
-        let file = File::open(file_path)
-            .map_err(|e| anyhow::anyhow!("Failed to open audio file {}: {}", file_path, e))?;
-        let buf_reader = BufReader::new(file);
-        
-        let source = Decoder::new(buf_reader)
-            .map_err(|e| anyhow::anyhow!("Failed to decode audio file {}: {}", file_path, e))?;
+        let source = decode_audio_file(file_path)?;
 
-        let sink = Sink::try_new(&self.stream_handle)?;
-        sink.set_volume(self.config.audio.volume);
+        let sink = self.create_sink(SoundKind::End)?;
 
         // Loop the audio continuously until stopped
         let looped_source = source.repeat_infinite();
@@ -152,15 +637,35 @@ impl NotificationManager {
         Ok(())
     }
 
+    /// Play a bundled sample, looping it until stopped for the end alarm
+    fn play_embedded_sound(&mut self, data: &'static [u8], kind: SoundKind, looping: bool) -> Result<()> {
+        let source = decode_embedded(data)?;
+
+        let sink = self.create_sink(kind)?;
+        if looping {
+            sink.append(source.repeat_infinite());
+        } else if self.config.audio.spatial_cues && kind.pan() != 0.0 {
+            let (left, right) = pan_gains(kind.pan());
+            sink.append(ChannelVolume::new(source, vec![left, right]));
+        } else {
+            sink.append(source);
+        }
+
+        // Store the sink reference for later control
+        self.current_sink = Some(Arc::new(sink));
+
+        Ok(())
+    }
+
     /// Play default end sound with continuous looping until stopped
     fn play_default_end_sound_continuous(&mut self) -> Result<()> {
-        let sound_data = generate_notification_sound();
+        let count = self.config.audio.beep_count(SoundKind::End);
+        let sound_data = SoundKind::End.generate_samples(self.tone_format, count);
         
-        let sink = Sink::try_new(&self.stream_handle)?;
-        sink.set_volume(self.config.audio.volume);
+        let sink = self.create_sink(SoundKind::End)?;
         
         // Create a repeating source from the sound data
-        let source = SineWaveSource::new(sound_data).repeat_infinite();
+        let source = SineWaveSource::new(sound_data, self.tone_format).repeat_infinite();
         sink.append(source);
 
         // Store the sink reference for later control
@@ -171,13 +676,14 @@ impl NotificationManager {
 
 
 
-    /// Play sound data through the audio system (non-blocking)
-    fn play_sound_data_non_blocking(&mut self, sound_data: Vec<i16>) -> Result<()> {
-        let sink = Sink::try_new(&self.stream_handle)?;
-        sink.set_volume(self.config.audio.volume);
+    /// Play a generated sound through the audio system (non-blocking)
+    fn play_sound_non_blocking(&mut self, kind: SoundKind) -> Result<()> {
+        let sink = self.create_sink(kind)?;
         
         // Convert the sound data to a source
-        let source = SineWaveSource::new(sound_data);
+        let count = self.config.audio.beep_count(kind);
+        let (samples, format) = generate_positioned_samples(kind, self.tone_format, count, self.config.audio.spatial_cues);
+        let source = SineWaveSource::new(samples, format);
         sink.append(source);
         
         // Store the sink reference but don't wait for completion
@@ -187,15 +693,206 @@ impl NotificationManager {
     }
 }
 
+/// Check that the configured audio file, if any, can be opened and decoded
+/// Warns once and returns false so playback falls back to the generated tones.
+fn check_custom_audio(audio: &AudioConfig) -> bool {
+    let Some(path) = &audio.audio_file else {
+        return true;
+    };
+
+    match decode_audio_file(path) {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!("{}, using the built-in sounds instead", e);
+            false
+        }
+    }
+}
+
+/// File extensions of the audio formats the decoder can play
+const AUDIO_EXTENSIONS: [&str; 4] = ["wav", "mp3", "flac", "ogg"];
+
+/// List the playable files in `dir`, sorted by name
+fn list_audio_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| anyhow::anyhow!("Failed to read audio directory {}: {}", dir.display(), e))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_audio_file(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Check if the file has the extension of a supported audio format
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(ext)))
+}
+
+/// Picks the end alarm from a list of audio files
+#[derive(Debug)]
+struct AlarmPicker {
+    selection: SoundSelection,
+    /// Index of the next file for round-robin
+    next: usize,
+    /// State of the xorshift generator for random picks, never zero
+    rng: u64,
+}
+
+impl AlarmPicker {
+    fn new(selection: SoundSelection, seed: u64) -> Self {
+        Self {
+            selection,
+            next: 0,
+            rng: seed.max(1),
+        }
+    }
+
+    /// Create the picker for the config, warning if `audio_dir` has nothing to play
+    fn from_config(audio: &AudioConfig) -> Self {
+        if let Some(dir) = &audio.audio_dir {
+            match list_audio_files(Path::new(dir)) {
+                Ok(files) if files.is_empty() => {
+                    tracing::warn!("No audio files in {}, using the other sounds instead", dir);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("{}, using the other sounds instead", e),
+            }
+        }
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(1);
+        Self::new(audio.selection, seed)
+    }
+
+    /// Pick the file for the next alarm
+    fn pick<'a>(&mut self, files: &'a [PathBuf]) -> Option<&'a PathBuf> {
+        if files.is_empty() {
+            return None;
+        }
+        let index = match self.selection {
+            SoundSelection::Random => (self.next_random() % files.len() as u64) as usize,
+            SoundSelection::RoundRobin => {
+                let index = self.next % files.len();
+                self.next = index + 1;
+                index
+            }
+        };
+        files.get(index)
+    }
+
+    /// Advance the xorshift64 generator
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+}
+
+/// Error context for a sink that couldn't be created on the output stream
+#[derive(Debug)]
+struct StreamLost;
+
+impl std::fmt::Display for StreamLost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Audio output stream is gone")
+    }
+}
+
+/// Check if an error came from an output stream that stopped working
+fn is_stream_lost(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<StreamLost>().is_some()
+}
+
+/// Run `play`, and if its output stream is gone, `reinit` it and run `play` once more
+/// Other errors, such as an undecodable file, are returned right away.
+fn retry_after_reinit<S>(
+    state: &mut S,
+    play: impl Fn(&mut S) -> Result<()>,
+    reinit: impl Fn(&mut S) -> Result<()>,
+) -> Result<()> {
+    match play(state) {
+        Err(e) if is_stream_lost(&e) => {
+            tracing::warn!("{}, reopening the output device", e);
+            if let Err(reinit_error) = reinit(state) {
+                tracing::warn!("Failed to reopen the audio output device: {}", reinit_error);
+                return Err(e);
+            }
+            play(state)
+        }
+        result => result,
+    }
+}
+
+/// Tracks when the next metronome tick is due during a work session
+#[derive(Debug, Clone, Copy)]
+struct Metronome {
+    interval_secs: u64,
+    /// Whole intervals worked when last checked
+    intervals: u64,
+}
+
+impl Metronome {
+    fn new(interval_secs: u64) -> Self {
+        Self {
+            interval_secs: interval_secs.max(1),
+            intervals: 0,
+        }
+    }
+
+    /// Check if `worked_secs` crossed into a new interval since the last check
+    /// Ticks are wall-clock based and may land a little late, so crossing counts rather
+    /// than hitting the exact second. A new session starts at zero again.
+    fn is_due(&mut self, worked_secs: u64) -> bool {
+        let intervals = worked_secs / self.interval_secs;
+        let due = intervals > self.intervals;
+        self.intervals = intervals;
+        due
+    }
+}
+
+/// Open and decode an audio file
+fn decode_audio_file(path: &str) -> Result<Decoder<BufReader<File>>> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open audio file {}: {}", path, e))?;
+    Decoder::new(BufReader::new(file))
+        .map_err(|e| anyhow::anyhow!("Failed to decode audio file {}: {}", path, e))
+}
+
+/// Get the bundled WAV data for a built-in sound; the generated beeps have none
+fn embedded_wav(sound: BuiltinSound) -> Option<&'static [u8]> {
+    match sound {
+        BuiltinSound::Beep => None,
+        BuiltinSound::Chime => Some(CHIME_WAV),
+        BuiltinSound::Bell => Some(BELL_WAV),
+    }
+}
+
+/// Decode a bundled WAV from memory
+fn decode_embedded(data: &'static [u8]) -> Result<Decoder<Cursor<&'static [u8]>>> {
+    Decoder::new(Cursor::new(data)).map_err(|e| anyhow::anyhow!("Failed to decode built-in sound: {}", e))
+}
+
+/// Check if an alarm started at `started` has played for `alarm_seconds` by `now`
+/// An `alarm_seconds` of 0 means the alarm loops until stopped.
+fn alarm_expired(started: Instant, now: Instant, alarm_seconds: u64) -> bool {
+    alarm_seconds > 0 && now.saturating_duration_since(started) >= Duration::from_secs(alarm_seconds)
+}
+
 /// Simple sine wave source for generating beep sounds
 struct SineWaveSource {
     data: Vec<i16>,
     position: usize,
+    format: ToneFormat,
 }
 
 impl SineWaveSource {
-    fn new(data: Vec<i16>) -> Self {
-        Self { data, position: 0 }
+    fn new(data: Vec<i16>, format: ToneFormat) -> Self {
+        Self { data, position: 0, format }
     }
 }
 
@@ -219,25 +916,31 @@ impl Source for SineWaveSource {
     }
 
     fn channels(&self) -> u16 {
-        1 // Mono
+        self.format.channels
     }
 
     fn sample_rate(&self) -> u32 {
-        44100 // Standard sample rate
+        self.format.sample_rate
     }
 
     fn total_duration(&self) -> Option<Duration> {
-        Some(Duration::from_secs_f32(
-            self.data.len() as f32 / 44100.0,
-        ))
+        let frames = self.data.len() as f32 / self.format.channels as f32;
+        Some(Duration::from_secs_f32(frames / self.format.sample_rate as f32))
+    }
+}
+
+/// Write a mono sample to every channel of the interleaved buffer
+fn push_frame(sound_data: &mut Vec<i16>, sample: i16, channels: u16) {
+    for _ in 0..channels {
+        sound_data.push(sample);
     }
 }
 
 /// Generate a simple beep sound at the specified frequency and duration
-fn generate_beep_sound(frequency: f32, duration: f32) -> Vec<i16> {
-    let sample_rate = 44100.0;
+fn generate_beep_sound(frequency: f32, duration: f32, format: ToneFormat) -> Vec<i16> {
+    let sample_rate = format.sample_rate as f32;
     let samples = (sample_rate * duration) as usize;
-    let mut sound_data = Vec::with_capacity(samples);
+    let mut sound_data = Vec::with_capacity(samples * format.channels as usize);
 
     for i in 0..samples {
         let t = i as f32 / sample_rate;
@@ -252,18 +955,68 @@ fn generate_beep_sound(frequency: f32, duration: f32) -> Vec<i16> {
             1.0
         };
         
-        sound_data.push((sample * envelope * 0.3 * i16::MAX as f32) as i16);
+        push_frame(&mut sound_data, (sample * envelope * 0.3 * i16::MAX as f32) as i16, format.channels);
+    }
+
+    sound_data
+}
+
+/// Frequency of the low-time warning in Hz, below the work start beep
+const WARNING_FREQUENCY: f32 = 440.0;
+
+/// Share of the work volume the low-time warning plays at
+const WARNING_VOLUME: f32 = 0.5;
+
+/// Generate the soft tone played when a work session is nearly over
+fn generate_warning_sound(format: ToneFormat) -> Vec<i16> {
+    generate_beep_sound(WARNING_FREQUENCY, 0.3, format)
+}
+
+/// Frequency of the metronome tick in Hz
+const TICK_FREQUENCY: f32 = 1800.0;
+
+/// Length of the metronome tick in seconds
+const TICK_SECS: f32 = 0.03;
+
+/// Generate a short click that decays quickly, unlike the smooth start beeps
+fn generate_tick_sound(format: ToneFormat) -> Vec<i16> {
+    let sample_rate = format.sample_rate as f32;
+    let samples = (sample_rate * TICK_SECS) as usize;
+    let mut sound_data = Vec::with_capacity(samples * format.channels as usize);
+
+    for i in 0..samples {
+        let t = i as f32 / sample_rate;
+        let sample = (t * TICK_FREQUENCY * 2.0 * std::f32::consts::PI).sin();
+        let envelope = 1.0 - t / TICK_SECS;
+        push_frame(&mut sound_data, (sample * envelope * 0.3 * i16::MAX as f32) as i16, format.channels);
     }
 
     sound_data
 }
 
-/// Generate a more complex notification sound (two-tone beep)
-fn generate_notification_sound() -> Vec<i16> {
-    let sample_rate = 44100.0;
+/// Silence between repeated beeps, in seconds
+const BEEP_GAP_SECS: f32 = 0.15;
+
+/// Play `sound` `count` times with a short silence in between (at least once)
+fn repeat_with_gaps(sound: &[i16], count: u32, format: ToneFormat) -> Vec<i16> {
+    let gap = (format.sample_rate as f32 * BEEP_GAP_SECS) as usize * format.channels as usize;
+    let count = count.max(1) as usize;
+    let mut data = Vec::with_capacity(sound.len() * count + gap * (count - 1));
+    for index in 0..count {
+        if index > 0 {
+            data.resize(data.len() + gap, 0);
+        }
+        data.extend_from_slice(sound);
+    }
+    data
+}
+
+/// Generate a more complex notification sound (two-tone beep), `count` times in a row
+fn generate_notification_sound(format: ToneFormat, count: u32) -> Vec<i16> {
+    let sample_rate = format.sample_rate as f32;
     let duration = 0.6; // Total duration
     let samples = (sample_rate * duration) as usize;
-    let mut sound_data = Vec::with_capacity(samples);
+    let mut sound_data = Vec::with_capacity(samples * format.channels as usize);
 
     for i in 0..samples {
         let t = i as f32 / sample_rate;
@@ -292,9 +1045,398 @@ fn generate_notification_sound() -> Vec<i16> {
             1.0
         };
         
-        sound_data.push((sample * envelope * i16::MAX as f32) as i16);
+        push_frame(&mut sound_data, (sample * envelope * i16::MAX as f32) as i16, format.channels);
     }
 
-    sound_data
+    repeat_with_gaps(&sound_data, count, format)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_device_name() {
+        let names: Vec<String> = ["HDMI Output", "Built-in Speakers", "USB Headset", "speakers"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(match_device_name(&names, "USB Headset"), Some(2));
+        // An exact name beats one that only contains it
+        assert_eq!(match_device_name(&names, "speakers"), Some(3));
+        assert_eq!(match_device_name(&names, "usb headset"), Some(2));
+        assert_eq!(match_device_name(&names, "hdmi"), Some(0));
+        assert_eq!(match_device_name(&names, " Built-in "), Some(1));
+        assert_eq!(match_device_name(&names, "Bluetooth"), None);
+        assert_eq!(match_device_name(&names, ""), None);
+        assert_eq!(match_device_name(&[], "HDMI Output"), None);
+    }
+
+    #[test]
+    fn test_desktop_notification_urgency_per_event() {
+        let config = NotificationsConfig::default();
+        let expected = [
+            (NotificationEvent::WorkStart, Urgency::Normal),
+            (NotificationEvent::BreakStart, Urgency::Low),
+            (NotificationEvent::Complete, Urgency::Critical),
+            (NotificationEvent::DailyGoal, Urgency::Normal),
+        ];
+        for (event, urgency) in expected {
+            let notification = DesktopNotification::new(&config, event, "Rustdoro", "Work done");
+            assert_eq!(notification.urgency, urgency, "{:?}", event);
+            assert_eq!(notification.timeout, None);
+            assert_eq!(
+                notification.notify_send_args(),
+                [format!("--urgency={}", urgency.name()), "Rustdoro".to_string(), "Work done".to_string()]
+            );
+        }
+
+        let config = NotificationsConfig {
+            timeout_ms: 8000,
+            ..NotificationsConfig::default()
+        };
+        let notification = DesktopNotification::new(&config, NotificationEvent::Complete, "Rustdoro", "Work done");
+        assert_eq!(notification.timeout, Some(Duration::from_secs(8)));
+        assert_eq!(
+            notification.notify_send_args(),
+            ["--urgency=critical", "--expire-time=8000", "Rustdoro", "Work done"]
+        );
+    }
+
+    #[test]
+    fn test_without_audio_is_silent() {
+        let mut manager = NotificationManager::without_audio(Config::default());
+
+        assert!(!manager.audio_available);
+        assert!(!manager.is_enabled());
+        assert!(manager.play_end_sound().is_ok());
+        assert!(manager.play_work_start_sound().is_ok());
+        assert!(manager.play_break_start_sound().is_ok());
+        assert!(manager.current_sink.is_none());
+        manager.stop_audio();
+    }
+
+    #[test]
+    fn test_muted_sounds_are_skipped() {
+        // A manager that believes it has a device but can't create sinks
+        let mut manager = NotificationManager::without_audio(Config::default());
+        manager.audio_available = true;
+        assert!(!manager.config.general.no_sound);
+        assert!(manager.play_end_sound().is_err());
+
+        manager.set_muted(true);
+        assert!(!manager.is_enabled());
+        assert!(manager.play_end_sound().is_ok());
+        assert!(manager.play_work_start_sound().is_ok());
+        assert!(manager.alarm_started.is_none());
+        assert!(manager.current_sink.is_none());
+
+        manager.set_muted(false);
+        assert!(manager.is_enabled());
+    }
+
+    #[test]
+    fn test_undecodable_audio_file_falls_back() {
+        let path = std::env::temp_dir().join(format!("rustdoro-not-audio-{}.ogg", std::process::id()));
+        std::fs::write(&path, "definitely not audio").unwrap();
+
+        let mut config = Config::default();
+        config.audio.audio_file = Some(path.to_string_lossy().into_owned());
+        let mut manager = NotificationManager::without_audio(config);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!manager.custom_audio_ok);
+        assert!(manager.custom_audio_file().is_none());
+        assert!(manager.play_end_sound().is_ok());
+        assert!(manager.play_work_start_sound().is_ok());
+    }
+
+    #[test]
+    fn test_decodable_audio_file_is_used() {
+        let path = std::env::temp_dir().join(format!("rustdoro-custom-{}.wav", std::process::id()));
+        export_sound_wav(SoundKind::Break, ToneFormat::default(), 1, &path).unwrap();
+
+        let mut config = Config::default();
+        config.audio.audio_file = Some(path.to_string_lossy().into_owned());
+        let manager = NotificationManager::without_audio(config);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(manager.custom_audio_ok);
+        assert!(manager.custom_audio_file().is_some());
+    }
+
+    #[test]
+    fn test_alarm_picker_round_robin() {
+        let files: Vec<PathBuf> = ["a.wav", "b.mp3", "c.ogg"].iter().map(PathBuf::from).collect();
+        let mut picker = AlarmPicker::new(SoundSelection::RoundRobin, 7);
+
+        let picks: Vec<&PathBuf> = (0..5).map(|_| picker.pick(&files).unwrap()).collect();
+        assert_eq!(picks, [&files[0], &files[1], &files[2], &files[0], &files[1]]);
+        // A shorter list after files were removed still wraps around
+        assert_eq!(picker.pick(&files[..1]), Some(&files[0]));
+        assert_eq!(picker.pick(&[]), None);
+    }
+
+    #[test]
+    fn test_alarm_picker_random() {
+        let files: Vec<PathBuf> = (0..4).map(|i| PathBuf::from(format!("{}.wav", i))).collect();
+        let picks = |seed| {
+            let mut picker = AlarmPicker::new(SoundSelection::Random, seed);
+            (0..32).map(|_| picker.pick(&files).unwrap().clone()).collect::<Vec<_>>()
+        };
+
+        // The same seed gives the same sequence, and every file comes up
+        let first = picks(42);
+        assert_eq!(first, picks(42));
+        assert_ne!(first, picks(43));
+        assert!(files.iter().all(|file| first.contains(file)));
+        // A zero seed must not get the generator stuck on one file
+        let zero = picks(0);
+        assert!(zero.iter().any(|file| file != &zero[0]));
+    }
+
+    #[test]
+    fn test_list_audio_files() {
+        let dir = std::env::temp_dir().join(format!("rustdoro-audio-dir-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested.wav")).unwrap();
+        for name in ["b.WAV", "a.mp3", "notes.txt", "c.flac"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+
+        let names: Vec<_> = list_audio_files(&dir)
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names, ["a.mp3", "b.WAV", "c.flac"]);
+        assert!(list_audio_files(&dir).is_err());
+    }
+
+    #[test]
+    fn test_builtin_sounds_decode() {
+        assert!(embedded_wav(BuiltinSound::Beep).is_none());
+
+        for sound in [BuiltinSound::Chime, BuiltinSound::Bell] {
+            let data = embedded_wav(sound).unwrap();
+            let source = decode_embedded(data).unwrap();
+            assert_eq!(source.channels(), 1, "{:?}", sound);
+            assert!(source.count() > 0, "{:?}", sound);
+        }
+    }
+
+    #[test]
+    fn test_export_sound_wav() {
+        let path = std::env::temp_dir().join(format!("rustdoro-test-{}.wav", std::process::id()));
+
+        let format = ToneFormat::default();
+
+        for kind in [SoundKind::Work, SoundKind::Break, SoundKind::End] {
+            export_sound_wav(kind, format, 2, &path).unwrap();
+
+            let reader = hound::WavReader::open(&path).unwrap();
+            assert_eq!(reader.spec().channels, 1);
+            assert_eq!(reader.spec().sample_rate, 44100);
+            assert_eq!(reader.len() as usize, kind.generate_samples(format, 2).len());
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Output whose stream can die, standing in for a disconnected device
+    struct FlakyOutput {
+        alive: bool,
+        can_reopen: bool,
+        plays: u32,
+        reinits: u32,
+    }
+
+    impl FlakyOutput {
+        fn new(alive: bool, can_reopen: bool) -> Self {
+            Self { alive, can_reopen, plays: 0, reinits: 0 }
+        }
+
+        fn play(&mut self) -> Result<()> {
+            self.plays += 1;
+            if self.alive {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("NoDevice")).context(StreamLost)
+            }
+        }
+
+        fn reinit(&mut self) -> Result<()> {
+            self.reinits += 1;
+            self.alive = self.can_reopen;
+            if self.can_reopen {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("no default output device"))
+            }
+        }
+    }
+
+    #[test]
+    fn test_lost_stream_is_reopened_once() {
+        // First sink fails, the device comes back
+        let mut output = FlakyOutput::new(false, true);
+        assert!(retry_after_reinit(&mut output, FlakyOutput::play, FlakyOutput::reinit).is_ok());
+        assert_eq!((output.plays, output.reinits), (2, 1));
+
+        // A healthy stream is left alone
+        let mut output = FlakyOutput::new(true, true);
+        assert!(retry_after_reinit(&mut output, FlakyOutput::play, FlakyOutput::reinit).is_ok());
+        assert_eq!((output.plays, output.reinits), (1, 0));
+
+        // The device stays gone
+        let mut output = FlakyOutput::new(false, false);
+        let error = retry_after_reinit(&mut output, FlakyOutput::play, FlakyOutput::reinit).unwrap_err();
+        assert!(is_stream_lost(&error));
+        assert_eq!((output.plays, output.reinits), (1, 1));
+
+        // Other failures don't touch the device
+        let mut output = FlakyOutput::new(true, true);
+        let broken_file = |output: &mut FlakyOutput| {
+            output.plays += 1;
+            Err(anyhow::anyhow!("Failed to decode audio file"))
+        };
+        assert!(retry_after_reinit(&mut output, broken_file, FlakyOutput::reinit).is_err());
+        assert_eq!((output.plays, output.reinits), (1, 0));
+    }
+
+    #[test]
+    fn test_metronome_cadence() {
+        let mut metronome = Metronome::new(60);
+        assert!(!metronome.is_due(0));
+        assert!(!metronome.is_due(59));
+        assert!(metronome.is_due(60));
+        // Only once per interval, even if the same second is seen again
+        assert!(!metronome.is_due(60));
+        assert!(!metronome.is_due(119));
+        // A late tick still counts
+        assert!(metronome.is_due(121));
+
+        // The next session starts over, with its first tick after a full interval
+        assert!(!metronome.is_due(0));
+        assert!(!metronome.is_due(30));
+        assert!(metronome.is_due(60));
+
+        let mut every_second = Metronome::new(0);
+        assert!(every_second.is_due(1));
+        assert!(every_second.is_due(2));
+    }
+
+    #[test]
+    fn test_tick_sound_is_short() {
+        let format = ToneFormat::default();
+        let tick = generate_tick_sound(format);
+        assert_eq!(tick.len(), (44100.0 * TICK_SECS) as usize);
+        assert!(tick.len() < generate_beep_sound(600.0, 0.2, format).len());
+        assert!(tick.iter().all(|sample| sample.unsigned_abs() <= (0.3 * i16::MAX as f32) as u16));
+    }
+
+    #[test]
+    fn test_stereo_tones_are_interleaved() {
+        let mono = ToneFormat::default();
+        let stereo = ToneFormat { sample_rate: 44100, channels: 2 };
+
+        let mono_beep = generate_beep_sound(600.0, 0.2, mono);
+        let stereo_beep = generate_beep_sound(600.0, 0.2, stereo);
+        assert_eq!(mono_beep.len(), 8820);
+        assert_eq!(stereo_beep.len(), mono_beep.len() * 2);
+        assert_eq!(stereo_beep[200], stereo_beep[201]);
+        assert_eq!(stereo_beep[200], mono_beep[100]);
+
+        let stereo_end = generate_notification_sound(stereo, 1);
+        assert_eq!(stereo_end.len(), generate_notification_sound(mono, 1).len() * 2);
+
+        let source = SineWaveSource::new(stereo_beep, stereo);
+        assert_eq!(source.channels(), 2);
+        assert_eq!(source.total_duration(), Some(Duration::from_secs_f32(0.2)));
+    }
+
+    #[test]
+    fn test_beep_count_scales_buffer() {
+        let format = ToneFormat::default();
+        let gap = (44100.0 * BEEP_GAP_SECS) as usize;
+
+        for kind in [SoundKind::Work, SoundKind::Break, SoundKind::End] {
+            let single = kind.generate_samples(format, 1).len();
+            assert_eq!(kind.generate_samples(format, 3).len(), single * 3 + gap * 2, "{:?}", kind);
+            // Zero still plays the sound once
+            assert_eq!(kind.generate_samples(format, 0).len(), single, "{:?}", kind);
+        }
+        assert_eq!(SoundKind::End.generate_samples(format, 1), generate_notification_sound(format, 1));
+
+        // Gaps are whole frames of silence in stereo too
+        let stereo = ToneFormat { sample_rate: 44100, channels: 2 };
+        let beeps = SoundKind::Work.generate_samples(stereo, 2);
+        assert_eq!(beeps.len(), SoundKind::Work.generate_samples(stereo, 1).len() * 2 + gap * 2);
+    }
+
+    #[test]
+    fn test_spatial_cues_pan_start_sounds() {
+        let format = ToneFormat::default();
+        let peaks = |samples: &[i16]| {
+            let peak = |channel: usize| samples.iter().skip(channel).step_by(2).map(|s| s.unsigned_abs()).max().unwrap();
+            (peak(0), peak(1))
+        };
+
+        let (work, work_format) = generate_positioned_samples(SoundKind::Work, format, 1, true);
+        assert_eq!(work_format.channels, 2);
+        let (left, right) = peaks(&work);
+        assert!(left > right, "work should lean left: {} vs {}", left, right);
+
+        let (brk, _) = generate_positioned_samples(SoundKind::Break, format, 1, true);
+        let (left, right) = peaks(&brk);
+        assert!(right > left, "break should lean right: {} vs {}", left, right);
+
+        // The end alarm and disabled cues are left as configured
+        let (end, end_format) = generate_positioned_samples(SoundKind::End, format, 1, true);
+        assert_eq!(end_format, format);
+        assert_eq!(end, SoundKind::End.generate_samples(format, 1));
+        let (plain, plain_format) = generate_positioned_samples(SoundKind::Work, format, 1, false);
+        assert_eq!(plain_format.channels, 1);
+        assert_eq!(plain, SoundKind::Work.generate_samples(format, 1));
+    }
+
+    #[test]
+    fn test_tone_format_from_config() {
+        let mut audio = Config::default().audio;
+        assert_eq!(ToneFormat::from_config(&audio), ToneFormat::default());
+
+        audio.channels = 0;
+        audio.sample_rate = 48000;
+        let format = ToneFormat::from_config(&audio);
+        assert_eq!(format.channels, 1);
+        assert_eq!(format.sample_rate, 48000);
+    }
+
+    #[test]
+    fn test_alarm_expiry() {
+        let start = Instant::now();
+
+        assert!(!alarm_expired(start, start + Duration::from_secs(9), 10));
+        assert!(alarm_expired(start, start + Duration::from_secs(10), 10));
+        assert!(alarm_expired(start, start + Duration::from_secs(60), 10));
+        // Zero loops forever
+        assert!(!alarm_expired(start, start + Duration::from_secs(3600), 0));
+    }
+
+    #[test]
+    fn test_stop_alarm_if_expired() {
+        let mut config = Config::default();
+        config.audio.alarm_seconds = 5;
+        let mut manager = NotificationManager::without_audio(config);
+        let start = Instant::now();
+
+        // Nothing to stop when no alarm is playing
+        assert!(!manager.stop_alarm_if_expired(start + Duration::from_secs(10)));
+
+        manager.alarm_started = Some(start);
+        assert!(!manager.stop_alarm_if_expired(start + Duration::from_secs(4)));
+        assert!(manager.alarm_started.is_some());
+        assert!(manager.stop_alarm_if_expired(start + Duration::from_secs(5)));
+        assert!(manager.alarm_started.is_none());
+    }
+}
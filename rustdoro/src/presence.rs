@@ -0,0 +1,89 @@
+use discord_rich_presence::{
+    activity::{Activity, Party, Timestamps},
+    DiscordIpc, DiscordIpcClient,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::timer::{SessionType, Timer};
+
+/// Discord Rich Presence integration showing the current pomodoro state
+pub struct DiscordPresence {
+    client: DiscordIpcClient,
+    connected: bool,
+    /// Set after the first failed connect or update so Discord isn't retried every tick
+    disabled: bool,
+    /// Session type and running state last sent to Discord
+    last_state: Option<(SessionType, bool)>,
+}
+
+impl DiscordPresence {
+    /// Create a presence client for the given Discord application ID
+    pub fn new(client_id: &str) -> Self {
+        Self {
+            client: DiscordIpcClient::new(client_id),
+            connected: false,
+            disabled: false,
+            last_state: None,
+        }
+    }
+
+    /// Update the presence if the session type or running state changed
+    pub fn update(&mut self, timer: &Timer) {
+        if self.disabled {
+            return;
+        }
+
+        let state = (timer.get_session_type(), timer.is_running());
+        if self.last_state == Some(state) {
+            return;
+        }
+
+        if !self.connected {
+            if self.client.connect().is_err() {
+                // Discord is most likely not running
+                self.disabled = true;
+                return;
+            }
+            self.connected = true;
+        }
+
+        let activity_state = match state {
+            (_, false) => "Paused",
+            (SessionType::Work, true) => "Focusing",
+            (SessionType::ShortBreak | SessionType::LongBreak, true) => "On break",
+        };
+        let session_type = timer.get_session_type();
+        let labels = timer.labels();
+        let details = format!("{} {}", labels.emoji(session_type), labels.label(session_type));
+        let mut activity = Activity::new().state(activity_state).details(details);
+
+        if timer.is_running() {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let end = now + timer.remaining_time.as_secs() as i64;
+            activity = activity.timestamps(Timestamps::new().end(end));
+        }
+
+        let completed = timer.get_pomodoros_completed() as i32;
+        if completed > 0 {
+            let max = (timer.get_target_pomodoros() as i32).max(completed);
+            activity = activity.party(Party::new().size([completed, max]));
+        }
+
+        if self.client.set_activity(activity).is_err() {
+            self.disabled = true;
+            return;
+        }
+        self.last_state = Some(state);
+    }
+
+    /// Clear the presence and close the connection
+    pub fn shutdown(&mut self) {
+        if self.connected {
+            let _ = self.client.clear_activity();
+            let _ = self.client.close();
+            self.connected = false;
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// App state kept between runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedState {
+    /// Day the daily goal was last celebrated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal_notified_date: Option<NaiveDate>,
+}
+
+/// Get the path of the state file
+pub fn state_path() -> Result<PathBuf> {
+    let mut path: PathBuf = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
+    path.push("rustdoro");
+    path.push("state.json");
+    Ok(path)
+}
+
+impl PersistedState {
+    /// Load the state from a file, starting empty if it does not exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Write the state to a file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Pomodoros to complete each day, celebrated once per calendar day
+#[derive(Debug)]
+pub struct DailyGoal {
+    /// Pomodoros per day (0 means no goal)
+    goal: u64,
+    /// File the state is saved to, if it could be found
+    path: Option<PathBuf>,
+    state: PersistedState,
+}
+
+impl DailyGoal {
+    /// Track `goal` with the state saved at `path`
+    pub fn new(goal: u64, path: Option<PathBuf>) -> Self {
+        let state = match &path {
+            Some(path) => PersistedState::load(path).unwrap_or_else(|e| {
+                tracing::warn!("Failed to read {}: {}", path.display(), e);
+                PersistedState::default()
+            }),
+            None => PersistedState::default(),
+        };
+        Self { goal, path, state }
+    }
+
+    /// Track `goal` with the state saved at the default location
+    pub fn load_default(goal: u64) -> Self {
+        let path = state_path()
+            .inspect_err(|e| tracing::warn!("Daily goal state is not persisted: {}", e))
+            .ok();
+        Self::new(goal, path)
+    }
+
+    /// Check `count` pomodoros done on `today` against the goal
+    /// Returns true only the first time the goal is met on a day.
+    pub fn check(&mut self, today: NaiveDate, count: u64) -> bool {
+        if self.goal == 0 || count < self.goal || self.is_met(today) {
+            return false;
+        }
+
+        self.state.goal_notified_date = Some(today);
+        if let Some(path) = &self.path {
+            if let Err(e) = self.state.save(path) {
+                tracing::warn!("Failed to save {}: {}", path.display(), e);
+            }
+        }
+        true
+    }
+
+    /// Check if the goal was already met on `today`
+    pub fn is_met(&self, today: NaiveDate) -> bool {
+        self.goal > 0 && self.state.goal_notified_date == Some(today)
+    }
+
+    /// Get the number of pomodoros to complete each day
+    pub fn goal(&self) -> u64 {
+        self.goal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goal_fires_once_per_day() {
+        let path = std::env::temp_dir().join(format!("rustdoro-state-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let tuesday = monday.succ_opt().unwrap();
+
+        let mut goal = DailyGoal::new(3, Some(path.clone()));
+        assert!(!goal.check(monday, 2));
+        assert!(!goal.is_met(monday));
+        assert!(goal.check(monday, 3));
+        assert!(goal.is_met(monday));
+        assert!(!goal.check(monday, 4));
+        assert!(!goal.check(monday, 5));
+
+        // A restart the same day remembers the goal was celebrated
+        let mut reloaded = DailyGoal::new(3, Some(path.clone()));
+        assert!(reloaded.is_met(monday));
+        assert!(!reloaded.check(monday, 6));
+
+        // The next day starts over
+        assert!(!reloaded.is_met(tuesday));
+        assert!(reloaded.check(tuesday, 3));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_no_goal_never_fires() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let mut goal = DailyGoal::new(0, None);
+        assert!(!goal.check(today, 10));
+        assert!(!goal.is_met(today));
+    }
+}
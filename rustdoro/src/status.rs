@@ -0,0 +1,85 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::timer::{Timer, TimerState};
+
+/// Status line written when no `status_format` is configured
+pub const DEFAULT_STATUS_FORMAT: &str = "{emoji} {session} {time} ({count})";
+
+/// Fill a status template with the current timer state
+/// Supports `{session}`, `{time}`, `{count}`, `{state}` and `{emoji}`.
+pub fn format_status(template: &str, timer: &Timer) -> String {
+    let session = timer.get_session_type();
+    let labels = timer.labels();
+
+    template
+        .replace("{session}", labels.label(session))
+        .replace("{time}", &timer.get_display_time())
+        .replace("{count}", &timer.get_pomodoros_completed().to_string())
+        .replace("{state}", state_name(timer.state))
+        .replace("{emoji}", labels.emoji(session))
+}
+
+/// Get the lowercase name of a timer state, as used in status lines
+pub fn state_name(state: TimerState) -> &'static str {
+    match state {
+        TimerState::Running => "running",
+        TimerState::Paused => "paused",
+        TimerState::Stopped => "stopped",
+    }
+}
+
+/// Replace the status file with a single line
+/// The line is written to a temporary file first and renamed over the target,
+/// so status bars never read a half-written file.
+pub fn write_status_file(path: &Path, line: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid status file path {}", path.display()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    std::fs::write(&temp_path, format!("{}\n", line))?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_format_status_placeholders() {
+        let mut timer = Timer::new(Config::default());
+        assert_eq!(
+            format_status("{session} {time} ({count}) {state}", &timer),
+            "Work 25:00 (0) stopped"
+        );
+
+        timer.start();
+        timer.remaining_time = std::time::Duration::from_secs(24 * 60 + 13);
+        assert_eq!(format_status("{session}|{time}|{state}", &timer), "Work|24:13|running");
+        assert_eq!(
+            format_status(DEFAULT_STATUS_FORMAT, &timer),
+            format!("{} Work 24:13 (0)", timer.get_session_type().emoji())
+        );
+
+        timer.skip_session();
+        assert_eq!(format_status("{session} {unknown}", &timer), "Short Break {unknown}");
+    }
+
+    #[test]
+    fn test_write_status_file_replaces_contents() {
+        let path = std::env::temp_dir().join(format!("rustdoro-status-{}.txt", std::process::id()));
+
+        write_status_file(&path, "first").unwrap();
+        write_status_file(&path, "second").unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(contents, "second\n");
+    }
+}
@@ -0,0 +1,101 @@
+use crate::config::MAX_SESSION_MINUTES;
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+
+/// Task from a task-list file, worked on for one work session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedTask {
+    pub name: String,
+    /// Length of the task's work session in minutes
+    pub minutes: u64,
+}
+
+/// Parse a task list with one `name:minutes` task per line
+/// Blank lines and lines starting with `#` are ignored. The name may contain
+/// colons itself, since only the text after the last one is the duration.
+pub fn parse_task_list(text: &str) -> Result<Vec<QueuedTask>> {
+    let mut tasks = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let task = parse_task_line(line).map_err(|e| anyhow!("line {}: {} in `{}`", index + 1, e, line))?;
+        tasks.push(task);
+    }
+    Ok(tasks)
+}
+
+/// Parse a single `name:minutes` line
+fn parse_task_line(line: &str) -> Result<QueuedTask> {
+    let (name, minutes) = line
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("expected `name:minutes`"))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("task name is empty"));
+    }
+    let minutes = minutes.trim();
+    let minutes: u64 = minutes
+        .parse()
+        .map_err(|_| anyhow!("`{}` is not a number of minutes", minutes))?;
+    if minutes == 0 {
+        return Err(anyhow!("duration must be at least 1 minute"));
+    }
+    if minutes > MAX_SESSION_MINUTES {
+        return Err(anyhow!("duration must be at most {} minutes (a week)", MAX_SESSION_MINUTES));
+    }
+    Ok(QueuedTask {
+        name: name.to_string(),
+        minutes,
+    })
+}
+
+/// Read and parse a task-list file
+pub fn load_task_list(path: &Path) -> Result<Vec<QueuedTask>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    parse_task_list(&text).with_context(|| format!("invalid task list {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, minutes: u64) -> QueuedTask {
+        QueuedTask {
+            name: name.to_string(),
+            minutes,
+        }
+    }
+
+    #[test]
+    fn test_parse_task_list() {
+        let text = "# Monday\nWrite report:50\n\n  Review PR 12 : 25 \nMeeting: notes:15\n";
+        assert_eq!(
+            parse_task_list(text).unwrap(),
+            vec![task("Write report", 50), task("Review PR 12", 25), task("Meeting: notes", 15)]
+        );
+        assert!(parse_task_list("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let error = parse_task_list("Write report:50\nEmails\n").unwrap_err().to_string();
+        assert_eq!(error, "line 2: expected `name:minutes` in `Emails`");
+
+        let error = parse_task_list("\nWrite report:fifty").unwrap_err().to_string();
+        assert_eq!(error, "line 2: `fifty` is not a number of minutes in `Write report:fifty`");
+
+        let error = parse_task_list(":25").unwrap_err().to_string();
+        assert_eq!(error, "line 1: task name is empty in `:25`");
+
+        let error = parse_task_list("Break it:0").unwrap_err().to_string();
+        assert_eq!(error, "line 1: duration must be at least 1 minute in `Break it:0`");
+
+        let error = parse_task_list("Ok:10080\nForever:18446744073709551615").unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "line 2: duration must be at most 10080 minutes (a week) in `Forever:18446744073709551615`"
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use std::time::{Duration, Instant};
-use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use crate::config::{Config, LabelsConfig, ResetTarget, MAX_SESSION_MINUTES};
+use crate::tasks::QueuedTask;
 
 /// Session types for the Pomodoro timer
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SessionType {
     Work,
     ShortBreak,
@@ -10,7 +13,23 @@ pub enum SessionType {
 }
 
 impl SessionType {
+    /// Get the display name for the session type
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            SessionType::Work => "Work",
+            SessionType::ShortBreak => "Short Break",
+            SessionType::LongBreak => "Long Break",
+        }
+    }
 
+    /// Get the name used in config files and JSON, such as "short_break"
+    pub fn key(&self) -> &'static str {
+        match self {
+            SessionType::Work => "work",
+            SessionType::ShortBreak => "short_break",
+            SessionType::LongBreak => "long_break",
+        }
+    }
 
     /// Get the emoji representation for the session type
     pub fn emoji(&self) -> &'static str {
@@ -22,8 +41,78 @@ impl SessionType {
     }
 }
 
+/// Task being worked on, with how many pomodoros it was expected to take
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Task {
+    pub name: String,
+    /// Expected pomodoros (0 means no estimate)
+    pub estimate: u32,
+    /// Pomodoros counted while the task was set
+    pub actual: u32,
+}
+
+impl Task {
+    pub fn new(name: String, estimate: u32) -> Self {
+        Self {
+            name,
+            estimate,
+            actual: 0,
+        }
+    }
+
+    /// Check if as many pomodoros as estimated have been spent
+    pub fn estimate_reached(&self) -> bool {
+        self.estimate > 0 && self.actual >= self.estimate
+    }
+
+    /// Check if the task took more pomodoros than estimated
+    pub fn over_estimate(&self) -> bool {
+        self.estimate > 0 && self.actual > self.estimate
+    }
+}
+
+/// One session in the session plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// Type of the session
+    pub session: SessionType,
+    /// Length in minutes, defaulting to the configured duration for the session type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutes: Option<u64>,
+}
+
+impl PlanStep {
+    /// Create a step that uses the configured duration for its session type
+    pub fn new(session: SessionType) -> Self {
+        Self { session, minutes: None }
+    }
+}
+
+/// Build the classic plan: work and short breaks, with a long break after every `per_set` pomodoros
+pub fn default_plan(per_set: u8) -> Vec<PlanStep> {
+    let mut plan = Vec::new();
+    for _ in 1..per_set.max(1) {
+        plan.push(PlanStep::new(SessionType::Work));
+        plan.push(PlanStep::new(SessionType::ShortBreak));
+    }
+    plan.push(PlanStep::new(SessionType::Work));
+    plan.push(PlanStep::new(SessionType::LongBreak));
+    plan
+}
+
+/// Position within the session plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct PlanCursor {
+    /// Index of the current step in the plan
+    position: usize,
+    /// Whether the current session is a short break after an uncounted skip, or
+    /// the planning break of `break_first`; the step at `position` follows it
+    detour: bool,
+}
+
 /// Timer states
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TimerState {
     /// Timer is currently running
     Running,
@@ -33,6 +122,109 @@ pub enum TimerState {
     Stopped,
 }
 
+/// Shortest break a count-up session can earn
+const MIN_EARNED_BREAK: Duration = Duration::from_secs(60);
+
+/// Work out the break earned by a count-up session of length `worked`
+/// The break is `worked * ratio`, at least a minute and at most `max` (zero means no limit).
+/// Without a limit it still never goes past `MAX_SESSION_MINUTES`.
+pub fn earned_break(worked: Duration, ratio: f64, max: Duration) -> Duration {
+    let longest = Duration::from_secs(MAX_SESSION_MINUTES * 60);
+    let earned = Duration::try_from_secs_f64(worked.as_secs_f64() * ratio)
+        .unwrap_or(longest)
+        .clamp(MIN_EARNED_BREAK, longest);
+    if max.is_zero() {
+        earned
+    } else {
+        earned.min(max)
+    }
+}
+
+/// Round a duration up to whole seconds
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Sessions at least this long show their times with hours
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Format a number of seconds as a clock, MM:SS or HH:MM:SS
+/// `hours` asks for HH:MM:SS even below an hour; an hour or more always shows them.
+pub fn format_clock(total_seconds: u64, hours: bool) -> String {
+    if hours || total_seconds >= HOUR.as_secs() {
+        let (hours, rest) = (total_seconds / 3600, total_seconds % 3600);
+        return format!("{:02}:{:02}:{:02}", hours, rest / 60, rest % 60);
+    }
+    format!("{:02}:{:02}", total_seconds / 60, total_seconds % 60)
+}
+
+/// Turn a number of minutes into a duration, clamped to `MAX_SESSION_MINUTES`
+/// Config validation and the task-list parser already warn about longer values, so
+/// this stays quiet and only guards against the multiplication overflowing.
+pub fn minutes_to_duration(minutes: u64) -> Duration {
+    Duration::from_secs(minutes.min(MAX_SESSION_MINUTES) * 60)
+}
+
+/// Snapshot of the session state taken before each transition, used for undo
+#[derive(Debug, Clone, Copy)]
+struct TransitionSnapshot {
+    session: SessionType,
+    remaining_time: Duration,
+    elapsed_time: Duration,
+    state: TimerState,
+    session_length: Duration,
+    cursor: PlanCursor,
+    plan_finished: bool,
+    earned_break: Option<Duration>,
+    worked_time: Duration,
+    paused_time: Duration,
+    pomodoros_completed: u32,
+    set_progress: u8,
+    consecutive_break_skips: u32,
+    task_actual: Option<u32>,
+    queue_position: usize,
+    overtime: Option<Duration>,
+    goal_progress: u32,
+    goal_reached: bool,
+    work_duration: Duration,
+}
+
+/// Serializable picture of a timer's session, counts and durations
+/// Times are whole seconds, so a snapshot never refers to the clock it was taken with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerSnapshot {
+    pub session: SessionType,
+    pub state: TimerState,
+    pub remaining_secs: u64,
+    pub session_length_secs: u64,
+    /// Time worked so far in a count-up session
+    pub elapsed_secs: u64,
+    /// Time worked past the end of the session, while in overtime
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overtime_secs: Option<u64>,
+    pub work_secs: u64,
+    pub short_break_secs: u64,
+    pub long_break_secs: u64,
+    pub pomodoros_completed: u32,
+    /// Work sessions counted since the last long break
+    pub set_progress: u8,
+    pub long_break_after_pomodoros: u8,
+    pub target_pomodoros: u32,
+    pub goal_progress: u32,
+    /// Index of the current step in the session plan
+    pub plan_position: usize,
+    /// Whether the session is a short break after an uncounted skip
+    #[serde(default)]
+    pub detour: bool,
+    /// Whether any session was started, so the standby screen is past
+    #[serde(default)]
+    pub started: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<Task>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
 /// Main timer structure that manages Pomodoro session state
 #[derive(Debug)]
 pub struct Timer {
@@ -48,46 +240,302 @@ pub struct Timer {
     pub short_break_duration: Duration,
     /// Duration for long breaks
     pub long_break_duration: Duration,
+    /// Time each counted work session adds to `work_duration` (zero means no ramp)
+    ramp_step: Duration,
+    /// Longest `work_duration` the ramp grows to (zero means no limit)
+    ramp_max: Duration,
+    /// Whether breaks grow in proportion to the ramped work sessions
+    ramp_breaks: bool,
+    /// Work session length the ramp starts from, and returns to on `reset`
+    ramp_start: Duration,
+    /// Full length of the current session
+    session_length: Duration,
+    /// Sequence of sessions the timer moves through
+    plan: Vec<PlanStep>,
+    /// Whether the plan starts over after its last step
+    loop_plan: bool,
+    /// Whether the timer opens with a short break before the first work session
+    break_first: bool,
+    /// Session `reset` goes back to
+    reset_to: ResetTarget,
+    /// Current position within the plan
+    cursor: PlanCursor,
+    /// Whether a plan that doesn't loop has run out, until `reset`
+    plan_finished: bool,
+    /// Set when the plan runs out, cleared by `take_plan_finished`
+    plan_finished_signal: bool,
     /// Number of completed pomodoros
     pub pomodoros_completed: u32,
     /// Last time the timer was updated (for precise timing)
     pub last_update_time: Option<Instant>,
-    /// Count of short breaks taken (used to determine long breaks)
-    pub break_count: u8,
+    /// Work sessions counted since the last long break
+    /// Only a long break or a reset clears it, so the lifetime total never shifts the set.
+    set_progress: u8,
     /// Number of pomodoros before a long break
     pub long_break_after_pomodoros: u8,
+    /// Number of pomodoros to complete in this run (0 means no goal)
+    target_pomodoros: u32,
+    /// Work sessions that ran to completion (skipped ones don't count toward the goal)
+    goal_progress: u32,
+    /// Whether the pomodoro goal has been reached
+    goal_reached: bool,
+    /// Set when the goal is reached, cleared by `take_goal_reached`
+    goal_reached_signal: bool,
+    /// Whether skipped work sessions still count as completed pomodoros
+    count_skipped: bool,
+    /// Set when a work session is counted, cleared by `take_completed_work`
+    completed_work_signal: bool,
+    /// Set when undo takes back a counted work session that was already taken, cleared by `take_undone_work`
+    undone_work_signal: bool,
+    /// State before the most recent skip/complete, restored by `undo_last_transition`
+    last_transition: Option<TransitionSnapshot>,
+    /// Whether work sessions count up until ended manually
+    count_up: bool,
+    /// Whether the break after a count-up session scales with the time worked
+    count_up_break: bool,
+    /// Fraction of the time worked that a count-up session earns as a break
+    break_ratio: f64,
+    /// Longest break a count-up session can earn (zero means no limit)
+    max_break: Duration,
+    /// Break earned by the last count-up session, kept until the next work session
+    earned_break: Option<Duration>,
+    /// Time worked in the current count-up work session
+    elapsed_time: Duration,
+    /// Whether a work session keeps counting past its end until ended manually
+    allow_overtime: bool,
+    /// Time worked past the end of the current work session, while in overtime
+    overtime: Option<Duration>,
+    /// Overtime of the last work session that ended
+    last_overtime: Duration,
+    /// Set when a work session runs into overtime, cleared by `take_overtime_started`
+    overtime_started_signal: bool,
+    /// Time left in a work session when it starts warning that it is nearly over (zero is off)
+    warn_threshold: Duration,
+    /// Whether the current session has already warned, so it only warns once
+    low_time_warned: bool,
+    /// Set when a work session starts warning, cleared by `take_low_time_warning`
+    low_time_signal: bool,
+    /// Time the current or last work session actually ran
+    worked_time: Duration,
+    /// Time the current or last work session spent paused
+    paused_time: Duration,
+    /// When the current pause started, while paused
+    paused_at: Option<Instant>,
+    /// Names and emoji shown for the session types
+    labels: LabelsConfig,
+    /// Times a running work session was paused since the timer was created or reset
+    interruptions: u32,
+    /// Whether any session was started since the timer was created or reset
+    started: bool,
+    /// Breaks skipped in a row, cleared when a break runs to its end
+    consecutive_break_skips: u32,
+    /// Task the counted pomodoros go to, if one was set
+    task: Option<Task>,
+    /// Set when the task reaches its estimate, cleared by `take_estimate_reached`
+    estimate_reached_signal: bool,
+    /// Project or client the pomodoros are filed under, kept across tasks
+    tag: Option<String>,
+    /// Task the last counted work session went to
+    completed_task: Option<String>,
+    /// Tasks loaded from a task-list file, each taking one work session
+    task_queue: Vec<QueuedTask>,
+    /// Index of the queued task the current or next work session is for
+    queue_position: usize,
+    /// Set when the last queued task is done, cleared by `take_queue_finished`
+    queue_finished_signal: bool,
+    /// Whether the timer runs a single session that ends without a break or a pomodoro
+    one_shot: bool,
 }
 
 impl Timer {
     /// Create a new timer instance with the given configuration
     pub fn new(config: Config) -> Self {
-        let work_duration = Duration::from_secs(config.work_duration_minutes() * 60);
+        let work_duration = minutes_to_duration(config.work_duration_minutes());
         
-        Self {
+        let mut timer = Self {
             current_session: SessionType::Work,
             remaining_time: work_duration,
             state: TimerState::Stopped,
             work_duration,
-            short_break_duration: Duration::from_secs(config.short_break_duration_minutes() * 60),
-            long_break_duration: Duration::from_secs(config.long_break_duration_minutes() * 60),
+            short_break_duration: minutes_to_duration(config.short_break_duration_minutes()),
+            long_break_duration: minutes_to_duration(config.long_break_duration_minutes()),
+            ramp_step: minutes_to_duration(config.time.ramp_step_minutes),
+            ramp_max: minutes_to_duration(config.time.ramp_max_minutes),
+            ramp_breaks: config.time.ramp_breaks,
+            ramp_start: work_duration,
+            session_length: work_duration,
+            plan: config.plan(),
+            loop_plan: config.time.loop_plan,
+            break_first: config.time.break_first,
+            reset_to: config.time.reset_to,
+            cursor: PlanCursor::default(),
+            plan_finished: false,
+            plan_finished_signal: false,
             pomodoros_completed: 0,
             last_update_time: None,
-            break_count: 0,
+            set_progress: 0,
             long_break_after_pomodoros: config.long_break_after_pomodoros(),
+            target_pomodoros: config.target_pomodoros(),
+            goal_progress: 0,
+            goal_reached: false,
+            goal_reached_signal: false,
+            count_skipped: config.time.count_skipped,
+            completed_work_signal: false,
+            undone_work_signal: false,
+            last_transition: None,
+            count_up: config.count_up(),
+            count_up_break: config.count_up_break(),
+            break_ratio: config.time.break_ratio,
+            max_break: minutes_to_duration(config.time.max_break_minutes),
+            earned_break: None,
+            elapsed_time: Duration::ZERO,
+            allow_overtime: config.time.overtime,
+            overtime: None,
+            last_overtime: Duration::ZERO,
+            overtime_started_signal: false,
+            warn_threshold: Duration::from_secs(config.time.warn_threshold_secs),
+            low_time_warned: false,
+            low_time_signal: false,
+            worked_time: Duration::ZERO,
+            paused_time: Duration::ZERO,
+            paused_at: None,
+            labels: config.labels.clone(),
+            interruptions: 0,
+            started: false,
+            consecutive_break_skips: 0,
+            task: None,
+            estimate_reached_signal: false,
+            tag: None,
+            completed_task: None,
+            task_queue: Vec::new(),
+            queue_position: 0,
+            queue_finished_signal: false,
+            one_shot: false,
+        };
+        timer.enter_step(timer.start_cursor());
+        timer
+    }
+
+    /// Create a timer for a single countdown of `length`
+    /// It ends without moving on to a break or counting a pomodoro, and can't be
+    /// started again until it is reset.
+    pub fn one_shot(config: Config, length: Duration) -> Self {
+        let mut timer = Self::new(config);
+        timer.plan = vec![PlanStep::new(SessionType::Work)];
+        timer.loop_plan = false;
+        timer.work_duration = length;
+        timer.ramp_step = Duration::ZERO;
+        timer.count_up = false;
+        timer.allow_overtime = false;
+        timer.break_first = false;
+        timer.one_shot = true;
+        timer.enter_step(PlanCursor::default());
+        timer
+    }
+
+    /// Check if the timer runs a single countdown without a cycle
+    pub fn is_one_shot(&self) -> bool {
+        self.one_shot
+    }
+
+    /// Take a serializable snapshot of the session, counts and durations
+    pub fn to_snapshot(&self) -> TimerSnapshot {
+        TimerSnapshot {
+            session: self.current_session,
+            state: self.state,
+            remaining_secs: self.remaining_time.as_secs(),
+            session_length_secs: self.session_length.as_secs(),
+            elapsed_secs: self.elapsed_time.as_secs(),
+            overtime_secs: self.overtime.map(|overtime| overtime.as_secs()),
+            work_secs: self.work_duration.as_secs(),
+            short_break_secs: self.short_break_duration.as_secs(),
+            long_break_secs: self.long_break_duration.as_secs(),
+            pomodoros_completed: self.pomodoros_completed,
+            set_progress: self.set_progress,
+            long_break_after_pomodoros: self.long_break_after_pomodoros,
+            target_pomodoros: self.target_pomodoros,
+            goal_progress: self.goal_progress,
+            plan_position: self.cursor.position,
+            detour: self.cursor.detour,
+            started: self.started,
+            task: self.task.clone(),
+            tag: self.tag.clone(),
         }
     }
 
+    /// Describe the timer as a line of JSON, see [`TimerSnapshot`]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.to_snapshot())
+    }
+
+    /// Put the timer back in the state of a snapshot
+    /// A running snapshot carries on counting from now, and a plan position past
+    /// the end of this timer's plan starts the plan over.
+    pub fn restore(&mut self, snapshot: &TimerSnapshot) {
+        let now = Instant::now();
+        self.current_session = snapshot.session;
+        self.state = snapshot.state;
+        self.remaining_time = Duration::from_secs(snapshot.remaining_secs);
+        self.session_length = Duration::from_secs(snapshot.session_length_secs);
+        self.elapsed_time = Duration::from_secs(snapshot.elapsed_secs);
+        self.overtime = snapshot.overtime_secs.map(Duration::from_secs);
+        self.work_duration = Duration::from_secs(snapshot.work_secs);
+        self.short_break_duration = Duration::from_secs(snapshot.short_break_secs);
+        self.long_break_duration = Duration::from_secs(snapshot.long_break_secs);
+        self.pomodoros_completed = snapshot.pomodoros_completed;
+        self.set_progress = snapshot.set_progress;
+        self.long_break_after_pomodoros = snapshot.long_break_after_pomodoros;
+        self.target_pomodoros = snapshot.target_pomodoros;
+        self.goal_progress = snapshot.goal_progress;
+        self.goal_reached = self.target_pomodoros > 0 && self.goal_progress >= self.target_pomodoros;
+        self.cursor = if snapshot.plan_position < self.plan.len() {
+            PlanCursor {
+                position: snapshot.plan_position,
+                detour: snapshot.detour,
+            }
+        } else {
+            PlanCursor::default()
+        };
+        self.plan_finished = false;
+        self.started = snapshot.started;
+        self.task = snapshot.task.clone();
+        self.tag = snapshot.tag.clone();
+        self.last_transition = None;
+        self.last_update_time = (self.state == TimerState::Running).then_some(now);
+        self.paused_at = (self.state == TimerState::Paused).then_some(now);
+    }
+
     /// Start or resume the current session timer
+    /// Does nothing once a plan that doesn't loop has run out.
     pub fn start(&mut self) {
+        if self.plan_finished {
+            return;
+        }
+        let now = Instant::now();
+        self.end_pause(now);
+        self.started = true;
         self.state = TimerState::Running;
-        self.last_update_time = Some(Instant::now());
+        self.last_update_time = Some(now);
     }
 
     /// Pause the current session timer
     pub fn pause(&mut self) {
         if self.state == TimerState::Running {
+            if self.current_session == SessionType::Work {
+                self.interruptions += 1;
+            }
             self.state = TimerState::Paused;
             self.last_update_time = None;
+            self.paused_at = Some(Instant::now());
+        }
+    }
+
+    /// Add the pause that is ending now to the paused time of a work session
+    fn end_pause(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            if self.current_session == SessionType::Work {
+                self.paused_time += now.saturating_duration_since(paused_at);
+            }
         }
     }
 
@@ -108,9 +556,50 @@ impl Timer {
     }
 
     /// Skip the current session and move to the next one
+    /// Ending a count-up work session or overtime this way is a normal completion.
     pub fn skip_session(&mut self) -> bool {
-        self.remaining_time = Duration::ZERO;
-        self.complete_session()
+        let skipped = !self.is_counting_up() && !self.is_in_overtime();
+        self.complete_session(skipped)
+    }
+
+    /// Give the current session a different length, starting it over
+    /// Later sessions keep their configured lengths.
+    pub fn set_session_length(&mut self, length: Duration) {
+        self.remaining_time = length;
+        self.session_length = length;
+    }
+
+    /// Set the time left in the current session, at most its full length
+    /// A session in overtime goes back to counting down. Count-up sessions have no
+    /// time left, so they are left alone.
+    pub fn set_remaining(&mut self, remaining: Duration) {
+        if self.is_counting_up() {
+            return;
+        }
+        self.overtime = None;
+        self.remaining_time = remaining.min(self.session_length);
+    }
+
+    /// Add time to the current session
+    /// Count-up sessions get the time added to the elapsed time instead, and a
+    /// session in overtime goes back to counting down.
+    pub fn add_time(&mut self, amount: Duration) {
+        self.overtime = None;
+        if self.is_counting_up() {
+            self.elapsed_time += amount;
+        } else {
+            self.remaining_time += amount;
+        }
+    }
+
+    /// Remove time from the current session, stopping at zero
+    /// A running session that reaches zero completes on the next tick.
+    pub fn subtract_time(&mut self, amount: Duration) {
+        if self.is_counting_up() {
+            self.elapsed_time = self.elapsed_time.saturating_sub(amount);
+        } else {
+            self.remaining_time = self.remaining_time.saturating_sub(amount);
+        }
     }
 
     /// Update the timer state (should be called regularly, e.g., every second)
@@ -123,41 +612,96 @@ impl Timer {
         if let Some(last_update) = self.last_update_time {
             let elapsed = now.duration_since(last_update);
             
-            if self.remaining_time <= elapsed {
+            if self.is_counting_up() {
+                self.elapsed_time += elapsed;
+                self.worked_time += elapsed;
+                self.last_update_time = Some(now);
+            } else if let Some(overtime) = &mut self.overtime {
+                *overtime += elapsed;
+                self.worked_time += elapsed;
+                self.last_update_time = Some(now);
+            } else if self.remaining_time <= elapsed {
+                self.record_worked(self.remaining_time);
+                let over = elapsed - self.remaining_time;
                 self.remaining_time = Duration::ZERO;
                 self.last_update_time = Some(now);
-                return self.complete_session();
+                // Work runs on into overtime instead of ending
+                if self.allow_overtime && self.current_session == SessionType::Work {
+                    self.worked_time += over;
+                    self.overtime = Some(over);
+                    self.overtime_started_signal = true;
+                    return false;
+                }
+                return self.complete_session(false);
             } else {
+                self.record_worked(elapsed);
                 self.remaining_time -= elapsed;
                 self.last_update_time = Some(now);
+                if self.is_low_on_time() && !self.low_time_warned {
+                    self.low_time_warned = true;
+                    self.low_time_signal = true;
+                }
             }
         }
 
         false
     }
 
-    /// Complete the current session and transition to the next one
-    fn complete_session(&mut self) -> bool {
+    /// Count running time toward the current work session
+    fn record_worked(&mut self, elapsed: Duration) {
+        if self.current_session == SessionType::Work {
+            self.worked_time += elapsed;
+        }
+    }
+
+    /// Complete the current session and transition to the next step of the plan
+    fn complete_session(&mut self, skipped: bool) -> bool {
         let session_completed = true;
-        
-        match self.current_session {
-            SessionType::Work => {
-                self.pomodoros_completed += 1;
-                
-                // Determine if it's time for a long break
-                if self.pomodoros_completed % self.long_break_after_pomodoros as u32 == 0 {
-                    self.current_session = SessionType::LongBreak;
-                    self.remaining_time = self.long_break_duration;
-                    self.break_count = 0; // Reset break count after long break
-                } else {
-                    self.current_session = SessionType::ShortBreak;
-                    self.remaining_time = self.short_break_duration;
-                    self.break_count += 1;
-                }
+        self.end_pause(Instant::now());
+        if self.one_shot {
+            // The countdown stays at its end, there is nothing to move on to
+            self.remaining_time = Duration::ZERO;
+            self.plan_finished = true;
+            self.plan_finished_signal = true;
+            self.state = TimerState::Stopped;
+            self.last_update_time = None;
+            return session_completed;
+        }
+        self.last_transition = Some(self.snapshot());
+        self.last_overtime = self.overtime.unwrap_or_default();
+
+        let mut counted = true;
+        if self.current_session == SessionType::Work {
+            counted = !skipped || self.count_skipped;
+            if counted {
+                self.count_work();
+            }
+
+            if !skipped {
+                self.record_goal_progress();
+            }
+
+            // Count-up breaks scale with the time worked
+            if self.count_up && self.count_up_break {
+                self.earned_break = Some(earned_break(self.elapsed_time, self.break_ratio, self.max_break));
+            }
+        }
+
+        if self.current_session != SessionType::Work {
+            if skipped {
+                self.consecutive_break_skips += 1;
+            } else {
+                self.consecutive_break_skips = 0;
             }
-            SessionType::ShortBreak | SessionType::LongBreak => {
-                self.current_session = SessionType::Work;
-                self.remaining_time = self.work_duration;
+        }
+
+        match self.next_cursor(self.cursor, counted) {
+            Some(cursor) => self.enter_step(cursor),
+            None => {
+                // A plan that doesn't loop stops at its first step until reset
+                self.enter_step(PlanCursor::default());
+                self.plan_finished = true;
+                self.plan_finished_signal = true;
             }
         }
 
@@ -166,62 +710,2194 @@ impl Timer {
         session_completed
     }
 
-    /// Get the formatted display time (MM:SS)
-    pub fn get_display_time(&self) -> String {
-        let total_seconds = self.remaining_time.as_secs();
-        let minutes = total_seconds / 60;
-        let seconds = total_seconds % 60;
-        format!("{:02}:{:02}", minutes, seconds)
+    /// Count the current work session as a completed pomodoro
+    fn count_work(&mut self) {
+        self.pomodoros_completed += 1;
+        self.set_progress = self.set_progress.saturating_add(1);
+        self.completed_work_signal = true;
+        if let Some(task) = &mut self.task {
+            task.actual += 1;
+            if task.estimate > 0 && task.actual == task.estimate {
+                self.estimate_reached_signal = true;
+            }
+        }
+        self.completed_task = self.task.as_ref().map(|task| task.name.clone());
+
+        // Move on to the next queued task, dropping the last one once it is done
+        if self.queue_position < self.task_queue.len() {
+            self.queue_position += 1;
+            if self.queue_position == self.task_queue.len() {
+                self.task = None;
+                self.queue_finished_signal = true;
+            }
+        }
+        self.ramp_up();
     }
 
-    /// Get the current session type
-    pub fn get_session_type(&self) -> SessionType {
-        self.current_session
+    /// Lengthen the work sessions by one ramp step, up to `ramp_max`
+    /// The ramp never goes below where it started, even with a lower `ramp_max`.
+    /// Count-up sessions have no set length to ramp.
+    fn ramp_up(&mut self) {
+        if self.ramp_step.is_zero() || self.count_up {
+            return;
+        }
+        let mut next = self.work_duration + self.ramp_step;
+        if !self.ramp_max.is_zero() {
+            next = next.min(self.ramp_max.max(self.ramp_start));
+        }
+        self.work_duration = next;
     }
 
-    /// Get the number of completed pomodoros
-    pub fn get_pomodoros_completed(&self) -> u32 {
-        self.pomodoros_completed
+    /// Get a break length, scaled with the ramp when `ramp_breaks` is set
+    fn ramped_break(&self, length: Duration) -> Duration {
+        if !self.ramp_breaks || self.ramp_start.is_zero() {
+            return length;
+        }
+        let ratio = self.work_duration.as_secs_f64() / self.ramp_start.as_secs_f64();
+        Duration::from_secs((length.as_secs_f64() * ratio).round() as u64)
     }
 
+    /// Jump to a fresh session of the given type, wherever the plan is
+    /// The plan carries on from the next step of that type, or from the current
+    /// step if it has none. Leaving an unfinished work session counts it only
+    /// with `count_skipped`, like a skip.
+    pub fn switch_to(&mut self, session: SessionType) {
+        if self.one_shot {
+            return;
+        }
+        self.end_pause(Instant::now());
+        self.last_transition = Some(self.snapshot());
+        self.last_overtime = self.overtime.unwrap_or_default();
 
+        // A session in overtime is already done, so leaving it always counts
+        let done = self.count_skipped || self.is_in_overtime();
+        if self.current_session == SessionType::Work && session != SessionType::Work && done {
+            self.count_work();
+        }
 
-    /// Check if the timer is currently running
-    pub fn is_running(&self) -> bool {
-        self.state == TimerState::Running
-    }
+        let len = self.plan.len();
+        let found = (0..len)
+            .map(|offset| (self.cursor.position + offset) % len)
+            .find(|&position| self.plan[position].session == session);
+        match found {
+            Some(position) => self.enter_step(PlanCursor { position, detour: false }),
+            None => {
+                self.enter_step(PlanCursor { detour: false, ..self.cursor });
+                self.enter_session_outside_plan(session);
+            }
+        }
 
-    /// Check if the timer is paused
-    pub fn is_paused(&self) -> bool {
-        self.state == TimerState::Paused
+        self.state = TimerState::Stopped;
+        self.last_update_time = None;
     }
 
-    /// Check if the timer is stopped
-    pub fn is_stopped(&self) -> bool {
-        self.state == TimerState::Stopped
+    /// Get the length of the next short break, the earned one after a count-up session
+    fn short_break_length(&self) -> Duration {
+        self.earned_break.unwrap_or_else(|| self.ramped_break(self.short_break_duration))
     }
 
-    /// Get the progress percentage of the current session (0.0 to 1.0)
-    pub fn get_progress(&self) -> f64 {
-        let total_duration = match self.current_session {
+    /// Turn the current step into a session of a type the plan doesn't have
+    fn enter_session_outside_plan(&mut self, session: SessionType) {
+        let length = match session {
             SessionType::Work => self.work_duration,
-            SessionType::ShortBreak => self.short_break_duration,
-            SessionType::LongBreak => self.long_break_duration,
+            SessionType::ShortBreak => self.short_break_length(),
+            SessionType::LongBreak => self.ramped_break(self.long_break_duration),
         };
+        self.current_session = session;
+        self.remaining_time = length;
+        self.session_length = length;
+    }
+
+    /// Work out where the plan goes once the session at `cursor` ends
+    /// An uncounted work session never earns the next step, so it is followed by a
+    /// short break and then repeated. Returns `None` when a plan that doesn't loop
+    /// has run out.
+    fn next_cursor(&self, cursor: PlanCursor, counted: bool) -> Option<PlanCursor> {
+        if cursor.detour {
+            return Some(PlanCursor { detour: false, ..cursor });
+        }
+        if self.plan[cursor.position].session == SessionType::Work && !counted {
+            return Some(PlanCursor { detour: true, ..cursor });
+        }
 
-        let elapsed = total_duration - self.remaining_time;
-        elapsed.as_secs_f64() / total_duration.as_secs_f64()
+        let position = cursor.position + 1;
+        if position < self.plan.len() {
+            Some(PlanCursor { position, detour: false })
+        } else if self.loop_plan {
+            Some(PlanCursor::default())
+        } else {
+            None
+        }
     }
 
-    /// Reset the timer to initial state
-    pub fn reset(&mut self) {
-        self.current_session = SessionType::Work;
-        self.remaining_time = self.work_duration;
-        self.state = TimerState::Stopped;
-        self.pomodoros_completed = 0;
+    /// Go straight to the first work session if the `break_first` planning break is up
+    /// Only a timer that hasn't started yet is changed; a later reset opens with the
+    /// planning break again.
+    pub fn skip_planning_break(&mut self) {
+        if !self.started && self.cursor == self.start_cursor() && self.cursor.detour {
+            self.enter_step(PlanCursor { detour: false, ..self.cursor });
+        }
+    }
+
+    /// Get where the timer starts and resets to
+    /// With `break_first`, a plan that opens with work gets a short break in front of it.
+    /// Later rounds of a looping plan go straight to work.
+    fn start_cursor(&self) -> PlanCursor {
+        let opens_with_work = self.plan.first().is_some_and(|step| step.session == SessionType::Work);
+        PlanCursor {
+            position: 0,
+            detour: self.break_first && opens_with_work,
+        }
+    }
+
+    /// Get the session type and length at a plan position
+    fn step_at(&self, cursor: PlanCursor) -> (SessionType, Duration) {
+        if cursor.detour {
+            return (SessionType::ShortBreak, self.short_break_length());
+        }
+
+        let step = self.plan[cursor.position];
+        let length = match step.minutes {
+            Some(minutes) => minutes_to_duration(minutes),
+            None => match step.session {
+                SessionType::Work => self.work_duration,
+                SessionType::ShortBreak => self.short_break_length(),
+                SessionType::LongBreak => self.ramped_break(self.long_break_duration),
+            },
+        };
+        (step.session, length)
+    }
+
+    /// Get the type and length of the next `n` sessions, without changing the timer
+    /// Assumes each session runs to its end, so every work session is counted and
+    /// moves the plan and the task queue along.
+    pub fn peek_next_sessions(&self, n: usize) -> Vec<(SessionType, Duration)> {
+        let mut upcoming = Vec::with_capacity(n);
+        if self.one_shot || self.plan_finished {
+            return upcoming;
+        }
+
+        let mut cursor = self.cursor;
+        let mut queue_position = self.queue_position;
+        if self.current_session == SessionType::Work {
+            queue_position = (queue_position + 1).min(self.task_queue.len());
+        }
+        while upcoming.len() < n {
+            let Some(next) = self.next_cursor(cursor, true) else {
+                break;
+            };
+            let (session, mut length) = self.step_at(next);
+            if session == SessionType::Work {
+                if let Some(queued) = self.queued_length(queue_position) {
+                    length = queued;
+                }
+                queue_position = (queue_position + 1).min(self.task_queue.len());
+            }
+            upcoming.push((session, length));
+            cursor = next;
+        }
+        upcoming
+    }
+
+    /// Move to a plan position and set up its session
+    fn enter_step(&mut self, cursor: PlanCursor) {
+        let (session, mut length) = self.step_at(cursor);
+        if session == SessionType::Work {
+            if let Some(queued) = self.apply_queued_task() {
+                length = queued;
+            }
+        }
+        self.cursor = cursor;
+        self.current_session = session;
+        self.remaining_time = length;
+        self.session_length = length;
+        self.overtime = None;
+        self.low_time_warned = false;
+        self.low_time_signal = false;
+
+        match session {
+            SessionType::Work => {
+                self.elapsed_time = Duration::ZERO;
+                self.worked_time = Duration::ZERO;
+                self.paused_time = Duration::ZERO;
+                self.earned_break = None;
+            }
+            SessionType::ShortBreak => {}
+            SessionType::LongBreak => self.set_progress = 0,
+        }
+    }
+
+    /// Make the current queued task the task being worked on
+    /// Returns the length of its work session, or None once the queue is empty.
+    fn apply_queued_task(&mut self) -> Option<Duration> {
+        let length = self.queued_length(self.queue_position)?;
+        let queued = &self.task_queue[self.queue_position];
+        if self.task.as_ref().map(|task| &task.name) != Some(&queued.name) {
+            self.task = Some(Task::new(queued.name.clone(), 0));
+        }
+        Some(length)
+    }
+
+    /// Get the work session length of the queued task at `position`, if there is one
+    fn queued_length(&self, position: usize) -> Option<Duration> {
+        self.task_queue
+            .get(position)
+            .map(|queued| minutes_to_duration(queued.minutes))
+    }
+
+    /// Work through `tasks` in order, one work session each
+    /// A work session that hasn't started yet takes on the first task right away.
+    pub fn set_task_queue(&mut self, tasks: Vec<QueuedTask>) {
+        self.task_queue = tasks;
+        self.queue_position = 0;
+        self.queue_finished_signal = false;
+        if self.current_session == SessionType::Work && self.is_stopped() {
+            self.enter_step(self.cursor);
+        }
+    }
+
+    /// Get the queued tasks that haven't been done yet, starting with the current one
+    pub fn queued_tasks(&self) -> &[QueuedTask] {
+        &self.task_queue[self.queue_position.min(self.task_queue.len())..]
+    }
+
+    /// Return true once right after the last queued task is done
+    pub fn take_queue_finished(&mut self) -> bool {
+        std::mem::take(&mut self.queue_finished_signal)
+    }
+
+    /// Get the task the last counted work session went to
+    pub fn last_completed_task(&self) -> Option<&str> {
+        self.completed_task.as_deref()
+    }
+
+    /// Get the configured session names and emoji
+    pub fn labels(&self) -> &LabelsConfig {
+        &self.labels
+    }
+
+    /// Get how many work sessions have been counted since the last long break
+    pub fn get_set_progress(&self) -> u8 {
+        self.set_progress
+    }
+
+    /// Get the position in the set as (work sessions done, sessions per set)
+    /// A long break shows the set it rewards as full, e.g. (4, 4).
+    pub fn get_cycle_position(&self) -> (u8, u8) {
+        let size = self.set_size();
+        if self.current_session == SessionType::LongBreak {
+            return (size, size);
+        }
+        (self.set_progress.min(size), size)
+    }
+
+    /// Count the work steps of the plan in the set the current step belongs to
+    /// A set runs up to and including the next long break, or to the end of the plan.
+    fn set_size(&self) -> u8 {
+        let position = self.cursor.position.min(self.plan.len().saturating_sub(1));
+        let is_long_break = |step: &PlanStep| step.session == SessionType::LongBreak;
+        let start = self.plan[..position].iter().rposition(is_long_break).map_or(0, |index| index + 1);
+        let end = self.plan[position..]
+            .iter()
+            .position(is_long_break)
+            .map_or(self.plan.len(), |index| position + index);
+        let work = self.plan[start..end]
+            .iter()
+            .filter(|step| step.session == SessionType::Work)
+            .count();
+        u8::try_from(work).unwrap_or(u8::MAX).max(1)
+    }
+
+    /// Get the session plan the timer moves through
+    pub fn plan(&self) -> &[PlanStep] {
+        &self.plan
+    }
+
+    /// Check if a plan that doesn't loop has run out
+    pub fn is_plan_finished(&self) -> bool {
+        self.plan_finished
+    }
+
+    /// Return true once right after a plan that doesn't loop runs out
+    pub fn take_plan_finished(&mut self) -> bool {
+        std::mem::take(&mut self.plan_finished_signal)
+    }
+
+    /// Capture the state needed to undo the next transition
+    fn snapshot(&self) -> TransitionSnapshot {
+        TransitionSnapshot {
+            session: self.current_session,
+            remaining_time: self.remaining_time,
+            elapsed_time: self.elapsed_time,
+            state: self.state,
+            session_length: self.session_length,
+            cursor: self.cursor,
+            plan_finished: self.plan_finished,
+            earned_break: self.earned_break,
+            worked_time: self.worked_time,
+            paused_time: self.paused_time,
+            pomodoros_completed: self.pomodoros_completed,
+            set_progress: self.set_progress,
+            consecutive_break_skips: self.consecutive_break_skips,
+            task_actual: self.task.as_ref().map(|task| task.actual),
+            queue_position: self.queue_position,
+            overtime: self.overtime,
+            goal_progress: self.goal_progress,
+            goal_reached: self.goal_reached,
+            work_duration: self.work_duration,
+        }
+    }
+
+    /// Undo the most recent skip or session completion
+    /// Returns false if there is nothing to undo.
+    pub fn undo_last_transition(&mut self) -> bool {
+        let Some(snapshot) = self.last_transition.take() else {
+            return false;
+        };
+
+        self.current_session = snapshot.session;
+        self.remaining_time = snapshot.remaining_time;
+        self.elapsed_time = snapshot.elapsed_time;
+        self.overtime = snapshot.overtime;
+        self.overtime_started_signal = false;
+        self.session_length = snapshot.session_length;
+        self.cursor = snapshot.cursor;
+        self.plan_finished = snapshot.plan_finished;
+        self.plan_finished_signal = false;
+        self.earned_break = snapshot.earned_break;
+        self.worked_time = snapshot.worked_time;
+        self.paused_time = snapshot.paused_time;
+        self.set_progress = snapshot.set_progress;
+        self.consecutive_break_skips = snapshot.consecutive_break_skips;
+        if snapshot.queue_position != self.queue_position {
+            self.queue_position = snapshot.queue_position;
+            self.queue_finished_signal = false;
+            self.apply_queued_task();
+        }
+        if let (Some(task), Some(actual)) = (&mut self.task, snapshot.task_actual) {
+            task.actual = actual;
+        }
+        self.estimate_reached_signal = false;
+        self.goal_progress = snapshot.goal_progress;
+        self.goal_reached = snapshot.goal_reached;
+        self.work_duration = snapshot.work_duration;
+        self.goal_reached_signal = false;
+        // A counted session nobody took yet never got recorded, so there is nothing to take back
+        let uncounted = self.pomodoros_completed > snapshot.pomodoros_completed;
+        if uncounted && !std::mem::take(&mut self.completed_work_signal) {
+            self.undone_work_signal = true;
+        }
+        self.completed_work_signal = false;
+        self.pomodoros_completed = snapshot.pomodoros_completed;
+        // Don't let time jump forward for a session that was running
+        self.state = match snapshot.state {
+            TimerState::Running => TimerState::Paused,
+            state => state,
+        };
+        self.paused_at = (self.state == TimerState::Paused).then(Instant::now);
         self.last_update_time = None;
-        self.break_count = 0;
+        true
+    }
+
+    /// Get the session that ended with the most recent skip or completion
+    pub fn last_ended_session(&self) -> Option<SessionType> {
+        self.last_transition.as_ref().map(|snapshot| snapshot.session)
+    }
+
+    /// Get the full length of the session that ended with the most recent skip or completion
+    pub fn last_ended_length(&self) -> Option<Duration> {
+        self.last_transition.as_ref().map(|snapshot| snapshot.session_length)
+    }
+
+    /// Count a completed work session toward the pomodoro goal
+    fn record_goal_progress(&mut self) {
+        if self.target_pomodoros == 0 || self.goal_reached {
+            return;
+        }
+
+        self.goal_progress += 1;
+        if self.goal_progress >= self.target_pomodoros {
+            self.goal_reached = true;
+            self.goal_reached_signal = true;
+        }
+    }
+
+    /// Check if the pomodoro goal has been reached
+    pub fn is_goal_reached(&self) -> bool {
+        self.goal_reached
+    }
+
+    /// Get the work sessions counted toward the pomodoro goal
+    pub fn get_goal_progress(&self) -> u32 {
+        self.goal_progress
     }
 
+    /// Return true once right after the goal is reached
+    pub fn take_goal_reached(&mut self) -> bool {
+        std::mem::take(&mut self.goal_reached_signal)
+    }
+
+    /// Set the task that counted pomodoros go to
+    /// Setting the current task again only changes its estimate.
+    pub fn set_task(&mut self, name: String, estimate: u32) {
+        match &mut self.task {
+            Some(task) if task.name == name => task.estimate = estimate,
+            _ => self.task = Some(Task::new(name, estimate)),
+        }
+    }
+
+    /// Stop counting pomodoros toward a task
+    pub fn clear_task(&mut self) {
+        self.task = None;
+    }
+
+    /// Get the task being worked on
+    pub fn task(&self) -> Option<&Task> {
+        self.task.as_ref()
+    }
+
+    /// File the following pomodoros under a tag, written with or without its `#`
+    /// A blank tag stops tagging.
+    pub fn set_tag(&mut self, tag: &str) {
+        let tag = tag.trim().trim_start_matches('#').trim();
+        self.tag = (!tag.is_empty()).then(|| tag.to_string());
+    }
+
+    /// Get the tag pomodoros are filed under, without its `#`
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// Return true once when the task reaches its estimate
+    pub fn take_estimate_reached(&mut self) -> bool {
+        std::mem::take(&mut self.estimate_reached_signal)
+    }
+
+    /// Return true once after each work session counted as a completed pomodoro
+    pub fn take_completed_work(&mut self) -> bool {
+        std::mem::take(&mut self.completed_work_signal)
+    }
 
-} 
\ No newline at end of file
+    /// Return true once after undo took back a work session reported by `take_completed_work`
+    pub fn take_undone_work(&mut self) -> bool {
+        std::mem::take(&mut self.undone_work_signal)
+    }
+
+    /// Check if the current session counts up instead of down
+    pub fn is_counting_up(&self) -> bool {
+        self.count_up && self.current_session == SessionType::Work
+    }
+
+    /// Check if work sessions count up instead of down, whatever the current session
+    pub fn is_count_up_mode(&self) -> bool {
+        self.count_up
+    }
+
+    /// Get how long the current or last work session actually ran, excluding pauses
+    pub fn get_net_work_duration(&self) -> Duration {
+        self.worked_time
+    }
+
+    /// Get how long the current or last work session was paused
+    pub fn get_paused_duration(&self) -> Duration {
+        self.paused_time
+    }
+
+    /// Get how often work sessions were paused since the timer was created or reset
+    pub fn get_interruptions(&self) -> u32 {
+        self.interruptions
+    }
+
+    /// Get how many breaks were skipped in a row
+    pub fn get_consecutive_break_skips(&self) -> u32 {
+        self.consecutive_break_skips
+    }
+
+    /// Get the break earned by the last count-up session, while it is still ahead or running
+    pub fn get_earned_break(&self) -> Option<Duration> {
+        match self.current_session {
+            SessionType::Work => None,
+            _ => self.earned_break,
+        }
+    }
+
+    /// Get the planned length of the current session
+    pub fn get_session_length(&self) -> Duration {
+        self.session_length
+    }
+
+    /// Get the time worked in the current count-up session
+    pub fn get_elapsed(&self) -> Duration {
+        self.elapsed_time
+    }
+
+    /// Check if a counting-down work session is within `warn_threshold` of its end
+    /// Sessions no longer than the threshold never warn, since they would from the start.
+    pub fn is_low_on_time(&self) -> bool {
+        !self.warn_threshold.is_zero()
+            && self.current_session == SessionType::Work
+            && !self.is_counting_up()
+            && !self.is_in_overtime()
+            && !self.one_shot
+            && self.session_length > self.warn_threshold
+            && self.remaining_time <= self.warn_threshold
+    }
+
+    /// Return true once when a work session gets within `warn_threshold` of its end
+    pub fn take_low_time_warning(&mut self) -> bool {
+        std::mem::take(&mut self.low_time_signal)
+    }
+
+    /// Check if the work session has run past its end and is counting overtime
+    pub fn is_in_overtime(&self) -> bool {
+        self.overtime.is_some()
+    }
+
+    /// Get the time worked past the end of the current work session, while in overtime
+    pub fn get_overtime(&self) -> Option<Duration> {
+        self.overtime
+    }
+
+    /// Get the overtime of the last work session that ended, zero if it had none
+    pub fn last_overtime(&self) -> Duration {
+        self.last_overtime
+    }
+
+    /// Return true once when a work session runs into overtime
+    pub fn take_overtime_started(&mut self) -> bool {
+        std::mem::take(&mut self.overtime_started_signal)
+    }
+
+    /// Check if the session's times are shown as HH:MM:SS, being an hour or longer
+    /// Count-up sessions have no set length, so they only show hours once they need them.
+    pub fn shows_hours(&self) -> bool {
+        !self.is_counting_up() && self.session_length >= HOUR
+    }
+
+    /// Get the formatted display time (MM:SS, or HH:MM:SS for sessions of an hour or more)
+    /// Shows the elapsed time for count-up sessions, the overtime as "+MM:SS" and
+    /// the remaining time otherwise.
+    ///
+    /// Remaining time is rounded up to the next whole second, so a fresh session shows
+    /// "25:00" and "00:00" only appears once the session is actually over. Elapsed time
+    /// is rounded down like a stopwatch.
+    pub fn get_display_time(&self) -> String {
+        if let Some(overtime) = self.overtime {
+            return format!("+{}", format_clock(overtime.as_secs(), self.shows_hours()));
+        }
+        let total_seconds = if self.is_counting_up() {
+            self.get_elapsed().as_secs()
+        } else {
+            ceil_secs(self.remaining_time)
+        };
+        format_clock(total_seconds, self.shows_hours())
+    }
+
+    /// Get the formatted time spent in the current session, in the same format as `get_display_time`
+    /// Rounded down like a stopwatch, so it adds up with `get_display_time` to the
+    /// session length. Time added on top of the session length counts as none spent.
+    pub fn get_elapsed_display(&self) -> String {
+        if self.is_counting_up() {
+            return self.get_display_time();
+        }
+        let overtime = self.overtime.unwrap_or_default();
+        let elapsed = self.session_length.saturating_sub(self.remaining_time) + overtime;
+        format_clock(elapsed.as_secs(), self.shows_hours())
+    }
+
+    /// Get the current session type
+    pub fn get_session_type(&self) -> SessionType {
+        self.current_session
+    }
+
+    /// Get the number of pomodoros to complete in this run (0 means no target)
+    pub fn get_target_pomodoros(&self) -> u32 {
+        self.target_pomodoros
+    }
+
+    /// Check if skipped work sessions still count as completed pomodoros
+    pub fn counts_skipped(&self) -> bool {
+        self.count_skipped
+    }
+
+    /// Check if the plan starts over once it runs out
+    pub fn loops_plan(&self) -> bool {
+        self.loop_plan
+    }
+
+    /// Check if the timer opens with a planning break before the first work session
+    pub fn opens_with_break(&self) -> bool {
+        self.break_first
+    }
+
+    /// Get where a reset goes back to
+    pub fn reset_target(&self) -> ResetTarget {
+        self.reset_to
+    }
+
+    /// Check if work sessions run into overtime instead of ending
+    pub fn allows_overtime(&self) -> bool {
+        self.allow_overtime
+    }
+
+    /// Get how close to its end a work session is low on time
+    pub fn get_warn_threshold(&self) -> Duration {
+        self.warn_threshold
+    }
+
+    /// Get the number of completed pomodoros
+    pub fn get_pomodoros_completed(&self) -> u32 {
+        self.pomodoros_completed
+    }
+
+    /// Correct the completed pomodoros by `delta`, never going below zero
+    /// Only the count changes; the session, the set position and the plan stay where they
+    /// are. Returns the change that was actually made.
+    pub fn adjust_count(&mut self, delta: i32) -> i32 {
+        let before = self.pomodoros_completed;
+        self.pomodoros_completed = before.saturating_add_signed(delta);
+        self.pomodoros_completed as i32 - before as i32
+    }
+
+
+
+    /// Check if the timer is currently running
+    pub fn is_running(&self) -> bool {
+        self.state == TimerState::Running
+    }
+
+    /// Check if the timer is paused
+    pub fn is_paused(&self) -> bool {
+        self.state == TimerState::Paused
+    }
+
+    /// Check if the timer is stopped
+    pub fn is_stopped(&self) -> bool {
+        self.state == TimerState::Stopped
+    }
+
+    /// Check if no session was started yet since the timer was created or reset
+    /// Unlike `is_stopped`, this stays false once a session has ended.
+    pub fn is_standby(&self) -> bool {
+        self.state == TimerState::Stopped && !self.started
+    }
+
+    /// Get the progress percentage of the current session (0.0 to 1.0)
+    /// Count-up sessions have no fixed length, so their progress is always 0.0.
+    pub fn get_progress(&self) -> f64 {
+        if self.is_counting_up() {
+            return 0.0;
+        }
+
+        // A session without a length has nothing to make progress through
+        if self.session_length.is_zero() {
+            return 0.0;
+        }
+
+        // Time added on top of the session length shows as no progress
+        let elapsed = self.session_length.saturating_sub(self.remaining_time);
+        elapsed.as_secs_f64() / self.session_length.as_secs_f64()
+    }
+
+    /// Reset the timer to initial state
+    /// With `reset_to` set to `last`, the current session starts over at its full
+    /// length instead of going back to the start of the plan.
+    pub fn reset(&mut self) {
+        self.set_progress = 0;
+        self.earned_break = None;
+        if !self.ramp_step.is_zero() {
+            self.work_duration = self.ramp_start;
+        }
+        match self.reset_to {
+            ResetTarget::Work => self.enter_step(self.start_cursor()),
+            ResetTarget::Last => {
+                let session = self.current_session;
+                self.enter_step(self.cursor);
+                if self.current_session != session {
+                    // Switched to with a number key, so it isn't the plan's step
+                    self.enter_session_outside_plan(session);
+                }
+            }
+        }
+        self.elapsed_time = Duration::ZERO;
+        self.worked_time = Duration::ZERO;
+        self.paused_time = Duration::ZERO;
+        self.paused_at = None;
+        self.last_overtime = Duration::ZERO;
+        self.overtime_started_signal = false;
+        self.interruptions = 0;
+        self.consecutive_break_skips = 0;
+        self.started = false;
+        self.state = TimerState::Stopped;
+        self.pomodoros_completed = 0;
+        self.last_update_time = None;
+        self.plan_finished = false;
+        self.plan_finished_signal = false;
+        self.goal_progress = 0;
+        self.goal_reached = false;
+        self.goal_reached_signal = false;
+        self.completed_work_signal = false;
+        self.last_transition = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramped_timer(step: u64, max: u64) -> Timer {
+        let mut config = Config::default();
+        config.time.work_minutes = 15;
+        config.time.ramp_step_minutes = step;
+        config.time.ramp_max_minutes = max;
+        Timer::new(config)
+    }
+
+    fn timer_with_target(target: u32) -> Timer {
+        let mut config = Config::default();
+        config.time.target_pomodoros = target;
+        Timer::new(config)
+    }
+
+    /// Run the current session to its natural end
+    fn finish_session(timer: &mut Timer) -> bool {
+        timer.start();
+        timer.remaining_time = Duration::ZERO;
+        timer.tick()
+    }
+
+    #[test]
+    fn test_default_emoji_code_points() {
+        assert_eq!(SessionType::Work.emoji(), "\u{1F345}");
+        assert_eq!(SessionType::ShortBreak.emoji(), "\u{2615}");
+        assert_eq!(SessionType::LongBreak.emoji(), "\u{1F3D6}\u{FE0F}");
+        assert_eq!(SessionType::Work.emoji().as_bytes(), [0xF0, 0x9F, 0x8D, 0x85]);
+    }
+
+    #[test]
+    fn test_goal_reached_fires_once() {
+        let mut timer = timer_with_target(2);
+        let mut signals = 0;
+
+        for _ in 0..8 {
+            assert!(finish_session(&mut timer));
+            if timer.take_goal_reached() {
+                signals += 1;
+                assert_eq!(timer.get_pomodoros_completed(), 2);
+            }
+        }
+
+        assert_eq!(signals, 1);
+        assert!(timer.is_goal_reached());
+    }
+
+    #[test]
+    fn test_skipped_work_does_not_count_toward_goal() {
+        let mut timer = timer_with_target(1);
+
+        timer.skip_session();
+        assert!(!timer.is_goal_reached());
+        assert!(!timer.take_goal_reached());
+
+        timer.skip_session(); // Skip the break back to work
+        finish_session(&mut timer);
+        assert!(timer.take_goal_reached());
+    }
+
+    #[test]
+    fn test_reset_clears_goal() {
+        let mut timer = timer_with_target(1);
+
+        finish_session(&mut timer);
+        assert!(timer.is_goal_reached());
+
+        timer.reset();
+        assert!(!timer.is_goal_reached());
+        assert!(!timer.take_goal_reached());
+        assert_eq!(timer.goal_progress, 0);
+    }
+
+    #[test]
+    fn test_adjust_count_clamps_at_zero() {
+        let mut timer = Timer::new(Config::default());
+        assert_eq!(timer.adjust_count(-1), 0);
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+
+        finish_session(&mut timer);
+        assert_eq!(timer.get_cycle_position(), (1, 4));
+        timer.start();
+
+        assert_eq!(timer.adjust_count(3), 3);
+        assert_eq!(timer.get_pomodoros_completed(), 4);
+        assert_eq!(timer.adjust_count(-10), -4);
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+
+        // The session keeps running and the set position is unchanged
+        assert!(timer.is_running());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.get_cycle_position(), (1, 4));
+    }
+
+    #[test]
+    fn test_undo_at_startup_is_noop() {
+        let mut timer = Timer::new(Config::default());
+
+        assert!(timer.last_transition.is_none());
+        assert!(!timer.undo_last_transition());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.remaining_time, timer.work_duration);
+        assert!(timer.is_stopped());
+    }
+
+    #[test]
+    fn test_undo_skip_restores_session() {
+        let mut config = Config::default();
+        config.time.count_skipped = true;
+        let mut timer = Timer::new(config);
+        timer.start();
+        timer.remaining_time = Duration::from_secs(600);
+
+        timer.skip_session();
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.remaining_time, Duration::from_secs(600));
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert_eq!(timer.set_progress, 0);
+        assert!(timer.is_paused());
+
+        // Only one level of history is kept
+        assert!(!timer.undo_last_transition());
+    }
+
+    #[test]
+    fn test_undo_work_completion_restores_goal() {
+        let mut timer = timer_with_target(1);
+
+        finish_session(&mut timer);
+        assert!(timer.is_goal_reached());
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+
+        assert!(timer.undo_last_transition());
+        assert!(!timer.is_goal_reached());
+        assert!(!timer.take_goal_reached());
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert_eq!(timer.goal_progress, 0);
+    }
+
+    #[test]
+    fn test_undo_break_completion() {
+        let mut timer = Timer::new(Config::default());
+
+        timer.skip_session();
+        timer.skip_session();
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.remaining_time, timer.short_break_duration);
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+    }
+
+    #[test]
+    fn test_add_and_subtract_time() {
+        let mut timer = Timer::new(Config::default());
+
+        timer.add_time(Duration::from_secs(60));
+        assert_eq!(timer.get_display_time(), "26:00");
+        assert_eq!(timer.get_progress(), 0.0);
+
+        timer.subtract_time(Duration::from_secs(120));
+        assert_eq!(timer.get_display_time(), "24:00");
+
+        timer.subtract_time(Duration::from_secs(60 * 60));
+        assert_eq!(timer.remaining_time, Duration::ZERO);
+        timer.start();
+        assert!(timer.tick());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+    }
+
+    fn skip_counting_timer(count_skipped: bool) -> Timer {
+        let mut config = Config::default();
+        config.time.count_skipped = count_skipped;
+        Timer::new(config)
+    }
+
+    #[test]
+    fn test_natural_completion_counts_pomodoro() {
+        let mut timer = skip_counting_timer(false);
+
+        finish_session(&mut timer);
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert!(timer.take_completed_work());
+        assert!(!timer.take_completed_work());
+
+        // Finishing a break is not a pomodoro
+        finish_session(&mut timer);
+        assert!(!timer.take_completed_work());
+    }
+
+    #[test]
+    fn test_skipped_work_not_counted_by_default() {
+        let mut timer = skip_counting_timer(false);
+
+        for _ in 0..4 {
+            timer.skip_session();
+            assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+            timer.skip_session();
+        }
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert!(!timer.take_completed_work());
+    }
+
+    #[test]
+    fn test_skipped_work_counted_when_enabled() {
+        let mut timer = skip_counting_timer(true);
+
+        timer.skip_session();
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert!(timer.take_completed_work());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+    }
+
+    #[test]
+    fn test_flowtime_counts_up_and_earns_break() {
+        let mut config = Config::default();
+        config.time.technique = Some(crate::config::Technique::Flowtime);
+        let mut timer = Timer::new(config);
+        assert!(timer.is_counting_up());
+
+        timer.start();
+        timer.last_update_time = Some(Instant::now() - Duration::from_secs(30 * 60));
+        assert!(!timer.tick());
+        assert!(timer.elapsed_time >= Duration::from_secs(30 * 60));
+        assert_eq!(timer.get_display_time(), "30:00");
+        assert_eq!(timer.get_progress(), 0.0);
+
+        // Ending a flowtime session counts as a completed pomodoro
+        timer.skip_session();
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert_eq!(timer.remaining_time.as_secs(), 6 * 60);
+        assert!(!timer.is_counting_up());
+
+        timer.skip_session();
+        assert!(timer.is_counting_up());
+        assert_eq!(timer.elapsed_time, Duration::ZERO);
+    }
+
+    fn count_up_timer(count_up_break: bool) -> Timer {
+        let mut config = Config::default();
+        config.time.count_up = true;
+        config.time.count_up_break = count_up_break;
+        Timer::new(config)
+    }
+
+    /// Pretend `secs` seconds passed since the last tick
+    fn advance(timer: &mut Timer, secs: u64) -> bool {
+        if timer.last_update_time.is_some() {
+            timer.last_update_time = Some(Instant::now() - Duration::from_secs(secs));
+        }
+        timer.tick()
+    }
+
+    #[test]
+    fn test_count_up_state_machine() {
+        let mut timer = count_up_timer(false);
+        assert!(timer.is_counting_up());
+        assert_eq!(timer.get_display_time(), "00:00");
+
+        // Stopped and paused timers don't accumulate
+        assert!(!advance(&mut timer, 60));
+        assert_eq!(timer.get_elapsed(), Duration::ZERO);
+
+        timer.start();
+        assert!(!advance(&mut timer, 90));
+        assert_eq!(timer.get_elapsed().as_secs(), 90);
+        assert_eq!(timer.get_display_time(), "01:30");
+        assert_eq!(timer.get_progress(), 0.0);
+
+        timer.pause();
+        assert!(!advance(&mut timer, 600));
+        assert_eq!(timer.get_elapsed().as_secs(), 90);
+
+        // Never completes on its own, even far past the work duration
+        timer.resume();
+        assert!(!advance(&mut timer, 60 * 60));
+        assert!(timer.is_running());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+
+        // Ending the session is a normal completion with the configured break
+        timer.skip_session();
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.remaining_time, timer.short_break_duration);
+        assert_eq!(timer.short_break_duration, Duration::from_secs(5 * 60));
+
+        // Breaks count down as usual
+        assert!(!timer.is_counting_up());
+        timer.start();
+        assert!(!advance(&mut timer, 60));
+        assert_eq!(timer.get_display_time(), "04:00");
+        assert!(advance(&mut timer, 4 * 60));
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.get_elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_count_up_proportional_break() {
+        let mut timer = count_up_timer(true);
+
+        timer.start();
+        advance(&mut timer, 50 * 60);
+        timer.skip_session();
+        assert_eq!(timer.remaining_time.as_secs(), 10 * 60);
+    }
+
+    #[test]
+    fn test_session_length_applies_to_current_session_only() {
+        let mut timer = Timer::new(Config::default());
+        timer.set_session_length(Duration::from_secs(95 * 60));
+        assert_eq!(timer.remaining_time, Duration::from_secs(95 * 60));
+        assert_eq!(timer.get_progress(), 0.0);
+
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.remaining_time, Duration::from_secs(25 * 60));
+    }
+
+    #[test]
+    fn test_standby_until_first_start() {
+        let mut timer = Timer::new(Config::default());
+        assert!(timer.is_standby());
+
+        timer.start();
+        assert!(!timer.is_standby());
+        // Stopped after a completion is not standby
+        finish_session(&mut timer);
+        assert!(timer.is_stopped());
+        assert!(!timer.is_standby());
+
+        timer.reset();
+        assert!(timer.is_standby());
+    }
+
+    #[test]
+    fn test_task_estimate_comparison() {
+        let mut task = Task::new("Report".to_string(), 3);
+        assert!(!task.estimate_reached());
+        task.actual = 3;
+        assert!(task.estimate_reached());
+        assert!(!task.over_estimate());
+        task.actual = 4;
+        assert!(task.over_estimate());
+
+        // Without an estimate there is nothing to reach
+        let mut open = Task::new("Inbox".to_string(), 0);
+        open.actual = 5;
+        assert!(!open.estimate_reached());
+        assert!(!open.over_estimate());
+    }
+
+    #[test]
+    fn test_set_tag_strips_the_hash() {
+        let mut timer = Timer::new(Config::default());
+        assert_eq!(timer.tag(), None);
+        timer.set_tag(" #client-a ");
+        assert_eq!(timer.tag(), Some("client-a"));
+        timer.set_tag("writing");
+        assert_eq!(timer.tag(), Some("writing"));
+
+        // The tag outlives resets and tasks
+        timer.reset();
+        timer.set_task("Report".to_string(), 2);
+        timer.clear_task();
+        assert_eq!(timer.tag(), Some("writing"));
+
+        timer.set_tag("#");
+        assert_eq!(timer.tag(), None);
+    }
+
+    #[test]
+    fn test_task_counts_completed_work() {
+        let mut timer = Timer::new(Config::default());
+        finish_session(&mut timer);
+        timer.set_task("Report".to_string(), 2);
+        assert_eq!(timer.task().unwrap().actual, 0);
+
+        // Breaks and uncounted skips don't count toward the task
+        finish_session(&mut timer);
+        timer.skip_session();
+        finish_session(&mut timer);
+        assert_eq!(timer.task().unwrap().actual, 0);
+
+        finish_session(&mut timer);
+        assert_eq!(timer.task().unwrap().actual, 1);
+        assert!(!timer.take_estimate_reached());
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        assert_eq!(timer.task().unwrap().actual, 2);
+        assert!(timer.take_estimate_reached());
+        assert!(!timer.take_estimate_reached());
+
+        // Undo takes the pomodoro back from the task
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.task().unwrap().actual, 1);
+
+        // A new estimate for the same task keeps its count, a new task starts over
+        timer.set_task("Report".to_string(), 4);
+        assert_eq!(timer.task().unwrap(), &Task { name: "Report".to_string(), estimate: 4, actual: 1 });
+        timer.set_task("Slides".to_string(), 1);
+        assert_eq!(timer.task().unwrap().actual, 0);
+        timer.clear_task();
+        assert!(timer.task().is_none());
+    }
+
+    fn queued(name: &str, minutes: u64) -> QueuedTask {
+        QueuedTask {
+            name: name.to_string(),
+            minutes,
+        }
+    }
+
+    #[test]
+    fn test_task_queue_sets_work_sessions() {
+        let mut timer = Timer::new(Config::default());
+        timer.set_task_queue(vec![queued("Report", 50), queued("Emails", 10)]);
+        assert_eq!(timer.task().unwrap().name, "Report");
+        assert_eq!(timer.remaining_time, Duration::from_secs(50 * 60));
+
+        // A skipped session doesn't use up its task
+        timer.start();
+        timer.skip_session();
+        timer.skip_session();
+        assert_eq!(timer.task().unwrap().name, "Report");
+        assert_eq!(timer.queued_tasks().len(), 2);
+
+        finish_session(&mut timer);
+        assert_eq!(timer.last_completed_task(), Some("Report"));
+        // Breaks keep their configured length
+        assert_eq!(timer.remaining_time, timer.short_break_duration);
+        assert_eq!(timer.task().unwrap().name, "Report");
+        finish_session(&mut timer);
+        assert_eq!(timer.task().unwrap().name, "Emails");
+        assert_eq!(timer.remaining_time, Duration::from_secs(10 * 60));
+
+        // Undo goes back to the task that was just finished
+        finish_session(&mut timer);
+        assert!(timer.take_queue_finished());
+        assert!(timer.task().is_none());
+        assert!(timer.queued_tasks().is_empty());
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.task().unwrap().name, "Emails");
+        assert_eq!(timer.session_length, Duration::from_secs(10 * 60));
+
+        // An empty queue falls back to the configured duration
+        finish_session(&mut timer);
+        assert_eq!(timer.last_completed_task(), Some("Emails"));
+        finish_session(&mut timer);
+        assert_eq!(timer.remaining_time, timer.work_duration);
+        assert!(timer.task().is_none());
+    }
+
+    #[test]
+    fn test_one_shot_ends_without_a_break() {
+        let mut timer = Timer::one_shot(Config::default(), Duration::from_secs(10 * 60));
+        assert!(timer.is_one_shot());
+        assert_eq!(timer.get_display_time(), "10:00");
+
+        assert!(finish_session(&mut timer));
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.get_display_time(), "00:00");
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert!(!timer.take_completed_work());
+        assert!(timer.take_plan_finished());
+
+        // Nothing runs again, and there is no break to skip or switch to
+        timer.start();
+        assert!(timer.is_stopped());
+        timer.skip_session();
+        timer.switch_to(SessionType::ShortBreak);
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert!(!timer.undo_last_transition());
+
+        // Resetting starts the countdown over
+        timer.reset();
+        assert_eq!(timer.get_display_time(), "10:00");
+        timer.start();
+        assert!(timer.is_running());
+    }
+
+    fn overtime_timer() -> Timer {
+        let mut config = Config::default();
+        config.time.overtime = true;
+        Timer::new(config)
+    }
+
+    #[test]
+    fn test_overtime_counts_past_the_end() {
+        let mut timer = overtime_timer();
+        timer.start();
+        timer.remaining_time = Duration::from_secs(5);
+        timer.last_update_time = Some(Instant::now() - Duration::from_secs(8));
+
+        // Reaching zero flips into overtime instead of ending the session
+        assert!(!timer.tick());
+        assert!(timer.is_in_overtime());
+        assert!(timer.is_running());
+        assert!(timer.take_overtime_started());
+        assert!(!timer.take_overtime_started());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.get_overtime().unwrap().as_secs(), 3);
+        assert_eq!(timer.get_display_time(), "+00:03");
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+
+        timer.last_update_time = Some(Instant::now() - Duration::from_secs(120));
+        assert!(!timer.tick());
+        assert_eq!(timer.get_display_time(), "+02:03");
+
+        // Pausing stops the overtime too
+        timer.pause();
+        assert!(!timer.tick());
+        assert_eq!(timer.get_display_time(), "+02:03");
+
+        // Ending it by hand is a normal completion that keeps the overage
+        assert!(timer.skip_session());
+        assert!(!timer.is_in_overtime());
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert!(timer.take_completed_work());
+        assert_eq!(timer.last_overtime().as_secs(), 123);
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+
+        // Undo goes back into overtime
+        assert!(timer.undo_last_transition());
+        assert!(timer.is_in_overtime());
+        assert_eq!(timer.get_display_time(), "+02:03");
+    }
+
+    #[test]
+    fn test_low_time_warning_fires_once() {
+        let mut config = Config::default();
+        config.time.warn_threshold_secs = 60;
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        timer.remaining_time = Duration::from_secs(65);
+        assert!(!advance(&mut timer, 4));
+        assert!(!timer.is_low_on_time());
+        assert!(!timer.take_low_time_warning());
+
+        // Reaching the threshold warns once, however often it ticks after
+        assert!(!advance(&mut timer, 1));
+        assert!(timer.is_low_on_time());
+        assert!(timer.take_low_time_warning());
+        for _ in 0..3 {
+            advance(&mut timer, 5);
+            assert!(!timer.take_low_time_warning());
+        }
+
+        // Adding time ends the warning without arming it again
+        timer.add_time(Duration::from_secs(120));
+        assert!(!timer.is_low_on_time());
+        advance(&mut timer, 130);
+        assert!(timer.is_low_on_time());
+        assert!(!timer.take_low_time_warning());
+
+        // The next work session warns again, breaks never do
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        timer.start();
+        let almost_over = timer.remaining_time.as_secs() - 10;
+        advance(&mut timer, almost_over);
+        assert!(!timer.is_low_on_time());
+        assert!(!timer.take_low_time_warning());
+        finish_session(&mut timer);
+        timer.start();
+        let almost_over = timer.remaining_time.as_secs() - 30;
+        advance(&mut timer, almost_over);
+        assert!(timer.take_low_time_warning());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut config = Config::default();
+        config.time.target_pomodoros = 3;
+        let mut timer = Timer::new(config.clone());
+        timer.set_task("Report".to_string(), 4);
+        timer.set_tag("client-a");
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        timer.skip_session();
+        timer.start();
+        timer.remaining_time = Duration::from_secs(8 * 60 + 20);
+        timer.pause();
+
+        let json = timer.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["session"], "short_break");
+        assert_eq!(value["state"], "paused");
+        assert_eq!(value["remaining_secs"], 500);
+        assert_eq!(value["pomodoros_completed"], 1);
+
+        let snapshot: TimerSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot, timer.to_snapshot());
+        let mut restored = Timer::new(config);
+        restored.restore(&snapshot);
+        assert_eq!(restored.to_snapshot(), snapshot);
+        assert_eq!(restored.get_display_time(), timer.get_display_time());
+        assert_eq!(restored.get_cycle_position(), timer.get_cycle_position());
+        assert_eq!(restored.task(), timer.task());
+        assert!(restored.is_paused());
+
+        // The restored timer carries on through the plan like the original
+        finish_session(&mut timer);
+        finish_session(&mut restored);
+        assert_eq!(restored.to_snapshot(), timer.to_snapshot());
+    }
+
+    #[test]
+    fn test_breaks_and_default_config_have_no_overtime() {
+        let mut timer = Timer::new(Config::default());
+        assert!(finish_session(&mut timer));
+        assert!(!timer.is_in_overtime());
+        assert_eq!(timer.last_overtime(), Duration::ZERO);
+
+        let mut timer = overtime_timer();
+        timer.switch_to(SessionType::ShortBreak);
+        assert!(finish_session(&mut timer));
+        assert!(!timer.is_in_overtime());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+
+        // Adding time goes back to counting down, switching away counts the session
+        timer.start();
+        timer.remaining_time = Duration::ZERO;
+        timer.tick();
+        assert!(timer.is_in_overtime());
+        timer.add_time(Duration::from_secs(60));
+        assert!(!timer.is_in_overtime());
+        assert_eq!(timer.get_display_time(), "01:00");
+        timer.remaining_time = Duration::ZERO;
+        timer.tick();
+        timer.switch_to(SessionType::LongBreak);
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert!(!timer.is_in_overtime());
+    }
+
+    #[test]
+    fn test_switch_to_work() {
+        let mut timer = Timer::new(Config::default());
+        finish_session(&mut timer);
+        timer.start();
+        timer.remaining_time = Duration::from_secs(60);
+
+        timer.switch_to(SessionType::Work);
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.remaining_time, Duration::from_secs(25 * 60));
+        assert_eq!(timer.get_progress(), 0.0);
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+
+        // Switching to the session that is on starts it over
+        timer.start();
+        timer.remaining_time = Duration::from_secs(60);
+        timer.switch_to(SessionType::Work);
+        assert_eq!(timer.get_display_time(), "25:00");
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+    }
+
+    #[test]
+    fn test_switch_to_short_break() {
+        let mut timer = Timer::new(Config::default());
+        timer.start();
+        timer.switch_to(SessionType::ShortBreak);
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.remaining_time, Duration::from_secs(5 * 60));
+        // The unfinished work session isn't counted
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert!(!timer.take_completed_work());
+
+        // The plan goes on from the break as usual
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+
+        // Counted skips count switching away from work too
+        let mut config = Config::default();
+        config.time.count_skipped = true;
+        let mut timer = Timer::new(config);
+        timer.switch_to(SessionType::ShortBreak);
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert!(timer.take_completed_work());
+    }
+
+    #[test]
+    fn test_switch_to_long_break() {
+        let mut timer = Timer::new(Config::default());
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        assert_eq!(timer.get_set_progress(), 1);
+
+        timer.switch_to(SessionType::LongBreak);
+        assert_eq!(timer.get_session_type(), SessionType::LongBreak);
+        assert_eq!(timer.remaining_time, Duration::from_secs(15 * 60));
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+
+        // A new set starts after the long break
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.get_cycle_position(), (0, 4));
+
+        // A switch can be undone like a skip
+        let mut timer = Timer::new(Config::default());
+        finish_session(&mut timer);
+        timer.switch_to(SessionType::LongBreak);
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.get_set_progress(), 1);
+    }
+
+    #[test]
+    fn test_switch_to_session_missing_from_plan() {
+        let mut config = Config::default();
+        config.time.plan = vec![PlanStep::new(SessionType::Work), PlanStep::new(SessionType::ShortBreak)];
+        let mut timer = Timer::new(config);
+
+        timer.switch_to(SessionType::LongBreak);
+        assert_eq!(timer.get_session_type(), SessionType::LongBreak);
+        assert_eq!(timer.remaining_time, Duration::from_secs(15 * 60));
+    }
+
+    #[test]
+    fn test_consecutive_break_skips() {
+        let mut timer = Timer::new(Config::default());
+
+        // Work sessions, finished or skipped, don't count
+        for _ in 0..3 {
+            finish_session(&mut timer);
+            timer.skip_session();
+        }
+        timer.skip_session();
+        assert_eq!(timer.get_consecutive_break_skips(), 3);
+
+        // Undo takes back the last skipped break
+        timer.skip_session();
+        assert_eq!(timer.get_consecutive_break_skips(), 4);
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.get_consecutive_break_skips(), 3);
+
+        // A break taken in full clears the streak
+        finish_session(&mut timer);
+        assert_eq!(timer.get_consecutive_break_skips(), 0);
+        timer.skip_session();
+        timer.skip_session();
+        assert_eq!(timer.get_consecutive_break_skips(), 1);
+        timer.reset();
+        assert_eq!(timer.get_consecutive_break_skips(), 0);
+    }
+
+    #[test]
+    fn test_interruptions_count_work_pauses() {
+        let mut timer = Timer::new(Config::default());
+        timer.start();
+        timer.pause();
+        timer.pause();
+        timer.resume();
+        timer.pause();
+        assert_eq!(timer.get_interruptions(), 2);
+
+        // Pausing a break is not an interruption
+        timer.skip_session();
+        timer.start();
+        timer.pause();
+        assert_eq!(timer.get_interruptions(), 2);
+
+        timer.reset();
+        assert_eq!(timer.get_interruptions(), 0);
+    }
+
+    #[test]
+    fn test_net_work_duration_excludes_pauses() {
+        let mut timer = Timer::new(Config::default());
+        let planned = timer.work_duration;
+
+        timer.start();
+        advance(&mut timer, 10 * 60);
+        timer.pause();
+        // Five minutes away from the desk
+        timer.paused_at = Some(Instant::now() - Duration::from_secs(5 * 60));
+        timer.resume();
+        advance(&mut timer, 10 * 60);
+        timer.skip_session();
+
+        // Only the two running stretches count, even though the session was ended on time
+        let net = timer.get_net_work_duration();
+        let paused = timer.get_paused_duration();
+        assert_eq!(net.as_secs(), 20 * 60);
+        assert_eq!(paused.as_secs(), 5 * 60);
+        assert_eq!(planned.as_secs() - net.as_secs(), paused.as_secs());
+
+        // The totals stay available during the break and start over with the next work session
+        timer.start();
+        advance(&mut timer, 60);
+        assert_eq!(timer.get_net_work_duration().as_secs(), 20 * 60);
+        timer.skip_session();
+        assert_eq!(timer.get_net_work_duration(), Duration::ZERO);
+        assert_eq!(timer.get_paused_duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_natural_completion_has_full_net_duration() {
+        let mut timer = Timer::new(Config::default());
+        timer.start();
+        timer.pause();
+        timer.paused_at = Some(Instant::now() - Duration::from_secs(120));
+        timer.resume();
+        advance(&mut timer, 30 * 60);
+
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.get_net_work_duration(), timer.work_duration);
+        assert_eq!(timer.get_paused_duration().as_secs(), 120);
+    }
+
+    #[test]
+    fn test_earned_break_scales_and_caps() {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        let cap = minutes(30);
+
+        assert_eq!(earned_break(minutes(25), 0.2, cap), minutes(5));
+        assert_eq!(earned_break(minutes(30), 0.2, cap), minutes(6));
+        assert_eq!(earned_break(minutes(90), 0.5, cap), minutes(30));
+        assert_eq!(earned_break(minutes(90), 0.5, Duration::ZERO), minutes(45));
+        // Very short sessions still earn a minute
+        assert_eq!(earned_break(Duration::from_secs(20), 0.2, cap), minutes(1));
+        // A huge ratio is capped at the longest session instead of overflowing
+        let week = Duration::from_secs(MAX_SESSION_MINUTES * 60);
+        assert_eq!(earned_break(minutes(90), 1e30, Duration::ZERO), week);
+        assert_eq!(earned_break(minutes(90), 1e30, cap), cap);
+    }
+
+    #[test]
+    fn test_earned_break_is_exposed_until_work() {
+        let mut timer = count_up_timer(true);
+        timer.break_ratio = 0.25;
+        assert_eq!(timer.get_earned_break(), None);
+
+        timer.start();
+        timer.elapsed_time = Duration::from_secs(24 * 60);
+        timer.skip_session();
+        assert_eq!(timer.get_earned_break(), Some(Duration::from_secs(6 * 60)));
+        assert_eq!(timer.get_display_time(), "06:00");
+
+        timer.skip_session();
+        assert_eq!(timer.get_earned_break(), None);
+    }
+
+    #[test]
+    fn test_earned_break_leaves_configured_break_alone() {
+        let mut timer = count_up_timer(true);
+        timer.break_ratio = 0.25;
+        let configured = timer.short_break_duration;
+
+        timer.start();
+        timer.elapsed_time = Duration::from_secs(24 * 60);
+        timer.skip_session();
+        assert_eq!(timer.get_display_time(), "06:00");
+        assert_eq!(timer.short_break_duration, configured);
+        assert_eq!(timer.to_snapshot().short_break_secs, configured.as_secs());
+
+        // Undo goes back to the work session, and redoing it earns the break again
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.get_earned_break(), None);
+        timer.skip_session();
+        assert_eq!(timer.get_display_time(), "06:00");
+
+        timer.reset();
+        assert_eq!(timer.get_earned_break(), None);
+        timer.switch_to(SessionType::ShortBreak);
+        assert_eq!(timer.remaining_time, configured);
+    }
+
+    #[test]
+    fn test_count_up_reset_clears_elapsed() {
+        let mut timer = count_up_timer(false);
+
+        timer.start();
+        advance(&mut timer, 120);
+        timer.reset();
+        assert_eq!(timer.get_elapsed(), Duration::ZERO);
+        assert!(timer.is_stopped());
+        assert!(timer.is_counting_up());
+    }
+
+    #[test]
+    fn test_display_time_rounds_remaining_up() {
+        let mut timer = Timer::new(Config::default());
+        assert_eq!(timer.get_display_time(), "25:00");
+
+        let cases = [
+            (Duration::from_secs(25 * 60) - Duration::from_millis(100), "25:00"),
+            (Duration::from_millis(59_900), "01:00"),
+            (Duration::from_secs(60), "01:00"),
+            (Duration::from_millis(60_100), "01:01"),
+            (Duration::from_millis(900), "00:01"),
+            (Duration::from_millis(100), "00:01"),
+            (Duration::from_nanos(1), "00:01"),
+            (Duration::ZERO, "00:00"),
+        ];
+        for (remaining, expected) in cases {
+            timer.remaining_time = remaining;
+            assert_eq!(timer.get_display_time(), expected, "{:?}", remaining);
+        }
+    }
+
+    #[test]
+    fn test_elapsed_display_boundaries() {
+        let mut timer = Timer::new(Config::default());
+        assert_eq!(timer.get_elapsed_display(), "00:00");
+
+        let cases = [
+            (Duration::from_secs(25 * 60) - Duration::from_millis(100), "00:00"),
+            (Duration::from_secs(24 * 60), "01:00"),
+            (Duration::from_millis(24 * 60_000 + 100), "00:59"),
+            (Duration::from_secs(13 * 60), "12:00"),
+            (Duration::from_millis(100), "24:59"),
+            (Duration::ZERO, "25:00"),
+            // Time added beyond the session length
+            (Duration::from_secs(30 * 60), "00:00"),
+        ];
+        for (remaining, expected) in cases {
+            timer.remaining_time = remaining;
+            assert_eq!(timer.get_elapsed_display(), expected, "{:?}", remaining);
+        }
+
+        let mut timer = count_up_timer(false);
+        timer.elapsed_time = Duration::from_millis(61_900);
+        assert_eq!(timer.get_elapsed_display(), "01:01");
+
+        assert_eq!(format_clock(0, false), "00:00");
+        assert_eq!(format_clock(59 * 60 + 59, false), "59:59");
+        assert_eq!(format_clock(60 * 60, false), "01:00:00");
+        assert_eq!(format_clock(59, true), "00:00:59");
+    }
+
+    #[test]
+    fn test_long_sessions_show_hours() {
+        let mut config = Config::default();
+        config.time.work_minutes = 90;
+        let mut timer = Timer::new(config.clone());
+        assert_eq!(timer.get_display_time(), "01:30:00");
+        timer.start();
+        advance(&mut timer, 30 * 60 + 1);
+        assert_eq!(timer.get_display_time(), "00:59:59");
+        assert_eq!(timer.get_elapsed_display(), "00:30:01");
+
+        config.time.work_minutes = 1500;
+        let timer = Timer::new(config);
+        assert_eq!(timer.get_display_time(), "25:00:00");
+        assert_eq!(format_clock(100 * 3600 + 61, false), "100:01:01");
+    }
+
+    #[test]
+    fn test_huge_durations_are_clamped() {
+        let week = Duration::from_secs(MAX_SESSION_MINUTES * 60);
+        assert_eq!(minutes_to_duration(90), Duration::from_secs(90 * 60));
+        assert_eq!(minutes_to_duration(MAX_SESSION_MINUTES), week);
+        assert_eq!(minutes_to_duration(MAX_SESSION_MINUTES + 1), week);
+        assert_eq!(minutes_to_duration(u64::MAX), week);
+    }
+
+    #[test]
+    fn test_display_time_rounds_elapsed_down() {
+        let mut timer = count_up_timer(false);
+
+        timer.elapsed_time = Duration::from_millis(59_900);
+        assert_eq!(timer.get_display_time(), "00:59");
+        timer.elapsed_time = Duration::from_millis(900);
+        assert_eq!(timer.get_display_time(), "00:00");
+    }
+
+    #[test]
+    fn test_no_goal_never_fires() {
+        let mut timer = timer_with_target(0);
+
+        for _ in 0..4 {
+            finish_session(&mut timer);
+            assert!(!timer.take_goal_reached());
+        }
+    }
+
+    fn timer_with_plan(plan: Vec<PlanStep>, loop_plan: bool) -> Timer {
+        let mut config = Config::default();
+        config.time.plan = plan;
+        config.time.loop_plan = loop_plan;
+        Timer::new(config)
+    }
+
+    #[test]
+    fn test_default_plan_shape() {
+        use SessionType::*;
+        let sessions: Vec<SessionType> = default_plan(3).iter().map(|step| step.session).collect();
+        assert_eq!(sessions, [Work, ShortBreak, Work, ShortBreak, Work, LongBreak]);
+        assert_eq!(default_plan(0), default_plan(1));
+        assert_eq!(default_plan(1).len(), 2);
+    }
+
+    #[test]
+    fn test_default_plan_keeps_classic_cycle() {
+        use SessionType::*;
+        let mut timer = Timer::new(Config::default());
+        let mut sessions = Vec::new();
+
+        // An uncounted skip takes a short break and doesn't move toward the long break
+        timer.skip_session();
+        sessions.push(timer.get_session_type());
+        finish_session(&mut timer);
+        for _ in 0..8 {
+            finish_session(&mut timer);
+            sessions.push(timer.get_session_type());
+            finish_session(&mut timer);
+        }
+
+        assert_eq!(
+            sessions,
+            [ShortBreak, ShortBreak, ShortBreak, ShortBreak, LongBreak, ShortBreak, ShortBreak, ShortBreak, LongBreak]
+        );
+        assert_eq!(timer.get_pomodoros_completed(), 8);
+        assert_eq!(timer.get_session_type(), Work);
+    }
+
+    #[test]
+    fn test_long_break_follows_full_set_after_reset() {
+        use SessionType::*;
+        let mut config = Config::default();
+        config.time.tomatoes_per_set = 3;
+        let mut timer = Timer::new(config);
+
+        // Start a set, then reset halfway through it
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        assert_eq!(timer.get_set_progress(), 1);
+        timer.reset();
+        assert_eq!(timer.get_set_progress(), 0);
+
+        let mut breaks = Vec::new();
+        for _ in 0..3 {
+            finish_session(&mut timer);
+            breaks.push(timer.get_session_type());
+            finish_session(&mut timer);
+        }
+        assert_eq!(breaks, [ShortBreak, ShortBreak, LongBreak]);
+        assert_eq!(timer.get_set_progress(), 0);
+        assert_eq!(timer.get_pomodoros_completed(), 3);
+    }
+
+    #[test]
+    fn test_cycle_position_across_sets() {
+        let mut timer = Timer::new(Config::default());
+        assert_eq!(timer.get_cycle_position(), (0, 4));
+
+        let mut positions = Vec::new();
+        for _ in 0..5 {
+            // Finish the work session and look at the break that follows
+            finish_session(&mut timer);
+            positions.push(timer.get_cycle_position());
+            finish_session(&mut timer);
+            positions.push(timer.get_cycle_position());
+        }
+        assert_eq!(
+            positions,
+            [(1, 4), (1, 4), (2, 4), (2, 4), (3, 4), (3, 4), (4, 4), (0, 4), (1, 4), (1, 4)]
+        );
+        assert_eq!(timer.get_pomodoros_completed(), 5);
+    }
+
+    #[test]
+    fn test_set_progress_with_skips() {
+        use SessionType::*;
+        let mut config = Config::default();
+        config.time.tomatoes_per_set = 2;
+        let mut timer = Timer::new(config.clone());
+
+        // Skipping a break or an uncounted work session leaves the set alone
+        finish_session(&mut timer);
+        timer.skip_session();
+        assert_eq!(timer.get_set_progress(), 1);
+        timer.skip_session();
+        assert_eq!(timer.get_session_type(), ShortBreak);
+        timer.skip_session();
+        assert_eq!(timer.get_set_progress(), 1);
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), LongBreak);
+
+        // Counted skips complete the set like finished sessions
+        config.time.count_skipped = true;
+        let mut timer = Timer::new(config);
+        timer.skip_session();
+        timer.skip_session();
+        assert_eq!(timer.get_set_progress(), 1);
+        timer.skip_session();
+        assert_eq!(timer.get_session_type(), LongBreak);
+        assert_eq!(timer.get_set_progress(), 0);
+
+        // Undo puts the set back the way it was
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.get_set_progress(), 1);
+    }
+
+    #[test]
+    fn test_cycle_position_follows_the_plan() {
+        let mut timer = timer_with_plan(
+            vec![
+                PlanStep::new(SessionType::Work),
+                PlanStep::new(SessionType::ShortBreak),
+                PlanStep::new(SessionType::Work),
+                PlanStep::new(SessionType::LongBreak),
+                PlanStep::new(SessionType::Work),
+                PlanStep::new(SessionType::LongBreak),
+            ],
+            true,
+        );
+        assert_eq!(timer.get_cycle_position(), (0, 2));
+
+        let mut positions = Vec::new();
+        for _ in 0..6 {
+            finish_session(&mut timer);
+            positions.push(timer.get_cycle_position());
+        }
+        assert_eq!(positions, [(1, 2), (1, 2), (2, 2), (0, 1), (1, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn test_custom_plan_progression() {
+        let mut timer = timer_with_plan(
+            vec![
+                PlanStep { session: SessionType::Work, minutes: Some(50) },
+                PlanStep { session: SessionType::ShortBreak, minutes: Some(10) },
+                PlanStep::new(SessionType::LongBreak),
+            ],
+            true,
+        );
+        assert_eq!(timer.get_display_time(), "50:00");
+
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.get_display_time(), "10:00");
+        assert_eq!(timer.last_ended_length(), Some(Duration::from_secs(50 * 60)));
+
+        timer.remaining_time = Duration::from_secs(5 * 60);
+        assert_eq!(timer.get_progress(), 0.5);
+
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::LongBreak);
+        assert_eq!(timer.remaining_time, timer.long_break_duration);
+
+        // The plan wraps around to its first step
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.get_display_time(), "50:00");
+        assert!(!timer.take_plan_finished());
+    }
+
+    #[test]
+    fn test_peek_follows_the_cycle() {
+        use SessionType::*;
+        let mut timer = Timer::new(Config::default());
+        let sessions = |timer: &Timer, n| -> Vec<SessionType> {
+            timer.peek_next_sessions(n).into_iter().map(|(session, _)| session).collect()
+        };
+
+        assert_eq!(sessions(&timer, 8), [ShortBreak, Work, ShortBreak, Work, ShortBreak, Work, LongBreak, Work]);
+        assert_eq!(
+            timer.peek_next_sessions(2),
+            [(ShortBreak, timer.short_break_duration), (Work, timer.work_duration)]
+        );
+        assert!(timer.peek_next_sessions(0).is_empty());
+
+        // Peeking doesn't move the timer, and matches where it actually goes
+        let upcoming = timer.peek_next_sessions(7);
+        for expected in upcoming {
+            finish_session(&mut timer);
+            assert_eq!((timer.get_session_type(), timer.session_length), expected);
+        }
+        assert_eq!(timer.get_session_type(), LongBreak);
+        assert_eq!(sessions(&timer, 3), [Work, ShortBreak, Work]);
+
+        // After an uncounted skip the work session comes back after a short break
+        let mut timer = Timer::new(Config::default());
+        timer.skip_session();
+        assert_eq!(sessions(&timer, 3), [Work, ShortBreak, Work]);
+    }
+
+    #[test]
+    fn test_peek_uses_queued_tasks_and_plan_end() {
+        use SessionType::*;
+        let mut timer = Timer::new(Config::default());
+        timer.set_task_queue(vec![queued("Report", 50), queued("Emails", 10), queued("Review", 20)]);
+        let works: Vec<Duration> = timer
+            .peek_next_sessions(6)
+            .into_iter()
+            .filter(|(session, _)| *session == Work)
+            .map(|(_, length)| length)
+            .collect();
+        assert_eq!(works, [Duration::from_secs(10 * 60), Duration::from_secs(20 * 60), timer.work_duration]);
+
+        let mut timer = timer_with_plan(vec![PlanStep::new(Work), PlanStep::new(ShortBreak)], false);
+        assert_eq!(timer.peek_next_sessions(5), [(ShortBreak, timer.short_break_duration)]);
+        finish_session(&mut timer);
+        assert!(timer.peek_next_sessions(5).is_empty());
+        finish_session(&mut timer);
+        assert!(timer.is_plan_finished());
+        assert!(timer.peek_next_sessions(5).is_empty());
+    }
+
+    #[test]
+    fn test_break_first_opens_with_a_short_break() {
+        use SessionType::*;
+        let mut config = Config::default();
+        config.time.break_first = true;
+        let mut timer = Timer::new(config);
+        assert_eq!(timer.get_session_type(), ShortBreak);
+        assert_eq!(timer.remaining_time, timer.short_break_duration);
+        assert_eq!(timer.get_cycle_position(), Timer::new(Config::default()).get_cycle_position());
+
+        // The long break still comes after four pomodoros, and the next set starts with work
+        let mut sessions = Vec::new();
+        for _ in 0..10 {
+            finish_session(&mut timer);
+            sessions.push(timer.get_session_type());
+        }
+        assert_eq!(
+            sessions,
+            [Work, ShortBreak, Work, ShortBreak, Work, ShortBreak, Work, LongBreak, Work, ShortBreak]
+        );
+        assert_eq!(timer.get_pomodoros_completed(), 5);
+
+        timer.reset();
+        assert_eq!(timer.get_session_type(), ShortBreak);
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert_eq!(timer.peek_next_sessions(1), [(Work, timer.work_duration)]);
+
+        // Runs that start right away go straight to work
+        timer.skip_planning_break();
+        assert_eq!(timer.get_session_type(), Work);
+        assert_eq!(timer.remaining_time, timer.work_duration);
+        timer.set_session_length(Duration::from_secs(40 * 60));
+        timer.start();
+        finish_session(&mut timer);
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert_eq!(timer.last_ended_length(), Some(Duration::from_secs(40 * 60)));
+
+        // A plan that opens with a break is left alone
+        let mut config = Config::default();
+        config.time.break_first = true;
+        config.time.plan = vec![PlanStep::new(LongBreak), PlanStep::new(Work)];
+        let mut timer = Timer::new(config);
+        timer.skip_planning_break();
+        assert_eq!(timer.get_session_type(), LongBreak);
+    }
+
+    #[test]
+    fn test_plan_without_loop_stops_at_end() {
+        let mut timer = timer_with_plan(
+            vec![PlanStep::new(SessionType::Work), PlanStep::new(SessionType::ShortBreak)],
+            false,
+        );
+
+        finish_session(&mut timer);
+        assert!(!timer.is_plan_finished());
+        finish_session(&mut timer);
+        assert!(timer.is_plan_finished());
+        assert!(timer.take_plan_finished());
+        assert!(!timer.take_plan_finished());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+
+        // Nothing runs until the plan is reset
+        timer.toggle_pause();
+        assert!(timer.is_stopped());
+
+        assert!(timer.undo_last_transition());
+        assert!(!timer.is_plan_finished());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+
+        finish_session(&mut timer);
+        timer.reset();
+        assert!(!timer.is_plan_finished());
+        timer.toggle_pause();
+        assert!(timer.is_running());
+    }
+
+    #[test]
+    fn test_undo_restores_plan_position() {
+        let mut timer = timer_with_plan(
+            vec![
+                PlanStep::new(SessionType::Work),
+                PlanStep { session: SessionType::ShortBreak, minutes: Some(2) },
+                PlanStep::new(SessionType::Work),
+                PlanStep::new(SessionType::LongBreak),
+            ],
+            true,
+        );
+
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::LongBreak);
+
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::LongBreak);
+    }
+
+    #[test]
+    fn test_ramp_lengthens_work_up_to_the_cap() {
+        let mut timer = ramped_timer(5, 30);
+        let mut works = Vec::new();
+        while works.len() < 6 {
+            if timer.get_session_type() == SessionType::Work {
+                works.push(timer.session_length.as_secs() / 60);
+            }
+            finish_session(&mut timer);
+        }
+        assert_eq!(works, [15, 20, 25, 30, 30, 30]);
+        // Breaks keep their length
+        assert_eq!(timer.short_break_duration, Duration::from_secs(5 * 60));
+
+        timer.reset();
+        assert_eq!(timer.session_length, Duration::from_secs(15 * 60));
+    }
+
+    #[test]
+    fn test_ramp_only_grows_with_counted_work() {
+        let mut timer = ramped_timer(5, 0);
+        timer.skip_session();
+        finish_session(&mut timer);
+        assert_eq!(timer.session_length, Duration::from_secs(15 * 60));
+
+        finish_session(&mut timer);
+        finish_session(&mut timer);
+        assert_eq!(timer.session_length, Duration::from_secs(20 * 60));
+
+        // Undoing the break puts the ramp back too
+        finish_session(&mut timer);
+        assert!(timer.undo_last_transition());
+        assert_eq!(timer.work_duration, Duration::from_secs(20 * 60));
+
+        // Without a limit it keeps growing
+        for _ in 0..10 {
+            finish_session(&mut timer);
+        }
+        assert_eq!(timer.work_duration, Duration::from_secs(45 * 60));
+    }
+
+    #[test]
+    fn test_ramp_breaks_scale_with_work() {
+        let mut timer = ramped_timer(15, 0);
+        timer.ramp_breaks = true;
+        finish_session(&mut timer);
+        // 30 minute work sessions are twice the start, so is the break
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.session_length, Duration::from_secs(10 * 60));
+        assert_eq!(timer.peek_next_sessions(1), [(SessionType::Work, Duration::from_secs(30 * 60))]);
+    }
+
+    #[test]
+    fn test_ramp_grows_with_work_counted_by_switching() {
+        let mut timer = ramped_timer(5, 0);
+        timer.count_skipped = true;
+        timer.switch_to(SessionType::ShortBreak);
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert_eq!(timer.work_duration, Duration::from_secs(20 * 60));
+
+        // Without count_skipped, switching away counts nothing and keeps the length
+        let mut timer = ramped_timer(5, 0);
+        timer.switch_to(SessionType::ShortBreak);
+        assert_eq!(timer.work_duration, Duration::from_secs(15 * 60));
+    }
+
+    #[test]
+    fn test_ramp_leaves_count_up_sessions_alone() {
+        let mut config = Config::default();
+        config.time.count_up = true;
+        config.time.count_up_break = true;
+        config.time.ramp_step_minutes = 5;
+        config.time.ramp_breaks = true;
+        let mut timer = Timer::new(config);
+        timer.break_ratio = 0.25;
+        timer.count_skipped = true;
+        let work = timer.work_duration;
+
+        timer.start();
+        timer.elapsed_time = Duration::from_secs(24 * 60);
+        timer.skip_session();
+        assert_eq!(timer.get_pomodoros_completed(), 1);
+        assert_eq!(timer.work_duration, work);
+        // The earned break isn't scaled again by the ramp
+        assert_eq!(timer.session_length, Duration::from_secs(6 * 60));
+    }
+
+    #[test]
+    fn test_set_remaining_stays_within_the_session() {
+        let mut timer = Timer::new(Config::default());
+        timer.set_remaining(Duration::from_secs(10));
+        assert_eq!(timer.remaining_time, Duration::from_secs(10));
+        assert_eq!(timer.session_length, Duration::from_secs(25 * 60));
+
+        timer.set_remaining(Duration::from_secs(2 * 60 * 60));
+        assert_eq!(timer.remaining_time, timer.session_length);
+
+        // It drives a session to its end like waiting would
+        timer.set_remaining(Duration::ZERO);
+        timer.start();
+        assert!(timer.tick());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+    }
+
+    #[test]
+    fn test_reset_goes_back_to_work_by_default() {
+        let mut timer = Timer::new(Config::default());
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        timer.start();
+        advance(&mut timer, 60);
+
+        timer.reset();
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.remaining_time, timer.work_duration);
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+    }
+
+    #[test]
+    fn test_reset_to_last_keeps_the_session() {
+        let mut config = Config::default();
+        config.time.reset_to = ResetTarget::Last;
+        let mut timer = Timer::new(config);
+        finish_session(&mut timer);
+        timer.start();
+        advance(&mut timer, 60);
+
+        timer.reset();
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.remaining_time, timer.short_break_duration);
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+
+        // The plan carries on from there
+        finish_session(&mut timer);
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+
+        // A session switched to outside the plan is kept too
+        let mut config = Config::default();
+        config.time.reset_to = ResetTarget::Last;
+        config.time.plan = vec![PlanStep::new(SessionType::Work), PlanStep::new(SessionType::ShortBreak)];
+        let mut timer = Timer::new(config);
+        timer.switch_to(SessionType::LongBreak);
+        timer.reset();
+        assert_eq!(timer.get_session_type(), SessionType::LongBreak);
+        assert_eq!(timer.remaining_time, timer.long_break_duration);
+    }
+}
@@ -1,21 +1,30 @@
 use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
-    backend::CrosstermBackend,
+    backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, Borders, Clear, List, ListItem, Paragraph,
+        BarChart, Block, Borders, Clear, Gauge, List, ListItem, Paragraph,
     },
     Frame, Terminal,
 };
+use std::collections::VecDeque;
 use std::io;
-use crate::timer::{SessionType, Timer};
+use std::time::{Duration, Instant};
+use crate::config::{ArtStyle, Config, ProgressDirection, ProgressStyle, TimeDisplay};
+use crate::heatmap::month_calendar;
+use crate::history::{DailyCount, History};
+use crate::timer::{format_clock, SessionType, Task, Timer, TimerState};
 
 /// Menu items for the top navigation bar
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -56,36 +65,759 @@ impl MenuItem {
 
 }
 
+/// What a key does outside of prompts and popups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyAction {
+    NextItem,
+    PrevItem,
+    Execute,
+    ToggleTimer,
+    Skip,
+    Reset,
+    Undo,
+    SwitchToWork,
+    SwitchToShortBreak,
+    SwitchToLongBreak,
+    Stats,
+    ToggleClock,
+    ToggleUpcoming,
+    EditTask,
+    SetTag,
+    VolumeDown,
+    VolumeUp,
+    Mute,
+    AddPomodoro,
+    RemovePomodoro,
+    Snooze,
+    JumpToEnd,
+    Help,
+    Quit,
+}
+
+impl KeyAction {
+    /// Every action in the order the help lists them
+    const ALL: [KeyAction; 24] = [
+        KeyAction::NextItem,
+        KeyAction::PrevItem,
+        KeyAction::Execute,
+        KeyAction::ToggleTimer,
+        KeyAction::Skip,
+        KeyAction::Reset,
+        KeyAction::Undo,
+        KeyAction::SwitchToWork,
+        KeyAction::SwitchToShortBreak,
+        KeyAction::SwitchToLongBreak,
+        KeyAction::Stats,
+        KeyAction::ToggleClock,
+        KeyAction::ToggleUpcoming,
+        KeyAction::EditTask,
+        KeyAction::SetTag,
+        KeyAction::VolumeDown,
+        KeyAction::VolumeUp,
+        KeyAction::Mute,
+        KeyAction::AddPomodoro,
+        KeyAction::RemovePomodoro,
+        KeyAction::Snooze,
+        KeyAction::JumpToEnd,
+        KeyAction::Help,
+        KeyAction::Quit,
+    ];
+
+    /// Get the help text for the action
+    fn description(&self) -> &'static str {
+        match self {
+            KeyAction::NextItem => "Next menu item",
+            KeyAction::PrevItem => "Previous menu item",
+            KeyAction::Execute => "Execute focused item",
+            KeyAction::ToggleTimer => "Start/Pause timer",
+            KeyAction::Skip => "Skip current session",
+            KeyAction::Reset => "Reset timer",
+            KeyAction::Undo => "Undo last skip/transition",
+            KeyAction::SwitchToWork => "Switch to work",
+            KeyAction::SwitchToShortBreak => "Switch to short break",
+            KeyAction::SwitchToLongBreak => "Switch to long break",
+            KeyAction::Stats => "Show statistics",
+            KeyAction::ToggleClock => "Show/Hide the clock",
+            KeyAction::ToggleUpcoming => "Show/Hide the upcoming sessions",
+            KeyAction::EditTask => "Set the task and its estimate",
+            KeyAction::SetTag => "Set the tag pomodoros are filed under",
+            KeyAction::VolumeDown => "Volume down",
+            KeyAction::VolumeUp => "Volume up",
+            KeyAction::Mute => "Mute/Unmute all sounds",
+            KeyAction::AddPomodoro => "Add a pomodoro by hand",
+            KeyAction::RemovePomodoro => "Remove a pomodoro by hand",
+            KeyAction::Snooze => "Snooze the end alarm",
+            KeyAction::JumpToEnd => "Jump to 10 seconds left (debug)",
+            KeyAction::Help => "Show/Hide this help",
+            KeyAction::Quit => "Quit application",
+        }
+    }
+
+    /// Check if the action moves between menu items rather than acting on the timer
+    fn is_navigation(&self) -> bool {
+        matches!(self, KeyAction::NextItem | KeyAction::PrevItem | KeyAction::Execute)
+    }
+
+    /// Check if focus lock and strict breaks keep the action from changing the session
+    fn is_lockable(&self) -> bool {
+        matches!(
+            self,
+            KeyAction::Skip
+                | KeyAction::Reset
+                | KeyAction::Undo
+                | KeyAction::SwitchToWork
+                | KeyAction::SwitchToShortBreak
+                | KeyAction::SwitchToLongBreak
+        )
+    }
+}
+
+/// Keys resolved from the configuration, used both to handle input and to build the help
+#[derive(Debug, Clone)]
+struct KeyBindings {
+    bindings: Vec<(KeyCode, KeyAction)>,
+}
+
+impl KeyBindings {
+    /// Resolve the bindings for a configuration
+    fn from_config(config: &Config) -> Self {
+        let space = if config.general.space_toggles_timer {
+            KeyAction::ToggleTimer
+        } else {
+            KeyAction::Execute
+        };
+        let mut bindings = vec![
+            (KeyCode::Tab, KeyAction::NextItem),
+            (KeyCode::Right, KeyAction::NextItem),
+            (KeyCode::Left, KeyAction::PrevItem),
+            (KeyCode::Enter, KeyAction::Execute),
+            (KeyCode::Char('p'), KeyAction::ToggleTimer),
+            (KeyCode::Char(' '), space),
+            (KeyCode::Char('s'), KeyAction::Skip),
+            (KeyCode::Char('r'), KeyAction::Reset),
+            (KeyCode::Char('u'), KeyAction::Undo),
+            (KeyCode::Char('1'), KeyAction::SwitchToWork),
+            (KeyCode::Char('2'), KeyAction::SwitchToShortBreak),
+            (KeyCode::Char('3'), KeyAction::SwitchToLongBreak),
+            (KeyCode::Char('t'), KeyAction::Stats),
+            (KeyCode::Char('v'), KeyAction::ToggleClock),
+            (KeyCode::Char('n'), KeyAction::ToggleUpcoming),
+            (KeyCode::Char('e'), KeyAction::EditTask),
+            (KeyCode::Char('#'), KeyAction::SetTag),
+            (KeyCode::Char('['), KeyAction::VolumeDown),
+            (KeyCode::Char(']'), KeyAction::VolumeUp),
+            (KeyCode::Char('m'), KeyAction::Mute),
+            (KeyCode::Char('+'), KeyAction::AddPomodoro),
+            (KeyCode::Char('-'), KeyAction::RemovePomodoro),
+            (KeyCode::Char('z'), KeyAction::Snooze),
+            (KeyCode::Char('h'), KeyAction::Help),
+            (KeyCode::Char('?'), KeyAction::Help),
+            (KeyCode::Char('q'), KeyAction::Quit),
+            (KeyCode::Esc, KeyAction::Quit),
+        ];
+        // Only bound with --debug, so it stays out of the help otherwise
+        if config.general.debug {
+            bindings.push((KeyCode::Char('d'), KeyAction::JumpToEnd));
+        }
+        Self { bindings }
+    }
+
+    /// Get the action bound to a key
+    fn action(&self, code: KeyCode) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .find(|(key, _)| *key == code)
+            .map(|(_, action)| *action)
+    }
+
+    /// Get the keys bound to an action, in binding order
+    fn keys_for(&self, action: KeyAction) -> Vec<KeyCode> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    /// Describe each bound action as a help line, navigation first
+    fn help_lines(&self) -> Vec<String> {
+        let line = |action: KeyAction| {
+            let keys = self.keys_for(action);
+            (!keys.is_empty()).then(|| {
+                let keys: Vec<String> = keys.into_iter().map(key_label).collect();
+                format!("  {:<16}- {}", keys.join(" or "), action.description())
+            })
+        };
+
+        let mut lines = vec!["Menu Navigation:".to_string()];
+        lines.extend(KeyAction::ALL.into_iter().filter(KeyAction::is_navigation).filter_map(line));
+        lines.push(String::new());
+        lines.push("Shortcuts:".to_string());
+        lines.extend(KeyAction::ALL.into_iter().filter(|action| !action.is_navigation()).filter_map(line));
+        lines
+    }
+}
+
+/// Show a key the way the help writes it, like `[P]` or `[Esc]`
+fn key_label(code: KeyCode) -> String {
+    match code {
+        // Brackets in brackets would be hard to read
+        KeyCode::Char(c @ ('[' | ']')) => c.to_string(),
+        KeyCode::Char(' ') => "[Space]".to_string(),
+        KeyCode::Char(c) => format!("[{}]", c.to_ascii_uppercase()),
+        KeyCode::Tab => "[Tab]".to_string(),
+        KeyCode::Enter => "[Enter]".to_string(),
+        KeyCode::Esc => "[Esc]".to_string(),
+        KeyCode::Left => "[←]".to_string(),
+        KeyCode::Right => "[→]".to_string(),
+        other => format!("[{:?}]", other),
+    }
+}
+
+/// Display settings that affect how the main screen is rendered
+#[derive(Debug, Clone)]
+struct RenderOptions {
+    hide_clock: bool,
+    /// Whether hiding the clock also hides the progress
+    hide_progress: bool,
+    time_display: TimeDisplay,
+    progress_style: ProgressStyle,
+    progress_direction: ProgressDirection,
+    art_style: ArtStyle,
+    /// Only draw the session status and the timer
+    minimal: bool,
+    /// Draw the next few sessions under the statistics
+    show_upcoming: bool,
+    /// Start and end colors that work sessions shift between as they progress
+    gradient: Option<([u8; 3], [u8; 3])>,
+    progress_chars: ProgressChars,
+    theme: Theme,
+}
+
+impl RenderOptions {
+    /// Check if nothing on screen may show how far the session is
+    fn blackout(&self) -> bool {
+        self.hide_clock && self.hide_progress
+    }
+}
+
+/// Characters the progress bars are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProgressChars {
+    fill: char,
+    empty: char,
+}
+
+impl Default for ProgressChars {
+    fn default() -> Self {
+        Self { fill: '█', empty: '░' }
+    }
+}
+
+/// Source of every style in the UI, so no-color mode only has to be handled here
+/// Without colors, highlights are reversed and inactive items dimmed instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Theme {
+    color: bool,
+}
+
+impl Theme {
+    /// Style with a foreground color
+    fn fg(self, color: Color) -> Style {
+        if self.color {
+            Style::default().fg(color)
+        } else {
+            Style::default()
+        }
+    }
+
+    /// Style with foreground and background colors
+    fn colors(self, fg: Color, bg: Color) -> Style {
+        self.fg(fg).bg(if self.color { bg } else { Color::Reset })
+    }
+
+    /// Style for a highlighted item
+    fn highlight(self, fg: Color, bg: Color) -> Style {
+        if self.color {
+            Style::default().fg(fg).bg(bg)
+        } else {
+            Style::default().add_modifier(Modifier::REVERSED)
+        }
+    }
+
+    /// Style for an item that can't be used right now
+    fn inactive(self) -> Style {
+        if self.color {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().add_modifier(Modifier::DIM)
+        }
+    }
+}
+
+/// Number of consecutive Esc presses that quit while focus lock is active
+const FOCUS_LOCK_ESCAPE_PRESSES: u8 = 3;
+
+/// Volume change per press of `[` or `]`
+const VOLUME_KEY_STEP: f32 = 0.1;
+
+/// How long the volume bar stays visible after a change
+const VOLUME_BAR_DURATION: Duration = Duration::from_secs(2);
+
+/// How long the confirmation stays visible after the clock is shown or hidden
+const CLOCK_NOTICE_DURATION: Duration = Duration::from_secs(2);
+
+/// Time left after the debug key jumps toward the end of a session
+const DEBUG_JUMP_REMAINING: Duration = Duration::from_secs(10);
+
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+/// Last part of a toast's time, during which it is drawn dimmed
+const TOAST_FADE: Duration = Duration::from_millis(500);
+
+/// Most toasts shown at once, pushing another drops the oldest
+const MAX_TOASTS: usize = 3;
+
+/// Screen areas that react to the mouse, as laid out by the last draw
+#[derive(Debug, Clone, Copy, Default)]
+struct ScreenRegions {
+    timer: Rect,
+    volume: Rect,
+}
+
+/// Current volume and whether to show it as a bar after a recent change
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct VolumeIndicator {
+    volume: f32,
+    show_bar: bool,
+    muted: bool,
+}
+
+/// Everything a frame is drawn from
+/// Two equal keys draw the same screen, so the second draw can be skipped.
+#[derive(Debug, Clone, PartialEq)]
+struct FrameKey {
+    size: Rect,
+    state: FrameState,
+    display_time: String,
+    /// Session progress in thousandths, for the gauges and the color gradient
+    progress: u32,
+    session: SessionType,
+    timer_state: TimerState,
+    standby: bool,
+    pomodoros: u32,
+    cycle: (u8, u8),
+    earned_break: Option<Duration>,
+    task: Option<Task>,
+    message: Option<String>,
+    show_goal_message: bool,
+    show_help: bool,
+    show_stats: bool,
+    note_input: Option<LineInput>,
+    task_input: Option<TaskInput>,
+    tag_input: Option<LineInput>,
+    tag: Option<String>,
+    summary: Option<RunSummary>,
+    break_nag: Option<u32>,
+    /// Whether the transition prompt is open, and the seconds left on its countdown
+    transition_prompt: Option<Option<u64>>,
+    history_total: u64,
+    hide_clock: bool,
+    /// Sessions listed under the statistics, if that line is shown
+    upcoming: Option<Vec<(SessionType, Duration)>>,
+    /// Day the daily counts are shown for
+    today: NaiveDate,
+    /// Toasts on screen and whether each is fading
+    toasts: Vec<(String, bool)>,
+}
+
+/// Single-line text input with basic editing
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LineInput {
+    text: String,
+}
+
+impl LineInput {
+    /// Append a character at the end of the line
+    fn insert(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    /// Remove the last character, if any
+    fn backspace(&mut self) {
+        self.text.pop();
+    }
+
+    /// Take the entered text, trimmed
+    fn submit(self) -> String {
+        self.text.trim().to_string()
+    }
+}
+
+/// Task name and estimate being entered
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TaskInput {
+    name: LineInput,
+    estimate: LineInput,
+    /// Whether keys go to the estimate rather than the name
+    editing_estimate: bool,
+}
+
+impl TaskInput {
+    /// Start from the task that is already set, if any
+    fn for_task(task: Option<&Task>) -> Self {
+        let mut input = Self::default();
+        if let Some(task) = task {
+            input.name.text = task.name.clone();
+            if task.estimate > 0 {
+                input.estimate.text = task.estimate.to_string();
+            }
+        }
+        input
+    }
+
+    /// Type a character into the field being edited; the estimate only takes digits
+    fn insert(&mut self, c: char) {
+        if !self.editing_estimate {
+            self.name.insert(c);
+        } else if c.is_ascii_digit() {
+            self.estimate.insert(c);
+        }
+    }
+
+    fn backspace(&mut self) {
+        if self.editing_estimate {
+            self.estimate.backspace();
+        } else {
+            self.name.backspace();
+        }
+    }
+
+    /// Take the entered name and estimate, or None if the name was left blank
+    fn submit(self) -> Option<(String, u32)> {
+        let name = self.name.submit();
+        let estimate = self.estimate.submit().parse().unwrap_or(0);
+        (!name.is_empty()).then_some((name, estimate))
+    }
+}
+
+/// UI state that changes from frame to frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FrameState {
+    focused_item: MenuItem,
+    focus_locked: bool,
+    break_locked: bool,
+    paused_idle: bool,
+    /// Whether debug keys are enabled, shown next to the session
+    debug: bool,
+    /// Whether a finished session is waiting to be acknowledged
+    completion_pending: bool,
+    volume: VolumeIndicator,
+    theme: Theme,
+    /// Pomodoros recorded today, shown on the standby screen
+    today_pomodoros: u64,
+    /// Confirmation shown in place of the state cue right after the clock is toggled
+    clock_notice: Option<&'static str>,
+    /// Whether the daily goal has been met today
+    goal_met: bool,
+}
+
 /// UI state and configuration
-pub struct AppUI {
+pub struct AppUI<B: Backend = CrosstermBackend<io::Stdout>> {
     pub should_quit: bool,
     pub show_help: bool,
-    terminal: Terminal<CrosstermBackend<io::Stdout>>,
-    hide_clock: bool,
+    /// Whether the statistics popup is open
+    pub show_stats: bool,
+    terminal: Terminal<B>,
+    render_options: RenderOptions,
     /// Currently focused menu item
     pub focused_menu_item: MenuItem,
     /// Flag to indicate if audio should be stopped on the next input check
     should_stop_audio: bool,
+    /// Whether mouse capture is enabled
+    mouse: bool,
+    /// Keys for the menu and the shortcuts, from the configuration
+    key_bindings: KeyBindings,
+    /// Pause the running session when the terminal loses focus
+    pause_on_blur: bool,
+    /// Resume a session paused on blur once focus returns
+    resume_on_focus: bool,
+    /// Also pause breaks (not just work sessions) on blur
+    pause_breaks_on_blur: bool,
+    /// Whether the current pause was triggered by losing focus
+    paused_by_blur: bool,
+    /// Block skip, reset and quit while a work session is running
+    focus_lock: bool,
+    /// Block skip, reset and pausing during breaks
+    strict_break: bool,
+    /// Consecutive Esc presses, used as the escape hatch out of focus lock
+    escape_presses: u8,
+    /// Pause a running work session after this long without input (zero disables)
+    idle_pause: Duration,
+    /// Resume a session paused for inactivity on the next input
+    idle_auto_resume: bool,
+    /// When the last key, mouse or focus event arrived
+    last_input: Instant,
+    /// Whether a key was pressed since the last check
+    key_pressed: bool,
+    /// Whether the current pause was triggered by inactivity
+    paused_by_idle: bool,
+    /// Note being typed after a work session, while the prompt is open
+    note_input: Option<LineInput>,
+    /// Task being entered, while the task prompt is open
+    task_input: Option<TaskInput>,
+    /// Tag being entered, while the tag prompt is open
+    tag_input: Option<LineInput>,
+    /// Note submitted from the prompt, waiting to be saved
+    submitted_note: Option<String>,
+    /// Mouse-sensitive areas from the last draw
+    regions: ScreenRegions,
+    /// Seconds added or removed per wheel step over the timer
+    scroll_time_step: Duration,
+    /// Volume change per wheel step over the volume indicator
+    scroll_volume_step: f32,
+    /// Volume shown in the indicator
+    volume: f32,
+    /// When the volume was last changed from the UI
+    volume_changed_at: Option<Instant>,
+    /// Volume change not yet applied to the audio output
+    pending_volume: Option<f32>,
+    /// When the clock was last shown or hidden with a key
+    clock_toggled_at: Option<Instant>,
+    /// Brief messages over the main screen, oldest first, with when each goes away
+    toasts: VecDeque<(String, Instant)>,
+    /// Whether sound is muted for now
+    muted: bool,
+    /// Mute change not yet applied to the audio output
+    pending_mute: Option<bool>,
+    /// Day the daily counts are shown for
+    today: NaiveDate,
+    /// Whether the daily goal has been met today
+    goal_met: bool,
+    /// Pomodoros to complete each day, marked in the stats calendar (0 means no goal)
+    daily_goal: u64,
+    /// Whether debug keys are enabled
+    debug: bool,
+    /// Pomodoros added or removed by hand that still need to reach the history
+    count_adjustment: i32,
+    /// Whether the end alarm should be snoozed
+    snooze_requested: bool,
+    /// Summary shown once a `--sessions` run is done, until quitting
+    summary: Option<RunSummary>,
+    /// Breaks skipped in a row, while the reminder to take one is shown
+    break_nag: Option<u32>,
+    /// Prompt to start the next session after one ended
+    transition_prompt: Option<TransitionPrompt>,
+    /// What the last drawn frame was drawn from
+    last_frame: Option<FrameKey>,
+    /// Frames drawn and frames skipped because nothing changed
+    frames_drawn: u64,
+    frames_skipped: u64,
+}
+
+/// Prompt shown with `confirm_transitions` until the next session is started
+#[derive(Debug, Clone, Copy)]
+struct TransitionPrompt {
+    /// When the next session starts without Enter, during a `--sessions` run
+    deadline: Option<Instant>,
 }
 
 impl AppUI {
     /// Initialize the terminal UI
-    pub fn new(hide_clock: bool) -> Result<Self> {
+    pub fn new(config: &Config) -> Result<Self> {
+        let pause_on_blur = config.general.pause_on_blur;
+
         // Setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        execute!(stdout, EnterAlternateScreen)?;
+        if config.general.mouse {
+            execute!(stdout, EnableMouseCapture)?;
+        }
+        if pause_on_blur {
+            execute!(stdout, EnableFocusChange)?;
+        }
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend)?;
 
-        Ok(Self {
+        Ok(Self::with_terminal(terminal, config))
+    }
+
+    /// Restore the terminal to its original state
+    pub fn restore_terminal(&mut self) -> Result<()> {
+        if self.pause_on_blur {
+            execute!(self.terminal.backend_mut(), DisableFocusChange)?;
+        }
+        if self.mouse {
+            execute!(self.terminal.backend_mut(), DisableMouseCapture)?;
+        }
+        disable_raw_mode()?;
+        execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
+        self.terminal.show_cursor()?;
+        Ok(())
+    }
+}
+
+impl<B: Backend> AppUI<B> {
+    /// Create the UI state around an already set up terminal
+    fn with_terminal(terminal: Terminal<B>, config: &Config) -> Self {
+        Self {
             should_quit: false,
             show_help: false,
+            show_stats: false,
             terminal,
-            hide_clock,
+            render_options: RenderOptions {
+                hide_clock: config.hide_clock(),
+                hide_progress: config.general.hide_progress,
+                time_display: config.general.time_display,
+                progress_direction: config.general.progress_direction,
+                progress_style: config.general.progress_style,
+                art_style: config.general.art_style,
+                minimal: config.general.minimal,
+                show_upcoming: config.general.show_upcoming,
+                gradient: config
+                    .general
+                    .gradient
+                    .then_some((config.general.gradient_start, config.general.gradient_end)),
+                progress_chars: {
+                    let (fill, empty) = config.progress_chars();
+                    ProgressChars { fill, empty }
+                },
+                theme: Theme {
+                    color: !config.general.no_color,
+                },
+            },
             focused_menu_item: MenuItem::Start,
             should_stop_audio: false,
-        })
+            mouse: config.general.mouse,
+            key_bindings: KeyBindings::from_config(config),
+            pause_on_blur: config.general.pause_on_blur,
+            resume_on_focus: config.general.resume_on_focus,
+            pause_breaks_on_blur: config.general.pause_breaks_on_blur,
+            paused_by_blur: false,
+            focus_lock: config.general.focus_lock,
+            strict_break: config.general.strict_break,
+            escape_presses: 0,
+            idle_pause: Duration::from_secs(config.general.idle_pause_secs),
+            idle_auto_resume: config.general.idle_auto_resume,
+            last_input: Instant::now(),
+            key_pressed: false,
+            paused_by_idle: false,
+            note_input: None,
+            task_input: None,
+            tag_input: None,
+            submitted_note: None,
+            regions: ScreenRegions::default(),
+            scroll_time_step: Duration::from_secs(config.general.scroll_time_step_secs),
+            scroll_volume_step: config.general.scroll_volume_step,
+            volume: config.audio.volume,
+            volume_changed_at: None,
+            pending_volume: None,
+            clock_toggled_at: None,
+            toasts: VecDeque::new(),
+            muted: false,
+            pending_mute: None,
+            today: Local::now().date_naive(),
+            goal_met: false,
+            daily_goal: u64::from(config.time.daily_goal),
+            debug: config.general.debug,
+            count_adjustment: 0,
+            snooze_requested: false,
+            summary: None,
+            break_nag: None,
+            transition_prompt: None,
+            last_frame: None,
+            frames_drawn: 0,
+            frames_skipped: 0,
+        }
+    }
+
+    /// Check if focus lock currently blocks skip, reset and quit
+    /// Overtime is past the end of the session, so it is never locked.
+    pub fn is_focus_locked(&self, timer: &Timer) -> bool {
+        self.focus_lock
+            && timer.is_running()
+            && timer.get_session_type() == SessionType::Work
+            && !timer.is_in_overtime()
+    }
+
+    /// Check if strict break mode currently blocks skip, reset and pausing
+    pub fn is_break_locked(&self, timer: &Timer) -> bool {
+        self.strict_break && timer.get_session_type() != SessionType::Work
+    }
+
+    /// Open the prompt for a note about the work session that just ended
+    pub fn open_note_prompt(&mut self) {
+        self.note_input = Some(LineInput::default());
+    }
+
+    /// Take the note submitted from the prompt, blank if it was skipped
+    pub fn take_submitted_note(&mut self) -> Option<String> {
+        self.submitted_note.take()
+    }
+
+    /// Edit the note; Enter submits it and Esc skips it
+    fn process_note_key(&mut self, key: KeyEvent) {
+        let Some(input) = &mut self.note_input else {
+            return;
+        };
+        // The first keystroke also acknowledges the end alarm
+        self.should_stop_audio = true;
+
+        match key.code {
+            KeyCode::Char(c) => input.insert(c),
+            KeyCode::Backspace => input.backspace(),
+            KeyCode::Enter => {
+                self.submitted_note = self.note_input.take().map(LineInput::submit);
+            }
+            KeyCode::Esc => {
+                self.note_input = None;
+                self.submitted_note = Some(String::new());
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key while the task prompt is open
+    /// Enter moves from the name to the estimate and then sets the task; a blank name clears it.
+    fn process_task_key(&mut self, key: KeyEvent, timer: &mut Timer) {
+        let Some(input) = &mut self.task_input else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Char(c) => input.insert(c),
+            KeyCode::Backspace => input.backspace(),
+            KeyCode::Tab => input.editing_estimate = !input.editing_estimate,
+            KeyCode::Enter if !input.editing_estimate => input.editing_estimate = true,
+            KeyCode::Enter => match self.task_input.take().and_then(TaskInput::submit) {
+                Some((name, estimate)) => timer.set_task(name, estimate),
+                None => timer.clear_task(),
+            },
+            KeyCode::Esc => self.task_input = None,
+            _ => {}
+        }
+    }
+
+    /// Handle a key while the tag prompt is open
+    /// A blank tag stops tagging.
+    fn process_tag_key(&mut self, key: KeyEvent, timer: &mut Timer) {
+        let Some(input) = &mut self.tag_input else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Char(c) => input.insert(c),
+            KeyCode::Backspace => input.backspace(),
+            KeyCode::Enter => {
+                if let Some(input) = self.tag_input.take() {
+                    timer.set_tag(&input.submit());
+                }
+            }
+            KeyCode::Esc => self.tag_input = None,
+            _ => {}
+        }
     }
 
     /// Update focused menu item based on timer state
@@ -102,46 +834,383 @@ impl AppUI {
         }
     }
 
-    /// Restore the terminal to its original state
-    pub fn restore_terminal(&mut self) -> Result<()> {
-        disable_raw_mode()?;
-        execute!(
-            self.terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        self.terminal.show_cursor()?;
-        Ok(())
+    /// Get how many frames were drawn and how many were skipped as unchanged
+    pub fn frame_counts(&self) -> (u64, u64) {
+        (self.frames_drawn, self.frames_skipped)
+    }
+
+    /// Collect what the next frame would be drawn from
+    fn frame_key(
+        &self,
+        timer: &Timer,
+        history: &History,
+        completion_message: Option<&str>,
+        show_goal_message: bool,
+    ) -> Result<FrameKey> {
+        Ok(FrameKey {
+            size: self.terminal.size()?,
+            state: self.frame_state(timer, history, completion_message),
+            display_time: timer.get_display_time(),
+            progress: (timer.get_progress() * 1000.0) as u32,
+            session: timer.get_session_type(),
+            timer_state: timer.state,
+            standby: timer.is_standby(),
+            pomodoros: timer.get_pomodoros_completed(),
+            cycle: timer.get_cycle_position(),
+            earned_break: timer.get_earned_break(),
+            task: timer.task().cloned(),
+            message: completion_message.map(str::to_string),
+            show_goal_message,
+            show_help: self.show_help,
+            show_stats: self.show_stats,
+            note_input: self.note_input.clone(),
+            task_input: self.task_input.clone(),
+            tag_input: self.tag_input.clone(),
+            tag: timer.tag().map(str::to_string),
+            summary: self.summary,
+            break_nag: self.break_nag,
+            transition_prompt: self.transition_prompt.map(|prompt| {
+                prompt
+                    .deadline
+                    .map(|deadline| deadline.saturating_duration_since(Instant::now()).as_secs())
+            }),
+            history_total: history.total(),
+            hide_clock: self.render_options.hide_clock,
+            upcoming: self
+                .render_options
+                .show_upcoming
+                .then(|| timer.peek_next_sessions(UPCOMING_SESSIONS)),
+            today: self.today,
+            toasts: {
+                let now = Instant::now();
+                self.toasts
+                    .iter()
+                    .map(|(message, expiry)| (message.clone(), expiry.saturating_duration_since(now) <= TOAST_FADE))
+                    .collect()
+            },
+        })
+    }
+
+    /// Work out the per-frame UI state
+    fn frame_state(&self, timer: &Timer, history: &History, completion_message: Option<&str>) -> FrameState {
+        FrameState {
+            focused_item: self.focused_menu_item,
+            focus_locked: self.is_focus_locked(timer),
+            break_locked: self.is_break_locked(timer),
+            paused_idle: self.paused_by_idle && timer.is_paused(),
+            debug: self.debug,
+            completion_pending: completion_message.is_some(),
+            volume: VolumeIndicator {
+                volume: self.volume,
+                show_bar: self
+                    .volume_changed_at
+                    .is_some_and(|changed| changed.elapsed() < VOLUME_BAR_DURATION),
+                muted: self.muted,
+            },
+            theme: self.render_options.theme,
+            today_pomodoros: history
+                .daily_counts(self.today, 1)
+                .iter()
+                .map(|day| day.pomodoros)
+                .sum(),
+            clock_notice: self
+                .clock_toggled_at
+                .filter(|toggled| toggled.elapsed() < CLOCK_NOTICE_DURATION)
+                .map(|_| {
+                    if self.render_options.hide_clock {
+                        "Clock hidden — press V to show it"
+                    } else {
+                        "Clock shown"
+                    }
+                }),
+            goal_met: self.goal_met,
+        }
     }
 
-    /// Draw the UI
-    pub fn draw(&mut self, timer: &Timer, show_completion_message: bool) -> Result<()> {
+    /// Draw the UI, unless nothing changed since the last frame
+    pub fn draw(
+        &mut self,
+        timer: &Timer,
+        history: &History,
+        completion_message: Option<&str>,
+        show_goal_message: bool,
+    ) -> Result<()> {
+        let key = self.frame_key(timer, history, completion_message, show_goal_message)?;
+        if self.last_frame.as_ref() == Some(&key) {
+            self.frames_skipped += 1;
+            return Ok(());
+        }
+
         let show_help = self.show_help;
-        let hide_clock = self.hide_clock;
-        let focused_item = self.focused_menu_item;
+        let key_bindings = &self.key_bindings;
+        let show_stats = self.show_stats;
+        let render_options = &self.render_options;
+        let state = key.state;
+        let theme = render_options.theme;
+        let note_input = self.note_input.as_ref();
+        let task_input = self.task_input.as_ref();
+        let tag_input = self.tag_input.as_ref();
+        let summary = self.summary.as_ref();
+        let break_nag = self.break_nag;
+        let transition_prompt = self.transition_prompt;
+        let today = self.today;
+        let daily_goal = self.daily_goal;
+        let toasts = &key.toasts;
+        let mut regions = ScreenRegions::default();
         
         self.terminal.draw(|f| {
-            render_new_ui(f, timer, hide_clock, focused_item);
+            regions = render_new_ui(f, timer, render_options, &state);
+            render_toasts(f, regions.timer, toasts, theme);
             
-            if show_help {
-                render_help_popup(f);
-            } else if show_completion_message {
-                render_completion_message_popup(f);
+            if let Some(input) = note_input {
+                render_note_popup(f, input, theme);
+            } else if let Some(input) = task_input {
+                render_task_popup(f, input, theme);
+            } else if let Some(input) = tag_input {
+                render_tag_popup(f, input, theme);
+            } else if let Some(summary) = summary {
+                render_summary_popup(f, summary, theme);
+            } else if let Some(skips) = break_nag {
+                render_break_nag_popup(f, skips, theme);
+            } else if show_help {
+                render_help_popup(f, key_bindings, theme);
+            } else if show_stats {
+                render_stats_popup(f, history, today, daily_goal, theme);
+            } else if let Some(prompt) = transition_prompt {
+                render_transition_prompt_popup(f, timer, completion_message, prompt, theme);
+            } else if let Some(message) = completion_message {
+                render_completion_message_popup(f, timer, message, show_goal_message, theme);
             }
         })?;
+        self.regions = regions;
+        self.last_frame = Some(key);
+        self.frames_drawn += 1;
         Ok(())
     }
 
     /// Handle keyboard input
     pub fn handle_input(&mut self, timer: &mut Timer) -> Result<bool> {
         if event::poll(std::time::Duration::from_millis(16))? {
-            if let Event::Key(key) = event::read()? {
-                return Ok(self.process_key_event(key, timer));
-            }
+            let event = event::read()?;
+            return Ok(self.handle_event(event, timer, Instant::now()));
         }
         Ok(false)
     }
 
+    /// Act on a terminal event, returning true if the app should quit
+    fn handle_event(&mut self, event: Event, timer: &mut Timer, now: Instant) -> bool {
+        // Some terminals also report key releases; only presses are input
+        if matches!(event, Event::Key(key) if key.kind != KeyEventKind::Press) {
+            return false;
+        }
+        if is_user_input(&event) && self.note_input(timer, now) {
+            // The input that ends an idle pause only resumes the session
+            return false;
+        }
+        match event {
+            Event::Key(key) => {
+                self.key_pressed = true;
+                return self.process_key_event(key, timer);
+            }
+            Event::FocusLost => self.handle_focus_lost(timer),
+            Event::FocusGained => self.handle_focus_gained(timer),
+            Event::Mouse(mouse) => self.handle_mouse(mouse, timer),
+            _ => {}
+        }
+        false
+    }
+
+    /// Pause a running work session once no input has arrived for `idle_pause`
+    /// Returns true if the session was paused.
+    pub fn pause_if_idle(&mut self, timer: &mut Timer, now: Instant) -> bool {
+        if !timer.is_running() || timer.get_session_type() != SessionType::Work {
+            return false;
+        }
+        if !idle_expired(self.last_input, now, self.idle_pause) {
+            return false;
+        }
+
+        timer.pause();
+        self.paused_by_idle = true;
+        self.focused_menu_item = MenuItem::Start;
+        true
+    }
+
+    /// Record user activity, resuming an idle pause if configured
+    /// Returns true if the session was resumed.
+    fn note_input(&mut self, timer: &mut Timer, now: Instant) -> bool {
+        self.last_input = now;
+        if !std::mem::take(&mut self.paused_by_idle) {
+            return false;
+        }
+
+        if self.idle_auto_resume && timer.is_paused() {
+            timer.resume();
+            self.focused_menu_item = MenuItem::Pause;
+            return true;
+        }
+        false
+    }
+
+    /// Pause the running session when the terminal loses focus
+    fn handle_focus_lost(&mut self, timer: &mut Timer) {
+        if !self.pause_on_blur || !timer.is_running() {
+            return;
+        }
+        if timer.get_session_type() != SessionType::Work && !self.pause_breaks_on_blur {
+            return;
+        }
+
+        timer.pause();
+        self.paused_by_blur = true;
+        self.focused_menu_item = MenuItem::Start;
+    }
+
+    /// Resume a session paused on blur when the terminal regains focus
+    fn handle_focus_gained(&mut self, timer: &mut Timer) {
+        if self.paused_by_blur && self.resume_on_focus && timer.is_paused() {
+            timer.resume();
+            self.focused_menu_item = MenuItem::Pause;
+        }
+        self.paused_by_blur = false;
+    }
+
+    /// Scroll over the timer to change the remaining time, or over the volume indicator
+    fn handle_mouse(&mut self, mouse: MouseEvent, timer: &mut Timer) {
+        let up = match mouse.kind {
+            MouseEventKind::ScrollUp => true,
+            MouseEventKind::ScrollDown => false,
+            _ => return,
+        };
+
+        if rect_contains(self.regions.volume, mouse.column, mouse.row) {
+            let step = if up { self.scroll_volume_step } else { -self.scroll_volume_step };
+            self.change_volume(step);
+        } else if rect_contains(self.regions.timer, mouse.column, mouse.row)
+            && !self.is_break_locked(timer)
+            && !self.is_focus_locked(timer)
+        {
+            if up {
+                timer.add_time(self.scroll_time_step);
+            } else {
+                timer.subtract_time(self.scroll_time_step);
+            }
+        }
+    }
+
+    /// Step the volume and show the volume bar
+    fn change_volume(&mut self, step: f32) {
+        self.volume = step_volume(self.volume, step);
+        self.volume_changed_at = Some(Instant::now());
+        self.pending_volume = Some(self.volume);
+    }
+
+    /// Take a volume change made in the UI that still needs to reach the audio output
+    pub fn take_volume_change(&mut self) -> Option<f32> {
+        self.pending_volume.take()
+    }
+
+    /// Mute or unmute all sounds without touching the config
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        self.pending_mute = Some(self.muted);
+    }
+
+    /// Show a brief message over the main screen for a couple of seconds
+    /// Only the newest `MAX_TOASTS` are kept.
+    pub fn push_toast(&mut self, message: &str) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back((message.to_string(), Instant::now() + TOAST_DURATION));
+    }
+
+    /// Drop the toasts whose time is up at `now`
+    pub fn expire_toasts(&mut self, now: Instant) {
+        self.toasts.retain(|(_, expiry)| *expiry > now);
+    }
+
+    /// Take a mute change made in the UI that still needs to reach the audio output
+    pub fn take_mute_change(&mut self) -> Option<bool> {
+        self.pending_mute.take()
+    }
+
+    /// Show the daily counts for `today`, which moves on at midnight
+    pub fn set_today(&mut self, today: NaiveDate) {
+        self.today = today;
+    }
+
+    /// Show or drop the daily goal badge in the statistics bar
+    pub fn set_goal_met(&mut self, goal_met: bool) {
+        self.goal_met = goal_met;
+    }
+
+    /// Show or hide the clock, e.g. while someone is looking at the screen
+    pub fn toggle_clock(&mut self) {
+        self.render_options.hide_clock = !self.render_options.hide_clock;
+        self.clock_toggled_at = Some(Instant::now());
+    }
+
+    /// Show or hide the line listing the next few sessions
+    pub fn toggle_upcoming(&mut self) {
+        self.render_options.show_upcoming = !self.render_options.show_upcoming;
+    }
+
+    /// Take the pomodoros added or removed by hand since the last call
+    pub fn take_count_adjustment(&mut self) -> Option<i32> {
+        Some(std::mem::take(&mut self.count_adjustment)).filter(|&delta| delta != 0)
+    }
+
+    /// Show the summary of a finished run, leaving only quitting
+    pub fn show_summary(&mut self, summary: RunSummary) {
+        self.summary = Some(summary);
+        self.show_help = false;
+        self.show_stats = false;
+    }
+
+    /// Remind the user to take a break until they acknowledge it
+    pub fn show_break_nag(&mut self, skips: u32) {
+        self.break_nag = Some(skips);
+        self.show_help = false;
+        self.show_stats = false;
+    }
+
+    /// Ask before the next session starts, starting it at `deadline` if one is given
+    pub fn show_transition_prompt(&mut self, deadline: Option<Instant>) {
+        self.transition_prompt = Some(TransitionPrompt { deadline });
+    }
+
+    /// Start the next session once the transition prompt has waited long enough
+    /// Returns true if the session was started.
+    pub fn start_if_prompt_expired(&mut self, timer: &mut Timer, now: Instant) -> bool {
+        let Some(deadline) = self.transition_prompt.and_then(|prompt| prompt.deadline) else {
+            return false;
+        };
+        if now < deadline {
+            return false;
+        }
+        self.start_from_prompt(timer);
+        true
+    }
+
+    /// Close the transition prompt and start the session it announced
+    fn start_from_prompt(&mut self, timer: &mut Timer) {
+        self.transition_prompt = None;
+        self.should_stop_audio = true;
+        timer.start();
+        self.focused_menu_item = MenuItem::Pause;
+    }
+
+    /// Return true once after the snooze key was pressed
+    pub fn take_snooze_request(&mut self) -> bool {
+        std::mem::take(&mut self.snooze_requested)
+    }
+
+    /// Return true once if any key was pressed since the last call
+    pub fn take_key_press(&mut self) -> bool {
+        std::mem::take(&mut self.key_pressed)
+    }
+
     /// Check if audio should be stopped on input and reset the flag
     pub fn should_stop_audio_on_input(&mut self) -> bool {
         let result = self.should_stop_audio;
@@ -165,10 +1234,30 @@ impl AppUI {
         self.focused_menu_item = items[prev_index];
     }
 
-    /// Execute the currently focused menu item
-    pub fn execute_focused_item(&mut self, timer: &mut Timer) -> bool {
-        match self.focused_menu_item {
-            MenuItem::Start => {
+    /// Start or pause the timer whatever menu item is focused
+    fn toggle_timer(&mut self, timer: &mut Timer) -> bool {
+        // A strict break can be started but not paused
+        if timer.is_running() && self.is_break_locked(timer) {
+            return false;
+        }
+        // Stop any playing audio when starting a new session via shortcut
+        if !timer.is_running() {
+            self.should_stop_audio = true;
+        }
+        timer.toggle_pause();
+        // Update focused item based on timer state
+        if timer.is_running() {
+            self.focused_menu_item = MenuItem::Pause;
+        } else {
+            self.focused_menu_item = MenuItem::Start;
+        }
+        false
+    }
+
+    /// Execute the currently focused menu item
+    pub fn execute_focused_item(&mut self, timer: &mut Timer) -> bool {
+        match self.focused_menu_item {
+            MenuItem::Start => {
                 if timer.is_stopped() || timer.is_paused() {
                     // Stop any playing audio when starting a new session
                     self.should_stop_audio = true;
@@ -181,7 +1270,7 @@ impl AppUI {
                 false
             }
             MenuItem::Pause => {
-                if timer.is_running() {
+                if timer.is_running() && !self.is_break_locked(timer) {
                     timer.toggle_pause();
                     // Update focus to start when timer pauses
                     if timer.is_paused() {
@@ -190,6 +1279,8 @@ impl AppUI {
                 }
                 false
             }
+            MenuItem::Skip | MenuItem::Reset | MenuItem::Exit if self.is_focus_locked(timer) => false,
+            MenuItem::Skip | MenuItem::Reset if self.is_break_locked(timer) => false,
             MenuItem::Skip => {
                 // Stop any playing audio when skipping
                 self.should_stop_audio = true;
@@ -217,75 +1308,225 @@ impl AppUI {
 
     /// Process keyboard events
     fn process_key_event(&mut self, key: KeyEvent, timer: &mut Timer) -> bool {
+        // Act once per keystroke where release and repeat events are reported too
+        if key.kind != KeyEventKind::Press {
+            return false;
+        }
+
+        // The note prompt takes every key until it is submitted or skipped
+        if self.note_input.is_some() {
+            self.process_note_key(key);
+            return false;
+        }
+        if self.task_input.is_some() {
+            self.process_task_key(key, timer);
+            return false;
+        }
+        if self.tag_input.is_some() {
+            self.process_tag_key(key, timer);
+            return false;
+        }
+
+        // The run summary stays up until quitting, other keys only silence the alarm
+        if self.summary.is_some() {
+            if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                self.should_quit = true;
+                return true;
+            }
+            self.should_stop_audio = true;
+            return false;
+        }
+
+        // The transition prompt waits for Enter, other keys only silence the alarm
+        if self.transition_prompt.is_some() {
+            match key.code {
+                KeyCode::Enter => self.start_from_prompt(timer),
+                KeyCode::Esc => {
+                    self.transition_prompt = None;
+                    self.should_stop_audio = true;
+                }
+                KeyCode::Char('q') => {
+                    self.should_quit = true;
+                    return true;
+                }
+                _ => self.should_stop_audio = true,
+            }
+            return false;
+        }
+
+        // The break reminder swallows keys until it is acknowledged
+        if self.break_nag.is_some() {
+            if matches!(key.code, KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Esc) {
+                self.break_nag = None;
+            }
+            return false;
+        }
+
+        // Count consecutive Esc presses so focus lock can always be escaped
+        if key.code == KeyCode::Esc {
+            self.escape_presses = self.escape_presses.saturating_add(1);
+        } else {
+            self.escape_presses = 0;
+        }
+
         if self.show_help {
             // In help mode, any key closes help
             self.show_help = false;
             return false;
         }
 
-        // Handle navigation keys
-        match key.code {
-            // Tab key - move to next menu item
-            KeyCode::Tab => {
+        if self.show_stats {
+            // Like help, any key closes the statistics
+            self.show_stats = false;
+            return false;
+        }
+
+        let Some(action) = self.key_bindings.action(key.code) else {
+            return false;
+        };
+        if action.is_lockable() && (self.is_focus_locked(timer) || self.is_break_locked(timer)) {
+            return false;
+        }
+
+        match action {
+            KeyAction::NextItem => {
                 self.next_menu_item();
                 false
             }
-            // Left arrow - move to previous menu item
-            KeyCode::Left => {
+            KeyAction::PrevItem => {
                 self.prev_menu_item();
                 false
             }
-            // Right arrow - move to next menu item
-            KeyCode::Right => {
-                self.next_menu_item();
-                false
-            }
-            // Enter or Space - execute focused menu item
-            KeyCode::Enter | KeyCode::Char(' ') => {
-                self.execute_focused_item(timer)
-            }
-            // Legacy shortcut keys (still supported)
-            KeyCode::Char('q') | KeyCode::Esc => {
+            KeyAction::Execute => self.execute_focused_item(timer),
+            KeyAction::Quit => {
+                if self.is_focus_locked(timer) && self.escape_presses < FOCUS_LOCK_ESCAPE_PRESSES {
+                    return false;
+                }
                 self.should_quit = true;
                 true
             }
-            KeyCode::Char('p') => {
-                // Stop any playing audio when starting a new session via shortcut
-                if !timer.is_running() {
-                    self.should_stop_audio = true;
-                }
-                timer.toggle_pause();
-                // Update focused item based on timer state
-                if timer.is_running() {
-                    self.focused_menu_item = MenuItem::Pause;
-                } else {
-                    self.focused_menu_item = MenuItem::Start;
-                }
-                false
-            }
-            KeyCode::Char('s') => {
+            KeyAction::ToggleTimer => self.toggle_timer(timer),
+            KeyAction::Skip => {
                 // Stop any playing audio when skipping via shortcut
                 self.should_stop_audio = true;
                 timer.skip_session();
                 self.focused_menu_item = MenuItem::Start;
                 false
             }
-            KeyCode::Char('r') => {
+            KeyAction::Reset => {
                 // Stop any playing audio when resetting via shortcut
                 self.should_stop_audio = true;
                 timer.reset();
                 self.focused_menu_item = MenuItem::Start;
                 false
             }
-            KeyCode::Char('h') | KeyCode::Char('?') => {
+            KeyAction::Undo => {
+                // Undo an accidental skip or the last session transition
+                if timer.undo_last_transition() {
+                    self.should_stop_audio = true;
+                    self.focused_menu_item = MenuItem::Start;
+                }
+                false
+            }
+            // Jump straight to a session type
+            KeyAction::SwitchToWork | KeyAction::SwitchToShortBreak | KeyAction::SwitchToLongBreak => {
+                let session = match action {
+                    KeyAction::SwitchToWork => SessionType::Work,
+                    KeyAction::SwitchToShortBreak => SessionType::ShortBreak,
+                    _ => SessionType::LongBreak,
+                };
+                self.should_stop_audio = true;
+                timer.switch_to(session);
+                self.focused_menu_item = MenuItem::Start;
+                false
+            }
+            KeyAction::Help => {
                 self.show_help = true;
                 false
             }
-            _ => false,
+            KeyAction::Stats => {
+                self.show_stats = true;
+                false
+            }
+            KeyAction::ToggleClock => {
+                self.toggle_clock();
+                false
+            }
+            KeyAction::ToggleUpcoming => {
+                self.toggle_upcoming();
+                false
+            }
+            KeyAction::Mute => {
+                self.toggle_mute();
+                false
+            }
+            KeyAction::EditTask => {
+                self.task_input = Some(TaskInput::for_task(timer.task()));
+                false
+            }
+            KeyAction::SetTag => {
+                self.tag_input = Some(LineInput {
+                    text: timer.tag().unwrap_or_default().to_string(),
+                });
+                false
+            }
+            // Volume keys leave a ringing alarm alone so it can be turned down
+            KeyAction::VolumeDown => {
+                self.change_volume(-VOLUME_KEY_STEP);
+                false
+            }
+            KeyAction::VolumeUp => {
+                self.change_volume(VOLUME_KEY_STEP);
+                false
+            }
+            // Correct the count for work done away from the timer, or a miscount
+            KeyAction::AddPomodoro => {
+                self.count_adjustment += timer.adjust_count(1);
+                false
+            }
+            KeyAction::RemovePomodoro => {
+                self.count_adjustment += timer.adjust_count(-1);
+                false
+            }
+            // Snoozing keeps the completion popup, so it doesn't count as interaction
+            KeyAction::Snooze => {
+                self.snooze_requested = true;
+                false
+            }
+            KeyAction::JumpToEnd => {
+                timer.set_remaining(DEBUG_JUMP_REMAINING);
+                false
+            }
         }
     }
 }
 
+/// Check if `idle_pause` has passed since `last_input`
+/// A zero `idle_pause` disables idle detection.
+fn idle_expired(last_input: Instant, now: Instant, idle_pause: Duration) -> bool {
+    !idle_pause.is_zero() && now.saturating_duration_since(last_input) >= idle_pause
+}
+
+/// Check if a terminal cell lies inside `rect`
+fn rect_contains(rect: Rect, column: u16, row: u16) -> bool {
+    column >= rect.x && column < rect.right() && row >= rect.y && row < rect.bottom()
+}
+
+/// Change a volume by `step`, clamped to 0.0..=1.0 and kept to whole percents
+fn step_volume(volume: f32, step: f32) -> f32 {
+    ((volume + step).clamp(0.0, 1.0) * 100.0).round() / 100.0
+}
+
+/// Check if an event comes from the user, so it counts against going idle
+/// Focus changes, resizes and plain mouse motion happen without anyone at the keyboard.
+fn is_user_input(event: &Event) -> bool {
+    match event {
+        Event::Key(_) => true,
+        Event::Mouse(mouse) => mouse.kind != MouseEventKind::Moved,
+        _ => false,
+    }
+}
+
 /// Helper function to create a centered rectangle
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -308,8 +1549,16 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 }
 
 /// Render the new single-screen UI
-fn render_new_ui(f: &mut Frame, timer: &Timer, hide_clock: bool, focused_item: MenuItem) {
+fn render_new_ui(
+    f: &mut Frame,
+    timer: &Timer,
+    options: &RenderOptions,
+    state: &FrameState,
+) -> ScreenRegions {
     let size = f.size();
+    if options.minimal {
+        return render_minimal_ui(f, size, timer, options, state);
+    }
     
     // Create main layout - single clean screen
     let chunks = Layout::default()
@@ -320,18 +1569,228 @@ fn render_new_ui(f: &mut Frame, timer: &Timer, hide_clock: bool, focused_item: M
             Constraint::Length(3),   // Session status
             Constraint::Min(8),      // ASCII art and timer
             Constraint::Length(3),   // Statistics
+            Constraint::Length(u16::from(options.show_upcoming)), // Upcoming sessions
+        ])
+        .split(size);
+
+    render_menu_bar(f, chunks[0], timer, state);
+    if options.show_upcoming {
+        render_upcoming(f, chunks[5], timer, state.theme);
+    }
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(20)])
+        .split(chunks[4]);
+    render_statistics(f, bottom[0], timer, state.goal_met, state.theme);
+    render_volume(f, bottom[1], state.volume, state.theme);
+
+    if timer.is_standby() && !state.completion_pending {
+        // Nothing has run yet, so greet instead of showing an idle 00:00 timer
+        let splash = Rect {
+            height: chunks[1].height + chunks[2].height + chunks[3].height,
+            ..chunks[1]
+        };
+        render_standby(f, splash, timer, state);
+        return ScreenRegions {
+            timer: splash,
+            volume: bottom[1],
+        };
+    }
+
+    if state.completion_pending {
+        render_completion_banner(f, chunks[1], timer, state.theme);
+    } else {
+        render_usage_hint(f, chunks[1], state);
+    }
+    render_session_status(f, chunks[2], timer, state, options.blackout());
+    render_ascii_art_center(f, chunks[3], timer, options, state.clock_notice);
+
+    ScreenRegions {
+        timer: chunks[3],
+        volume: bottom[1],
+    }
+}
+
+/// Render the welcome splash shown before the first session starts
+fn render_standby(f: &mut Frame, area: Rect, timer: &Timer, state: &FrameState) {
+    let theme = state.theme;
+    let session_type = timer.get_session_type();
+    let labels = timer.labels();
+    let length = if timer.is_counting_up() {
+        String::new()
+    } else {
+        format!(" · {} min", timer.get_session_length().as_secs().div_ceil(60))
+    };
+    let content = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "🍅 R U S T D O R O 🍅",
+            theme.fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "Next: {} {}{}",
+                labels.emoji(session_type),
+                labels.label(session_type),
+                length
+            ),
+            theme.fg(session_color(session_type)),
+        )),
+        Line::from(Span::styled(
+            format!("Today: {} pomodoros", state.today_pomodoros),
+            theme.fg(Color::White),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "▶ Press Space to start",
+            theme.highlight(Color::Green, Color::Black).add_modifier(Modifier::BOLD),
+        )),
+    ];
+
+    let splash = Paragraph::new(content).alignment(Alignment::Center);
+    f.render_widget(splash, area);
+}
+
+/// Render only the session status, the timer and an optional progress gauge
+fn render_minimal_ui(
+    f: &mut Frame,
+    size: Rect,
+    timer: &Timer,
+    options: &RenderOptions,
+    state: &FrameState,
+) -> ScreenRegions {
+    let theme = options.theme;
+    let show_gauge = options.progress_style != ProgressStyle::Ascii && !options.blackout();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),                     // Session status
+            Constraint::Length(1),                     // Timer
+            Constraint::Length(u16::from(show_gauge)), // Progress
+            Constraint::Min(0),
         ])
         .split(size);
 
-    render_menu_bar(f, chunks[0], focused_item, timer);
-    render_usage_hint(f, chunks[1]);
-    render_session_status(f, chunks[2], timer);
-    render_ascii_art_center(f, chunks[3], timer, hide_clock);
-    render_statistics(f, chunks[4], timer);
+    render_session_status(f, chunks[0], timer, state, options.blackout());
+
+    let color = progress_color(timer, options);
+    let time = Paragraph::new(Span::styled(
+        timer_text(timer, options),
+        theme.fg(color).add_modifier(timer_modifier(timer, options.blackout())),
+    ))
+    .alignment(Alignment::Center);
+    f.render_widget(time, chunks[1]);
+
+    if show_gauge {
+        let ratio = timer.get_progress().clamp(0.0, 1.0);
+        let gauge = Gauge::default()
+            .gauge_style(theme.colors(color, Color::DarkGray))
+            .ratio(ratio)
+            .label(format!("{:.0}%", ratio * 100.0));
+        f.render_widget(gauge, chunks[2]);
+    }
+
+    // Scrolling over the timer still adjusts it; there is no volume indicator
+    ScreenRegions {
+        timer: chunks[1],
+        volume: Rect::default(),
+    }
+}
+
+/// Color of the timer and art while a work session runs into overtime
+const OVERTIME_COLOR: Color = Color::Magenta;
+
+/// Color of the timer and art once a work session is within `warn_threshold_secs` of its end
+const LOW_TIME_COLOR: Color = Color::Red;
+
+/// Get the timer color, following the gradient during work sessions if enabled
+/// Overtime keeps its color even in blackout: the end alarm has already told that the
+/// session is over, so the color gives nothing more away.
+fn progress_color(timer: &Timer, options: &RenderOptions) -> Color {
+    if timer.is_in_overtime() {
+        return OVERTIME_COLOR;
+    }
+    if options.blackout() {
+        return session_color(timer.get_session_type());
+    }
+    if timer.is_low_on_time() {
+        return LOW_TIME_COLOR;
+    }
+    match options.gradient {
+        Some((start, end)) if timer.get_session_type() == SessionType::Work => {
+            interpolate_color(start, end, timer.get_progress())
+        }
+        _ => session_color(timer.get_session_type()),
+    }
+}
+
+/// Blend two RGB colors, `t` going from 0.0 (start) to 1.0 (end)
+fn interpolate_color(start: [u8; 3], end: [u8; 3], t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |i: usize| {
+        let (a, b) = (f64::from(start[i]), f64::from(end[i]));
+        (a + (b - a) * t).round() as u8
+    };
+    Color::Rgb(channel(0), channel(1), channel(2))
+}
+
+/// Get the color used for a session type
+fn session_color(session_type: SessionType) -> Color {
+    match session_type {
+        SessionType::Work => Color::Green,
+        SessionType::ShortBreak => Color::Yellow,
+        SessionType::LongBreak => Color::Blue,
+    }
+}
+
+/// Get the overlay shown while the timer isn't counting
+fn state_cue(timer: &Timer) -> Option<&'static str> {
+    match timer.state {
+        TimerState::Running => None,
+        TimerState::Paused => Some("⏸ PAUSED"),
+        TimerState::Stopped => Some("⏹ STOPPED — press P to start"),
+    }
+}
+
+/// Get the style the timer line is drawn with on top of its color
+/// It blinks while work is running low on time, which also shows without color,
+/// unless a `blackout` hides the progress.
+fn timer_modifier(timer: &Timer, blackout: bool) -> Modifier {
+    let dim = if timer.is_running() { Modifier::empty() } else { Modifier::DIM };
+    let blink = if timer.is_running() && timer.is_low_on_time() && !blackout {
+        Modifier::SLOW_BLINK
+    } else {
+        Modifier::empty()
+    };
+    Modifier::BOLD | dim | blink
+}
+
+/// Format the timer line, e.g. "⏰ 24:59 remaining"
+fn timer_text(timer: &Timer, options: &RenderOptions) -> String {
+    let clock = |time: String| if options.hide_clock { "••:••".to_string() } else { time };
+    if timer.is_in_overtime() {
+        return format!("⏰ {} overtime", clock(timer.get_display_time()));
+    }
+    let display = if timer.is_counting_up() {
+        TimeDisplay::Elapsed
+    } else {
+        options.time_display
+    };
+    match display {
+        TimeDisplay::Remaining => format!("⏰ {} remaining", clock(timer.get_display_time())),
+        TimeDisplay::Elapsed => format!("⏰ {} elapsed", clock(timer.get_elapsed_display())),
+        TimeDisplay::Both => format!(
+            "⏰ {} / {}",
+            clock(timer.get_elapsed_display()),
+            clock(format_clock(timer.get_session_length().as_secs(), timer.shows_hours()))
+        ),
+    }
 }
 
 /// Render the top menu bar with focus navigation
-fn render_menu_bar(f: &mut Frame, area: Rect, focused_item: MenuItem, timer: &Timer) {
+fn render_menu_bar(f: &mut Frame, area: Rect, timer: &Timer, state: &FrameState) {
+    let theme = state.theme;
     let menu_items = MenuItem::all();
     let mut spans = Vec::new();
     
@@ -341,7 +1800,7 @@ fn render_menu_bar(f: &mut Frame, area: Rect, focused_item: MenuItem, timer: &Ti
         }
         
         // Determine if this item should be highlighted
-        let is_focused = item == focused_item;
+        let is_focused = item == state.focused_item;
         
         // Special handling for Start/Pause based on timer state
         let (display_text, is_active) = match item {
@@ -353,24 +1812,26 @@ fn render_menu_bar(f: &mut Frame, area: Rect, focused_item: MenuItem, timer: &Ti
                 }
             }
             MenuItem::Pause => {
-                if timer.is_running() {
+                if timer.is_running() && !state.break_locked {
                     ("Pause", true)
                 } else {
                     ("Pause", false) // Show but inactive when not running
                 }
             }
+            // Locked items stay visible but inactive
+            MenuItem::Skip | MenuItem::Reset => {
+                (item.display_text(), !state.focus_locked && !state.break_locked)
+            }
+            MenuItem::Exit => (item.display_text(), !state.focus_locked),
             _ => (item.display_text(), true)
         };
         
         let style = if is_focused {
-            Style::default()
-                .bg(Color::White)
-                .fg(Color::Black)
-                .add_modifier(Modifier::BOLD)
+            theme.highlight(Color::Black, Color::White).add_modifier(Modifier::BOLD)
         } else if is_active {
-            Style::default().fg(Color::White)
+            theme.fg(Color::White)
         } else {
-            Style::default().fg(Color::DarkGray)
+            theme.inactive()
         };
         
         spans.push(Span::styled(format!("< {} >", display_text), style));
@@ -384,26 +1845,60 @@ fn render_menu_bar(f: &mut Frame, area: Rect, focused_item: MenuItem, timer: &Ti
 }
 
 /// Render usage hint
-fn render_usage_hint(f: &mut Frame, area: Rect) {
-    let hint = Paragraph::new("Press Tab/←/→ to navigate, Enter/Space to select")
-        .style(Style::default().fg(Color::Cyan))
-        .alignment(Alignment::Center);
+fn render_usage_hint(f: &mut Frame, area: Rect, state: &FrameState) {
+    let theme = state.theme;
+    let hint = if state.focus_locked {
+        Paragraph::new("🔒 focus locked — press Esc 3 times to quit")
+            .style(theme.fg(Color::Red))
+    } else if state.break_locked {
+        Paragraph::new("☕ Enjoy your break — controls locked")
+            .style(theme.fg(Color::Yellow))
+    } else {
+        Paragraph::new("Press Tab/←/→ to navigate, Enter/Space to select")
+            .style(theme.fg(Color::Cyan))
+    }
+    .alignment(Alignment::Center);
     
     f.render_widget(hint, area);
 }
 
+/// Render a highlighted line naming the session that just finished
+/// Unlike the completion popup it stays visible behind the help and statistics popups.
+fn render_completion_banner(f: &mut Frame, area: Rect, timer: &Timer, theme: Theme) {
+    let ended = timer.last_ended_session().unwrap_or(SessionType::Work);
+    let banner = Paragraph::new(format!("✅ {} complete — press any key", timer.labels().label(ended)))
+        .style(theme.highlight(Color::Black, Color::Green).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+
+    f.render_widget(banner, area);
+}
+
 /// Render session status with colors
-fn render_session_status(f: &mut Frame, area: Rect, timer: &Timer) {
+/// A `blackout` leaves out the earned break, since it grows with the time worked.
+fn render_session_status(f: &mut Frame, area: Rect, timer: &Timer, state: &FrameState, blackout: bool) {
+    let theme = state.theme;
     let session_type = timer.get_session_type();
-    let (session_text, session_color) = match session_type {
-        SessionType::Work => ("Work", Color::Green),
-        SessionType::ShortBreak => ("Short Break", Color::Yellow),
-        SessionType::LongBreak => ("Long Break", Color::Blue),
-    };
+    let session_color = session_color(session_type);
     
-    let status_text = format!("{} {}", session_type.emoji(), session_text);
+    let labels = timer.labels();
+    // Without colors the session is told apart by a tag instead
+    let mut status_text = if theme.color {
+        format!("{} {}", labels.emoji(session_type), labels.label(session_type))
+    } else {
+        format!("{} [{}]", labels.emoji(session_type), labels.label(session_type).to_uppercase())
+    };
+    if let Some(earned) = timer.get_earned_break().filter(|_| !blackout) {
+        let secs = earned.as_secs();
+        status_text.push_str(&format!(" — earned {}:{:02} break", secs / 60, secs % 60));
+    }
+    if state.paused_idle {
+        status_text.push_str(" — Paused (idle)");
+    }
+    if state.debug {
+        status_text.push_str(" (debug)");
+    }
     let status = Paragraph::new(status_text)
-        .style(Style::default().fg(session_color).add_modifier(Modifier::BOLD))
+        .style(theme.fg(session_color).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
     
@@ -411,156 +1906,2156 @@ fn render_session_status(f: &mut Frame, area: Rect, timer: &Timer) {
 }
 
 /// Render ASCII art center with timer
-fn render_ascii_art_center(f: &mut Frame, area: Rect, timer: &Timer, hide_clock: bool) {
-    let time_text = if hide_clock {
-        "••:••".to_string()
+/// `notice` replaces the pause or stop cue while it is set.
+fn render_ascii_art_center(
+    f: &mut Frame,
+    area: Rect,
+    timer: &Timer,
+    options: &RenderOptions,
+    notice: Option<&str>,
+) {
+    let theme = options.theme;
+    // Create ASCII art based on session type and progress
+    let session_type = timer.get_session_type();
+    // A blackout draws the art as if the session had just started
+    let (progress, direction) = if options.blackout() {
+        (0.0, ProgressDirection::Elapsed)
     } else {
-        timer.get_display_time()
+        (timer.get_progress(), options.progress_direction)
     };
-    
-    // Create ASCII art based on progress
-    let progress = timer.get_progress();
-    let ascii_art = create_progress_ascii_art(progress);
-    
-    let session_color = match timer.get_session_type() {
-        SessionType::Work => Color::Green,
-        SessionType::ShortBreak => Color::Yellow,
-        SessionType::LongBreak => Color::Blue,
+    let ascii_art = match options.art_style {
+        ArtStyle::Session => {
+            create_session_ascii_art(session_type, progress, options.progress_chars, direction)
+        }
+        ArtStyle::Tomato => create_tomato_fill_art(progress),
     };
     
+    let session_color = progress_color(timer, options);
+    // Dim everything that would otherwise look like it is counting
+    let dim = if timer.is_running() { Modifier::empty() } else { Modifier::DIM };
+    
     // Split ASCII art into lines for individual styling
     let ascii_lines: Vec<&str> = ascii_art.split('\n').collect();
     
     // Create content with logo, ASCII art, and timer
+    let cue = match notice.or(state_cue(timer)) {
+        Some(cue) => Line::from(Span::styled(
+            cue,
+            theme.fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        None => Line::from(""),
+    };
     let mut content = vec![
         Line::from(""),
         Line::from(Span::styled(
             "🍅 R U S T D O R O 🍅", 
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            theme.fg(Color::Red).add_modifier(Modifier::BOLD)
         )),
-        Line::from(""),
+        cue,
     ];
     
     // Add ASCII art lines with styling
-    for line in ascii_lines {
-        content.push(Line::from(Span::styled(line, Style::default().fg(session_color))));
+    if options.progress_style != ProgressStyle::Gauge {
+        for line in ascii_lines {
+            let color = if line.contains(TOMATO_FILL) { Color::Red } else { session_color };
+            content.push(Line::from(Span::styled(line, theme.fg(color).add_modifier(dim))));
+        }
     }
     
     // Add timer display
     content.push(Line::from(""));
     content.push(Line::from(Span::styled(
-        format!("│ ⏰ {} remaining │", time_text),
-        Style::default().fg(session_color).add_modifier(Modifier::BOLD)
+        format!("│ {} │", timer_text(timer, options)),
+        theme.fg(session_color).add_modifier(timer_modifier(timer, options.blackout()))
     )));
     content.push(Line::from(""));
     
     let ascii_display = Paragraph::new(content)
         .alignment(Alignment::Center);
     
-    f.render_widget(ascii_display, area);
+    if options.progress_style == ProgressStyle::Ascii || options.blackout() {
+        f.render_widget(ascii_display, area);
+        return;
+    }
+
+    // Reserve the bottom row for the gauge
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+    let ratio = timer.get_progress().clamp(0.0, 1.0);
+    f.render_widget(ascii_display, chunks[0]);
+
+    if options.progress_style == ProgressStyle::Line {
+        // Laid out from the current width on every frame, so it follows resizes
+        let (filled, empty, label) = progress_line_parts(ratio, chunks[1].width, options.progress_chars);
+        let line = Line::from(vec![
+            Span::styled(filled, theme.fg(session_color).add_modifier(dim)),
+            Span::styled(empty, theme.fg(Color::DarkGray)),
+            Span::styled(label, theme.fg(Color::White).add_modifier(dim)),
+        ]);
+        f.render_widget(Paragraph::new(line), chunks[1]);
+        return;
+    }
+
+    let gauge_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(chunks[1])[1];
+
+    let gauge = Gauge::default()
+        .gauge_style(theme.colors(session_color, Color::DarkGray).add_modifier(dim))
+        .ratio(ratio)
+        .label(format!("{:.0}%", ratio * 100.0));
+
+    f.render_widget(gauge, gauge_area);
+}
+
+/// Split a `width` wide progress line into its filled cells, empty cells and percentage
+/// Filled cells round down, so the bar is only full once the session is over.
+fn progress_line_parts(ratio: f64, width: u16, chars: ProgressChars) -> (String, String, String) {
+    let label = format!(" {:>3.0}%", ratio * 100.0);
+    let bar_width = usize::from(width).saturating_sub(label.len());
+    let filled = ((bar_width as f64 * ratio).floor() as usize).min(bar_width);
+    (
+        chars.fill.to_string().repeat(filled),
+        chars.empty.to_string().repeat(bar_width - filled),
+        label,
+    )
 }
 
 /// Render statistics without borders for clean look
-fn render_statistics(f: &mut Frame, area: Rect, timer: &Timer) {
-    let stats_text = format!("🍅 Completed Pomodoros: {}", timer.get_pomodoros_completed());
+fn render_statistics(f: &mut Frame, area: Rect, timer: &Timer, goal_met: bool, theme: Theme) {
+    // With a task there is less room, so the count goes without its label
+    let mut stats_text = match timer.task() {
+        Some(task) => format!(
+            "🍅 {}  ·  Set: {}  ·  {}",
+            timer.get_pomodoros_completed(),
+            cycle_markers(timer.get_cycle_position()),
+            task_text(task)
+        ),
+        None => format!(
+            "🍅 Completed Pomodoros: {}  ·  Set: {}",
+            timer.get_pomodoros_completed(),
+            cycle_markers(timer.get_cycle_position())
+        ),
+    };
+    if let Some(tag) = timer.tag() {
+        stats_text.push_str(&format!("  ·  #{}", tag));
+    }
+    if goal_met {
+        stats_text.push_str("  ·  🎉 Goal met!");
+    }
     let stats = Paragraph::new(stats_text)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::White))
+        .style(theme.fg(Color::White))
         .block(Block::default().borders(Borders::ALL));
     
     f.render_widget(stats, area);
 }
 
-/// Create ASCII art representing progress
-fn create_progress_ascii_art(progress: f64) -> String {
-    let segments = 8;
-    let filled_segments = (progress * segments as f64) as usize;
-    
-    // Create a more sophisticated octagon design
-    let mut art = String::new();
-    
-    // Top section
-    art.push_str("      ╭───────╮\n");
-    art.push_str("    ╱           ╲\n");
-    art.push_str("   ╱             ╲\n");
-    art.push_str("  ╱               ╲\n");
-    
-    // Middle section with progress bar
-    art.push_str(" │  ");
-    for i in 0..segments {
-        if i < filled_segments {
-            art.push('█');
-        } else {
-            art.push('░');
-        }
-    }
-    art.push_str("  │\n");
-    
-    // Bottom section
-    art.push_str("  ╲               ╱\n");
-    art.push_str("   ╲             ╱\n");
-    art.push_str("    ╲           ╱\n");
-    art.push_str("      ╰───────╯");
-    
-    art
+/// Number of sessions the upcoming line lists
+const UPCOMING_SESSIONS: usize = 3;
+
+/// Render the next few sessions, e.g. "Next: Short Break 05:00 → Work 25:00"
+fn render_upcoming(f: &mut Frame, area: Rect, timer: &Timer, theme: Theme) {
+    let upcoming = timer.peek_next_sessions(UPCOMING_SESSIONS);
+    let text = if upcoming.is_empty() {
+        "Next: nothing, the plan is done".to_string()
+    } else {
+        let sessions: Vec<String> = upcoming
+            .iter()
+            .map(|(session, length)| format!("{} {}", timer.labels().label(*session), format_clock(length.as_secs(), false)))
+            .collect();
+        format!("Next: {}", sessions.join(" → "))
+    };
+    let line = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .style(theme.fg(Color::Gray));
+    f.render_widget(line, area);
 }
 
-/// Render help popup
-fn render_help_popup(f: &mut Frame) {
-    let area = centered_rect(70, 80, f.size());
+/// Longest task name shown in the statistics bar
+const MAX_TASK_NAME_CHARS: usize = 20;
 
-    let help_items = vec![
-        ListItem::new("🍅 Rustdoro - Navigation Help"),
-        ListItem::new(""),
-        ListItem::new("Menu Navigation:"),
-        ListItem::new("  [Tab] or [→]    - Next menu item"),
-        ListItem::new("  [←]             - Previous menu item"),
-        ListItem::new("  [Enter/Space]   - Execute focused item"),
-        ListItem::new(""),
-        ListItem::new("Legacy Shortcuts (still work):"),
-        ListItem::new("  [P]             - Start/Pause timer"),
-        ListItem::new("  [S]             - Skip current session"),
-        ListItem::new("  [R]             - Reset timer"),
-        ListItem::new("  [H] or [?]      - Show/Hide this help"),
-        ListItem::new("  [Q] or [Esc]    - Quit application"),
-        ListItem::new(""),
-        ListItem::new("About Pomodoro Technique:"),
-        ListItem::new(""),
-        ListItem::new("• Work for 25 minutes (1 Pomodoro)"),
-        ListItem::new("• Take a 5-minute break"),
-        ListItem::new("• After 4 Pomodoros, take a 15-minute break"),
-        ListItem::new("• Repeat the cycle"),
-        ListItem::new(""),
-        ListItem::new("Press any key to close this help."),
-    ];
+/// Show the task and its progress, e.g. "Task: Report (2/3 🍅)"
+fn task_text(task: &Task) -> String {
+    let mut name: String = task.name.chars().take(MAX_TASK_NAME_CHARS).collect();
+    if task.name.chars().count() > MAX_TASK_NAME_CHARS {
+        name = format!("{}…", name.trim_end());
+    }
+    if task.estimate == 0 {
+        format!("Task: {} ({} 🍅)", name, task.actual)
+    } else {
+        format!("Task: {} ({}/{} 🍅)", name, task.actual, task.estimate)
+    }
+}
+
+/// Most markers drawn for the set before it is shown as a count instead
+const MAX_CYCLE_MARKERS: u8 = 8;
+
+/// Show the position in the set, e.g. "🍅🍅○○" for 2 of 4
+fn cycle_markers((done, size): (u8, u8)) -> String {
+    if size > MAX_CYCLE_MARKERS {
+        return format!("{}/{}", done, size);
+    }
+    let filled = "🍅".repeat(usize::from(done));
+    let empty = "○".repeat(usize::from(size - done));
+    filled + &empty
+}
+
+/// Render the volume indicator, as a bar right after it was changed
+fn render_volume(f: &mut Frame, area: Rect, volume: VolumeIndicator, theme: Theme) {
+    let percent = (volume.volume * 100.0).round() as u16;
+    let block = Block::default().borders(Borders::ALL);
+
+    if volume.muted {
+        let indicator = Paragraph::new("🔇 Muted")
+            .alignment(Alignment::Center)
+            .style(theme.fg(Color::Yellow))
+            .block(block);
+        f.render_widget(indicator, area);
+    } else if volume.show_bar {
+        let gauge = Gauge::default()
+            .block(block)
+            .gauge_style(theme.fg(Color::Cyan))
+            .percent(percent.min(100))
+            .label(format!("🔊 {}%", percent));
+        f.render_widget(gauge, area);
+    } else {
+        let indicator = Paragraph::new(format!("🔊 {}%", percent))
+            .alignment(Alignment::Center)
+            .style(theme.fg(Color::White))
+            .block(block);
+        f.render_widget(indicator, area);
+    }
+}
+
+/// Number of segments in the progress bar drawn inside the session art
+const PROGRESS_SEGMENTS: usize = 8;
+
+/// Placeholder in the art templates that is replaced by the progress bar
+const PROGRESS_PLACEHOLDER: &str = "########";
+
+/// Tomato outline shown during work sessions
+const WORK_ART: [&str; 6] = [
+    "        ╲╱        ",
+    "     ╭──────╮     ",
+    "   ╱          ╲   ",
+    "  │  ########  │  ",
+    "   ╲          ╱   ",
+    "     ╰──────╯     ",
+];
+
+/// Steaming coffee cup shown during short breaks
+const SHORT_BREAK_ART: [&str; 7] = [
+    "       ) )        ",
+    "      ( (         ",
+    "   ╭──────────╮   ",
+    "   │ ######## ├╮  ",
+    "   │          ├╯  ",
+    "   ╰──────────╯   ",
+    "  ══════════════  ",
+];
+
+/// Palm tree on the beach shown during long breaks
+const LONG_BREAK_ART: [&str; 6] = [
+    "    ╲  │  ╱    o  ",
+    "   ──╲ │ ╱──      ",
+    "       │          ",
+    "       │          ",
+    "  ~ ######## ~    ",
+    " ~~~~~~~~~~~~~~~~ ",
+];
+
+/// Create ASCII art for the session type with the progress bar inside it
+fn create_session_ascii_art(
+    session: SessionType,
+    progress: f64,
+    chars: ProgressChars,
+    direction: ProgressDirection,
+) -> String {
+    let template: &[&str] = match session {
+        SessionType::Work => &WORK_ART,
+        SessionType::ShortBreak => &SHORT_BREAK_ART,
+        SessionType::LongBreak => &LONG_BREAK_ART,
+    };
+    let segments = create_progress_segments(progress, chars, direction);
+
+    template
+        .iter()
+        .map(|line| line.replace(PROGRESS_PLACEHOLDER, &segments))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Tomato outline for the draining art; `.` marks cells that can hold filling
+const TOMATO_ART: [&str; 8] = [
+    "        ╲╱        ",
+    "     ╭──────╮     ",
+    "   ╱..........╲   ",
+    "  │............│  ",
+    "  │............│  ",
+    "  │............│  ",
+    "   ╲..........╱   ",
+    "     ╰──────╯     ",
+];
+
+/// Character used for the tomato's filling
+const TOMATO_FILL: char = '▓';
+
+/// Create a tomato whose filling drains from the top as the session progresses
+fn create_tomato_fill_art(progress: f64) -> String {
+    let fillable = TOMATO_ART.iter().filter(|line| line.contains('.')).count();
+    let remaining = (1.0 - progress).clamp(0.0, 1.0);
+    let filled = (remaining * fillable as f64).round() as usize;
+    // Filling sits at the bottom, so the first rows empty out first
+    let mut empty_rows = fillable - filled;
+
+    TOMATO_ART
+        .iter()
+        .map(|line| {
+            if !line.contains('.') {
+                return line.to_string();
+            }
+            let fill = if empty_rows > 0 {
+                empty_rows -= 1;
+                ' '
+            } else {
+                TOMATO_FILL
+            };
+            line.replace('.', &fill.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Create the segment bar shared by every session's art
+/// Draining from full leaves exactly the segments that filling up hasn't reached yet.
+fn create_progress_segments(progress: f64, chars: ProgressChars, direction: ProgressDirection) -> String {
+    let elapsed_segments = ((progress * PROGRESS_SEGMENTS as f64) as usize).min(PROGRESS_SEGMENTS);
+    let filled_segments = match direction {
+        ProgressDirection::Elapsed => elapsed_segments,
+        ProgressDirection::Remaining => PROGRESS_SEGMENTS - elapsed_segments,
+    };
+
+    (0..PROGRESS_SEGMENTS)
+        .map(|i| if i < filled_segments { chars.fill } else { chars.empty })
+        .collect()
+}
+
+/// Render help popup
+fn render_help_popup(f: &mut Frame, key_bindings: &KeyBindings, theme: Theme) {
+    let area = centered_rect(70, 80, f.size());
+
+    let mut help_items = vec![
+        ListItem::new("🍅 Rustdoro - Navigation Help"),
+        ListItem::new(""),
+    ];
+    help_items.extend(key_bindings.help_lines().into_iter().map(ListItem::new));
+    help_items.extend([
+        ListItem::new(""),
+        ListItem::new("About Pomodoro Technique:"),
+        ListItem::new(""),
+        ListItem::new("• Work for 25 minutes (1 Pomodoro)"),
+        ListItem::new("• Take a 5-minute break"),
+        ListItem::new("• After 4 Pomodoros, take a 15-minute break"),
+        ListItem::new("• Repeat the cycle"),
+        ListItem::new(""),
+        ListItem::new("Press any key to close this help."),
+    ]);
 
     let help_list = List::new(help_items)
         .block(
             Block::default()
                 .title(" Help ")
                 .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Yellow)),
+                .style(theme.fg(Color::Yellow)),
         )
-        .style(Style::default().fg(Color::White));
+        .style(theme.fg(Color::White));
 
     f.render_widget(Clear, area); // Clear the background
     f.render_widget(help_list, area);
 }
 
+/// Render the prompt for the task name and its estimate
+fn render_task_popup(f: &mut Frame, input: &TaskInput, theme: Theme) {
+    let area = centered_rect(60, 30, f.size());
+
+    let field = |label: &str, value: &LineInput, active: bool| {
+        let cursor = if active { "▏" } else { "" };
+        let style = if active {
+            theme.fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            theme.fg(Color::Gray)
+        };
+        Line::from(Span::styled(format!("{}: {}{}", label, value.text, cursor), style))
+    };
+    let text = vec![
+        Line::from("📋 What are you working on?"),
+        Line::from(""),
+        field("Task", &input.name, !input.editing_estimate),
+        field("Estimate (pomodoros)", &input.estimate, input.editing_estimate),
+        Line::from(""),
+        Line::from("Enter for next/save, Tab to switch, Esc to cancel"),
+        Line::from("A blank task clears it"),
+    ];
+    let prompt = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Task ")
+                .borders(Borders::ALL)
+                .style(theme.fg(Color::Green)),
+        )
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area); // Clear the background
+    f.render_widget(prompt, area);
+}
+
+/// Render the prompt for a note about the finished work session
+fn render_note_popup(f: &mut Frame, input: &LineInput, theme: Theme) {
+    let area = centered_rect(60, 25, f.size());
+
+    let text = vec![
+        Line::from("📝 What did you get done?"),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("> {}▏", input.text),
+            theme.fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from("Enter to save, Esc to skip"),
+    ];
+    let prompt = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Session Note ")
+                .borders(Borders::ALL)
+                .style(theme.fg(Color::Green)),
+        )
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area); // Clear the background
+    f.render_widget(prompt, area);
+}
+
+/// Render the prompt for the tag pomodoros are filed under
+fn render_tag_popup(f: &mut Frame, input: &LineInput, theme: Theme) {
+    let area = centered_rect(60, 25, f.size());
+
+    let text = vec![
+        Line::from("🏷 Which project or client is this for?"),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("#{}▏", input.text.trim_start_matches('#')),
+            theme.fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from("Enter to save, Esc to cancel, a blank tag clears it"),
+    ];
+    let prompt = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Tag ")
+                .borders(Borders::ALL)
+                .style(theme.fg(Color::Green)),
+        )
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area); // Clear the background
+    f.render_widget(prompt, area);
+}
+
+/// Totals of a finished `--sessions` run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSummary {
+    /// Work sessions completed
+    pub pomodoros: u32,
+    /// Time spent working, without pauses
+    pub focus: Duration,
+    /// Times work was paused
+    pub interruptions: u32,
+}
+
+/// Render the summary shown at the end of a `--sessions` run
+fn render_summary_popup(f: &mut Frame, summary: &RunSummary, theme: Theme) {
+    let area = centered_rect(50, 40, f.size());
+
+    let minutes = summary.focus.as_secs() / 60;
+    let text = format!(
+        "🎯 All sessions done!\n\n\
+         Pomodoros: {}\n\
+         Focus time: {}h {:02}m\n\
+         Interruptions: {}\n\n\
+         Press Q to quit",
+        summary.pomodoros,
+        minutes / 60,
+        minutes % 60,
+        summary.interruptions
+    );
+    let popup = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Summary ")
+                .borders(Borders::ALL)
+                .style(theme.fg(Color::Green)),
+        )
+        .style(theme.fg(Color::White))
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area); // Clear the background
+    f.render_widget(popup, area);
+}
+
+/// Render the prompt that starts the next session on Enter
+fn render_transition_prompt_popup(
+    f: &mut Frame,
+    timer: &Timer,
+    message: Option<&str>,
+    prompt: TransitionPrompt,
+    theme: Theme,
+) {
+    let area = centered_rect(60, 40, f.size());
+    let labels = timer.labels();
+    let ended = timer.last_ended_session().unwrap_or(SessionType::Work);
+    let next = timer.get_session_type();
+
+    let mut text = String::new();
+    if let Some(message) = message {
+        text.push_str(message);
+        text.push_str("\n\n");
+    }
+    text.push_str(&format!(
+        "{}'s over — start {} {}? [Enter]",
+        labels.label(ended),
+        labels.emoji(next),
+        labels.label(next)
+    ));
+    if let Some(deadline) = prompt.deadline {
+        let left = deadline.saturating_duration_since(Instant::now()).as_secs();
+        text.push_str(&format!("\nStarting on its own in {}:{:02}", left / 60, left % 60));
+    }
+    text.push_str("\n\nEsc to stay stopped");
+
+    let popup = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Up Next ")
+                .borders(Borders::ALL)
+                .style(theme.fg(Color::Green)),
+        )
+        .style(theme.fg(Color::White))
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area); // Clear the background
+    f.render_widget(popup, area);
+}
+
+/// Render the reminder shown after too many skipped breaks
+fn render_break_nag_popup(f: &mut Frame, skips: u32, theme: Theme) {
+    let area = centered_rect(60, 30, f.size());
+
+    let text = format!(
+        "☕ You've skipped {} breaks — take one!\n\n\
+         Press Enter to continue",
+        skips
+    );
+    let popup = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Take a break ")
+                .borders(Borders::ALL)
+                .style(theme.fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        )
+        .style(theme.fg(Color::White))
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area); // Clear the background
+    f.render_widget(popup, area);
+}
+
+/// Number of days shown in the statistics bar chart
+const STATS_DAYS: u64 = 7;
+
+/// Most tags the statistics popup lists, busiest first
+const MAX_STATS_TAGS: usize = 5;
+
+/// Render the statistics popup with pomodoros per day over the last week
+/// Tagged pomodoros are totalled per tag below the chart.
+fn render_stats_popup(f: &mut Frame, history: &History, today: NaiveDate, daily_goal: u64, theme: Theme) {
+    let area = centered_rect(80, 70, f.size());
+    let block = Block::default()
+        .title(" Statistics ")
+        .borders(Borders::ALL)
+        .style(theme.fg(Color::Yellow));
+
+    f.render_widget(Clear, area); // Clear the background
+
+    let daily = history.daily_counts(today, STATS_DAYS);
+    let week_total: u64 = daily.iter().map(|day| day.pomodoros).sum();
+    let month = history.daily_counts(today, u64::from(today.day()));
+    if week_total == 0 && month.iter().all(|day| day.pomodoros == 0) {
+        let message = Paragraph::new("No data yet\n\nPress any key to close.")
+            .block(block)
+            .style(theme.fg(Color::White))
+            .alignment(Alignment::Center);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let tags = history.tag_counts();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(if tags.is_empty() { 2 } else { 3 })])
+        .split(inner);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(7 * 5), Constraint::Length(1), Constraint::Length(CALENDAR_WIDTH)])
+        .split(chunks[0]);
+
+    let labels: Vec<String> = daily.iter().map(|day| day.date.format("%a").to_string()).collect();
+    let data: Vec<(&str, u64)> = labels
+        .iter()
+        .zip(&daily)
+        .map(|(label, day)| (label.as_str(), day.pomodoros))
+        .collect();
+    let chart = BarChart::default()
+        .data(&data)
+        .bar_width(4)
+        .bar_gap(1)
+        .bar_style(theme.fg(Color::Red))
+        .value_style(theme.highlight(Color::White, Color::Red))
+        .label_style(theme.fg(Color::White));
+    f.render_widget(chart, top[0]);
+    render_month_calendar(f, top[2], today, &month, daily_goal, theme);
+
+    let today_total = daily.last().map_or(0, |day| day.pomodoros);
+    let mut totals = format!(
+        "Today: {}  ·  Last 7 days: {}  ·  All time: {}\n",
+        today_total,
+        week_total,
+        history.total()
+    );
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags
+            .iter()
+            .take(MAX_STATS_TAGS)
+            .map(|count| format!("#{} {}", count.tag, count.pomodoros))
+            .collect();
+        totals.push_str(&format!("Tags: {}\n", tags.join("  ·  ")));
+    }
+    totals.push_str("Press any key to close.");
+    let totals = Paragraph::new(totals)
+    .style(theme.fg(Color::White))
+    .alignment(Alignment::Center);
+    f.render_widget(totals, chunks[1]);
+}
+
+/// Width of the stats calendar, three columns per day
+const CALENDAR_WIDTH: u16 = 7 * 3;
+
+/// Render the current month with the days that had pomodoros highlighted
+/// Days that met the daily goal are marked with a `*`.
+fn render_month_calendar(
+    f: &mut Frame,
+    area: Rect,
+    today: NaiveDate,
+    counts: &[DailyCount],
+    daily_goal: u64,
+    theme: Theme,
+) {
+    let mut lines = vec![
+        Line::from(Span::styled(today.format("%B %Y").to_string(), theme.fg(Color::Yellow))),
+        Line::from(Span::styled("Mo Tu We Th Fr Sa Su", theme.fg(Color::Gray))),
+    ];
+    for week in month_calendar(today, counts, daily_goal) {
+        let cells: Vec<Span> = week
+            .iter()
+            .map(|day| match day {
+                None => Span::raw("   "),
+                Some(day) => {
+                    let marker = if day.goal_met { '*' } else { ' ' };
+                    let style = match day.pomodoros {
+                        None => theme.fg(Color::DarkGray),
+                        Some(0) => theme.fg(Color::White),
+                        Some(_) if day.goal_met => theme.highlight(Color::Black, Color::Green),
+                        Some(_) => theme.highlight(Color::White, Color::Red),
+                    };
+                    Span::styled(format!("{:>2}{}", day.day, marker), style)
+                }
+            })
+            .collect();
+        lines.push(Line::from(cells));
+    }
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// Render the toasts centered over the bottom of `area`, newest last
+/// Fading toasts are dimmed.
+fn render_toasts(f: &mut Frame, area: Rect, toasts: &[(String, bool)], theme: Theme) {
+    let count = (toasts.len() as u16).min(area.height);
+    for (row, (message, fading)) in toasts.iter().skip(toasts.len() - usize::from(count)).enumerate() {
+        let line = Line::from(format!(" {} ", message));
+        let width = (line.width() as u16).min(area.width);
+        let toast = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.bottom() - count + row as u16,
+            width,
+            height: 1,
+        };
+        let modifier = if *fading { Modifier::DIM } else { Modifier::BOLD };
+        f.render_widget(Clear, toast);
+        f.render_widget(
+            Paragraph::new(line).style(theme.highlight(Color::Black, Color::White).add_modifier(modifier)),
+            toast,
+        );
+    }
+}
+
 /// Render completion message popup
-fn render_completion_message_popup(f: &mut Frame) {
+fn render_completion_message_popup(
+    f: &mut Frame,
+    timer: &Timer,
+    message: &str,
+    show_goal_message: bool,
+    theme: Theme,
+) {
     let area = centered_rect(50, 30, f.size());
 
-    let message = Paragraph::new("🎉 Session completed!\n\nPress any key to continue...")
+    let (text, title) = if show_goal_message {
+        (
+            format!(
+                "🏆 Goal reached!\n\n{} pomodoros completed.\n\nPress any key to continue...",
+                timer.get_target_pomodoros()
+            ),
+            " Goal Reached ",
+        )
+    } else {
+        (
+            format!("{}\n\nPress any key to continue...", message),
+            " Session Complete ",
+        )
+    };
+
+    let message = Paragraph::new(text)
         .block(
             Block::default()
-                .title(" Session Complete ")
+                .title(title)
                 .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Green)),
+                .style(theme.fg(Color::Green)),
         )
-        .style(Style::default().fg(Color::White))
+        .style(theme.fg(Color::White))
         .alignment(Alignment::Center);
 
     f.render_widget(Clear, area); // Clear the background
     f.render_widget(message, area);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HistoryRecord;
+    use chrono::TimeZone;
+    use crossterm::event::KeyModifiers;
+    use ratatui::backend::TestBackend;
+
+    fn test_ui(config: &Config) -> AppUI<TestBackend> {
+        let terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        AppUI::with_terminal(terminal, config)
+    }
+
+    fn sized_ui(config: &Config, width: u16, height: u16) -> AppUI<TestBackend> {
+        let terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        AppUI::with_terminal(terminal, config)
+    }
+
+    #[test]
+    fn test_unchanged_frames_are_skipped() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        let history = History::default();
+
+        ui.draw(&timer, &history, None, false).unwrap();
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert_eq!(ui.frame_counts(), (1, 1));
+
+        // A new second, a key and a message each change the screen
+        timer.start();
+        ui.draw(&timer, &history, None, false).unwrap();
+        timer.remaining_time -= Duration::from_secs(1);
+        ui.draw(&timer, &history, None, false).unwrap();
+        timer.remaining_time -= Duration::from_millis(300);
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert_eq!(ui.frame_counts(), (3, 2));
+
+        press(&mut ui, &mut timer, KeyCode::Char('h'));
+        ui.draw(&timer, &history, None, false).unwrap();
+        ui.draw(&timer, &history, Some("done"), false).unwrap();
+        ui.draw(&timer, &history, Some("done"), false).unwrap();
+        assert_eq!(ui.frame_counts(), (5, 3));
+        assert!(screen_text(&ui).contains("Navigation Help"));
+
+        // A resized terminal is always drawn again
+        ui.terminal.backend_mut().resize(60, 20);
+        ui.draw(&timer, &history, Some("done"), false).unwrap();
+        assert_eq!(ui.frame_counts(), (6, 3));
+    }
+
+    #[test]
+    fn test_toasts_go_away_after_their_time() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let timer = Timer::new(config);
+        let history = History::default();
+
+        ui.push_toast("Config reloaded");
+        ui.expire_toasts(Instant::now());
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert!(screen_text(&ui).contains(" Config reloaded "));
+
+        // Only the newest few are kept
+        for n in 0..MAX_TOASTS {
+            ui.push_toast(&format!("Toast {}", n));
+        }
+        ui.draw(&timer, &history, None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(!text.contains("Config reloaded"));
+        assert!(text.contains("Toast 0") && text.contains("Toast 2"));
+
+        ui.expire_toasts(Instant::now() + TOAST_DURATION);
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert!(!screen_text(&ui).contains("Toast"));
+    }
+
+    #[test]
+    fn test_blackout_shows_no_session_timing() {
+        let styles = [ProgressStyle::Ascii, ProgressStyle::Gauge, ProgressStyle::Both, ProgressStyle::Line];
+        for (style, minimal) in styles.into_iter().map(|style| (style, false)).chain([(ProgressStyle::Both, true)]) {
+            let mut config = Config::default();
+            config.general.no_clock = true;
+            config.general.hide_progress = true;
+            config.general.progress_style = style;
+            config.general.minimal = minimal;
+            let mut timer = Timer::new(config.clone());
+            timer.start();
+            timer.remaining_time = Duration::from_secs(25 * 60 * 3 / 4);
+
+            let mut ui = test_ui(&config);
+            ui.draw(&timer, &History::default(), None, false).unwrap();
+            let buffer = ui.terminal.backend().buffer();
+            // Everything above the statistics and volume bar, whose counts say nothing
+            // about the time in the session; the minimal UI has no such bar
+            let rows = if minimal { buffer.area.height } else { ui.regions.volume.y };
+            let text: String = (0..rows)
+                .flat_map(|y| (0..80).map(move |x| (x, y)))
+                .map(|(x, y)| buffer.get(x, y).symbol.as_str())
+                .collect();
+            assert!(!text.chars().any(|c| c.is_ascii_digit()), "{:?}: {}", style, text);
+            assert!(text.contains("••:••"), "{:?}: {}", style, text);
+        }
+
+        // The hidden clock alone still shows the progress
+        let mut config = Config::default();
+        config.general.no_clock = true;
+        config.general.progress_style = ProgressStyle::Both;
+        let mut timer = Timer::new(config.clone());
+        timer.start();
+        timer.remaining_time = Duration::from_secs(25 * 60 * 3 / 4);
+        let mut ui = test_ui(&config);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("25%"));
+    }
+
+    #[test]
+    fn test_gauge_with_zero_length_session() {
+        // Only a config that skipped validation can get here
+        let mut config = Config::default();
+        config.time.work_minutes = 0;
+        config.general.progress_style = ProgressStyle::Gauge;
+        let mut timer = Timer::new(config.clone());
+        timer.start();
+        assert_eq!(timer.get_progress(), 0.0);
+
+        let mut ui = test_ui(&config);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("0%"));
+    }
+
+    #[test]
+    fn test_progress_line_fills_with_width() {
+        let mut config = Config::default();
+        config.general.progress_style = ProgressStyle::Line;
+        let mut timer = Timer::new(config.clone());
+        timer.start();
+        timer.remaining_time = Duration::from_secs(25 * 60 * 3 / 4);
+
+        for width in [40, 100] {
+            let mut ui = sized_ui(&config, width, 30);
+            ui.draw(&timer, &History::default(), None, false).unwrap();
+            let buffer = ui.terminal.backend().buffer();
+            let row = (0..30)
+                .map(|y| (0..width).map(|x| buffer.get(x, y).symbol.as_str()).collect::<String>())
+                .find(|row| row.ends_with(" 25%"))
+                .unwrap();
+
+            // The bar spans the whole width, less the label
+            let bar_width = usize::from(width) - " 25%".len() - 1;
+            let filled = row.chars().filter(|&c| c == '█').count();
+            assert_eq!(filled, bar_width / 4, "width {}", width);
+            assert_eq!(filled + row.chars().filter(|&c| c == '░').count(), bar_width);
+        }
+    }
+
+    #[test]
+    fn test_progress_line_parts() {
+        let chars = ProgressChars::default();
+        assert_eq!(progress_line_parts(0.0, 15, chars), (String::new(), "░".repeat(10), "   0%".to_string()));
+        assert_eq!(progress_line_parts(0.999, 15, chars).0, "█".repeat(9));
+        assert_eq!(progress_line_parts(1.0, 15, chars), ("█".repeat(10), String::new(), " 100%".to_string()));
+        // Too narrow for a bar leaves just the label
+        assert_eq!(progress_line_parts(0.5, 3, chars).0, "");
+    }
+
+    #[test]
+    fn test_overtime_is_shown_in_its_own_color() {
+        let mut config = Config::default();
+        config.time.overtime = true;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        timer.remaining_time = Duration::ZERO;
+        timer.tick();
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("+00:00 overtime"));
+        assert_eq!(progress_color(&timer, &ui.render_options), OVERTIME_COLOR);
+    }
+
+    #[test]
+    fn test_low_time_style_switches_at_the_threshold() {
+        let mut config = Config::default();
+        config.time.warn_threshold_secs = 60;
+        let ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        timer.remaining_time = Duration::from_millis(60_001);
+        assert_eq!(progress_color(&timer, &ui.render_options), Color::Green);
+        assert!(!timer_modifier(&timer, false).contains(Modifier::SLOW_BLINK));
+
+        timer.remaining_time = Duration::from_secs(60);
+        assert_eq!(progress_color(&timer, &ui.render_options), LOW_TIME_COLOR);
+        assert!(timer_modifier(&timer, false).contains(Modifier::SLOW_BLINK));
+
+        // A paused timer stays red but stops blinking
+        timer.pause();
+        assert_eq!(progress_color(&timer, &ui.render_options), LOW_TIME_COLOR);
+        assert!(!timer_modifier(&timer, false).contains(Modifier::SLOW_BLINK));
+
+        let mut timer = Timer::new(Config::default());
+        timer.start();
+        timer.remaining_time = Duration::from_secs(1);
+        assert_eq!(progress_color(&timer, &ui.render_options), Color::Green);
+    }
+
+    #[test]
+    fn test_goal_badge_in_statistics() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("Goal met!"));
+        ui.set_goal_met(true);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("Goal met!"));
+    }
+
+    #[test]
+    fn test_debug_key_needs_debug_mode() {
+        let mut config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config.clone());
+        press(&mut ui, &mut timer, KeyCode::Char('d'));
+        assert_eq!(timer.remaining_time, Duration::from_secs(25 * 60));
+        assert!(!ui.key_bindings.help_lines().iter().any(|line| line.contains("debug")));
+
+        config.general.debug = true;
+        let mut ui = test_ui(&config);
+        press(&mut ui, &mut timer, KeyCode::Char('d'));
+        assert_eq!(timer.remaining_time, DEBUG_JUMP_REMAINING);
+        assert!(ui.key_bindings.help_lines().iter().any(|line| line.contains("[D]")));
+        timer.start();
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("Work (debug)"));
+    }
+
+    #[test]
+    fn test_mute_key_shows_indicator() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        press(&mut ui, &mut timer, KeyCode::Char('m'));
+        assert_eq!(ui.take_mute_change(), Some(true));
+        assert_eq!(ui.take_mute_change(), None);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("Muted"));
+        assert!(!text.contains("70%"));
+
+        press(&mut ui, &mut timer, KeyCode::Char('m'));
+        assert_eq!(ui.take_mute_change(), Some(false));
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("Muted"));
+        assert!(timer.is_running());
+    }
+
+    #[test]
+    fn test_clock_toggles_at_runtime() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let shown = screen_text(&ui);
+        assert!(shown.contains("25:00 remaining"));
+
+        press(&mut ui, &mut timer, KeyCode::Char('v'));
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let hidden = screen_text(&ui);
+        assert_ne!(hidden, shown);
+        assert!(!hidden.contains("25:00"));
+        assert!(hidden.contains("••:•• remaining"));
+        assert!(hidden.contains("Clock hidden"));
+
+        press(&mut ui, &mut timer, KeyCode::Char('v'));
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("25:00 remaining"));
+        assert!(text.contains("Clock shown"));
+
+        // The confirmation goes away on its own
+        ui.clock_toggled_at = Some(Instant::now() - CLOCK_NOTICE_DURATION);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("Clock shown"));
+    }
+
+    #[test]
+    fn test_time_display_modes() {
+        let rendered = |time_display: TimeDisplay| {
+            let mut config = Config::default();
+            config.general.time_display = time_display;
+            let mut ui = test_ui(&config);
+            let mut timer = Timer::new(config);
+            timer.start();
+            timer.remaining_time = Duration::from_secs(13 * 60);
+            ui.draw(&timer, &History::default(), None, false).unwrap();
+            screen_text(&ui)
+        };
+
+        assert!(rendered(TimeDisplay::Remaining).contains("13:00 remaining"));
+        assert!(rendered(TimeDisplay::Elapsed).contains("12:00 elapsed"));
+        let both = rendered(TimeDisplay::Both);
+        assert!(both.contains("12:00 / 25:00"));
+        assert!(!both.contains("remaining"));
+    }
+
+    #[test]
+    fn test_minimal_layout_fits_small_terminal() {
+        let mut config = Config::default();
+        let timer = Timer::new(config.clone());
+
+        // The full layout has no room left for the timer in a 30x5 pane
+        let mut ui = sized_ui(&config, 30, 5);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("25:00"));
+
+        config.general.minimal = true;
+        let mut ui = sized_ui(&config, 30, 5);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("Work"));
+        assert!(text.contains("25:00"));
+        assert!(!text.contains("Skip"));
+
+        // The gauge needs one more row
+        config.general.progress_style = ProgressStyle::Gauge;
+        let mut ui = sized_ui(&config, 30, 5);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("25:00"));
+        assert!(text.contains("0%"));
+    }
+
+    fn press(ui: &mut AppUI<TestBackend>, timer: &mut Timer, code: KeyCode) -> bool {
+        ui.process_key_event(KeyEvent::new(code, KeyModifiers::NONE), timer)
+    }
+
+    #[test]
+    fn test_key_release_is_ignored() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        for kind in [KeyEventKind::Release, KeyEventKind::Repeat] {
+            for code in [KeyCode::Char(' '), KeyCode::Char('s'), KeyCode::Char('q')] {
+                let key = KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind);
+                assert!(!ui.process_key_event(key, &mut timer));
+            }
+        }
+
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert!(!ui.should_quit);
+        assert!(!ui.should_stop_audio_on_input());
+
+        // The press itself still acts
+        press(&mut ui, &mut timer, KeyCode::Char(' '));
+        assert!(timer.is_running());
+    }
+
+    #[test]
+    fn test_space_runs_focused_item_by_default() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        ui.focused_menu_item = MenuItem::Help;
+        press(&mut ui, &mut timer, KeyCode::Char(' '));
+        assert!(ui.show_help);
+        assert!(timer.is_stopped());
+
+        ui.show_help = false;
+        ui.focused_menu_item = MenuItem::Start;
+        press(&mut ui, &mut timer, KeyCode::Char(' '));
+        assert!(timer.is_running());
+        assert_eq!(ui.focused_menu_item, MenuItem::Pause);
+    }
+
+    #[test]
+    fn test_space_toggles_timer_whatever_is_focused() {
+        let mut config = Config::default();
+        config.general.space_toggles_timer = true;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        ui.focused_menu_item = MenuItem::Help;
+        press(&mut ui, &mut timer, KeyCode::Char(' '));
+        assert!(!ui.show_help);
+        assert!(timer.is_running());
+        assert_eq!(ui.focused_menu_item, MenuItem::Pause);
+
+        ui.focused_menu_item = MenuItem::Skip;
+        press(&mut ui, &mut timer, KeyCode::Char(' '));
+        assert!(timer.is_paused());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(ui.focused_menu_item, MenuItem::Start);
+
+        // Enter still runs the focused item
+        ui.focused_menu_item = MenuItem::Help;
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert!(ui.show_help);
+        assert!(timer.is_paused());
+    }
+
+    #[test]
+    fn test_help_lists_the_active_bindings() {
+        let default_help = KeyBindings::from_config(&Config::default()).help_lines();
+        assert!(default_help.contains(&format!("  {:<16}- Execute focused item", "[Enter] or [Space]")));
+        assert!(default_help.contains(&format!("  {:<16}- Start/Pause timer", "[P]")));
+        assert!(default_help.contains(&format!("  {:<16}- Volume down", "[")));
+
+        let mut config = Config::default();
+        config.general.space_toggles_timer = true;
+        let mut ui = sized_ui(&config, 80, 60);
+        let mut timer = Timer::new(config);
+        press(&mut ui, &mut timer, KeyCode::Char('?'));
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+
+        let text = screen_text(&ui);
+        assert!(text.contains(&format!("{:<16}- Start/Pause timer", "[P] or [Space]")), "{}", text);
+        assert!(text.contains(&format!("{:<16}- Execute focused item", "[Enter]")));
+        assert!(text.contains("Snooze the end alarm"));
+    }
+
+    #[test]
+    fn test_upcoming_sessions_toggle() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        let upcoming = "Next: Short Break 05:00 → Work 25:00 → Short Break 05:00";
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("Next:"));
+
+        press(&mut ui, &mut timer, KeyCode::Char('n'));
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains(upcoming), "{}", screen_text(&ui));
+
+        press(&mut ui, &mut timer, KeyCode::Char('n'));
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains(upcoming));
+    }
+
+    #[test]
+    fn test_tag_prompt_and_stats() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        press(&mut ui, &mut timer, KeyCode::Char('#'));
+        for c in "client-a".chars() {
+            press(&mut ui, &mut timer, KeyCode::Char(c));
+        }
+        // Keys go to the prompt instead of the shortcuts
+        assert!(timer.is_running());
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert_eq!(timer.tag(), Some("client-a"));
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("#client-a"));
+
+        let mut history = History::default();
+        let mut record = HistoryRecord::new(Local::now(), 25);
+        record.tag = Some("client-a".to_string());
+        history.record(record.clone()).unwrap();
+        history.record(record).unwrap();
+        history.record(HistoryRecord::new(Local::now(), 25)).unwrap();
+        press(&mut ui, &mut timer, KeyCode::Char('t'));
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert!(screen_text(&ui).contains("Tags: #client-a 2"), "{}", screen_text(&ui));
+
+        // Esc keeps the tag, a blank one clears it
+        press(&mut ui, &mut timer, KeyCode::Esc);
+        press(&mut ui, &mut timer, KeyCode::Char('#'));
+        press(&mut ui, &mut timer, KeyCode::Esc);
+        assert_eq!(timer.tag(), Some("client-a"));
+        press(&mut ui, &mut timer, KeyCode::Char('#'));
+        for _ in 0.."client-a".len() {
+            press(&mut ui, &mut timer, KeyCode::Backspace);
+        }
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert_eq!(timer.tag(), None);
+    }
+
+    fn focus_lock_config() -> Config {
+        let mut config = Config::default();
+        config.general.focus_lock = true;
+        config
+    }
+
+    #[test]
+    fn test_focus_lock_blocks_skip_reset_and_quit() {
+        let config = focus_lock_config();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        assert!(ui.is_focus_locked(&timer));
+
+        press(&mut ui, &mut timer, KeyCode::Char('s'));
+        press(&mut ui, &mut timer, KeyCode::Char('r'));
+        assert!(!press(&mut ui, &mut timer, KeyCode::Char('q')));
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert!(timer.is_running());
+        assert!(!ui.should_quit);
+
+        ui.focused_menu_item = MenuItem::Skip;
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        ui.focused_menu_item = MenuItem::Exit;
+        assert!(!press(&mut ui, &mut timer, KeyCode::Enter));
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert!(!ui.should_quit);
+    }
+
+    #[test]
+    fn test_focus_lock_blocks_scrolling_the_timer() {
+        let config = focus_lock_config();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let (timer_area, volume_area) = (ui.regions.timer, ui.regions.volume);
+
+        // Scrolling down would otherwise drain the session and end it early
+        for _ in 0..30 {
+            scroll(&mut ui, &mut timer, MouseEventKind::ScrollDown, timer_area);
+        }
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert_eq!(timer.get_display_time(), "25:00");
+        // The volume stays reachable
+        scroll(&mut ui, &mut timer, MouseEventKind::ScrollDown, volume_area);
+        assert!(ui.take_volume_change().is_some());
+    }
+
+    #[test]
+    fn test_focus_lock_ends_with_overtime() {
+        let mut config = focus_lock_config();
+        config.time.overtime = true;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        timer.remaining_time = Duration::ZERO;
+        timer.tick();
+        assert!(timer.is_in_overtime());
+        assert!(!ui.is_focus_locked(&timer));
+
+        press(&mut ui, &mut timer, KeyCode::Char('s'));
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+    }
+
+    #[test]
+    fn test_focus_lock_triple_escape_quits() {
+        let config = focus_lock_config();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        assert!(!press(&mut ui, &mut timer, KeyCode::Esc));
+        assert!(!press(&mut ui, &mut timer, KeyCode::Esc));
+        // Any other key restarts the sequence
+        press(&mut ui, &mut timer, KeyCode::Tab);
+        assert!(!press(&mut ui, &mut timer, KeyCode::Esc));
+        assert!(!press(&mut ui, &mut timer, KeyCode::Esc));
+        assert!(press(&mut ui, &mut timer, KeyCode::Esc));
+        assert!(ui.should_quit);
+    }
+
+    #[test]
+    fn test_focus_lock_leaves_breaks_and_pauses_unlocked() {
+        let config = focus_lock_config();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        // Not locked until the work session runs
+        assert!(!ui.is_focus_locked(&timer));
+        timer.start();
+        press(&mut ui, &mut timer, KeyCode::Char('p'));
+        assert!(timer.is_paused());
+        press(&mut ui, &mut timer, KeyCode::Char('s'));
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+
+        timer.start();
+        assert!(!ui.is_focus_locked(&timer));
+        press(&mut ui, &mut timer, KeyCode::Char('s'));
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+    }
+
+    #[test]
+    fn test_strict_break_blocks_skip_and_pause() {
+        let mut config = Config::default();
+        config.general.strict_break = true;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.skip_session();
+        assert!(ui.is_break_locked(&timer));
+
+        // The break can still be started
+        press(&mut ui, &mut timer, KeyCode::Char('p'));
+        assert!(timer.is_running());
+
+        press(&mut ui, &mut timer, KeyCode::Char('p'));
+        press(&mut ui, &mut timer, KeyCode::Char('s'));
+        press(&mut ui, &mut timer, KeyCode::Char('r'));
+        ui.focused_menu_item = MenuItem::Skip;
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        ui.focused_menu_item = MenuItem::Pause;
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert!(timer.is_running());
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("Enjoy your break"));
+
+        // Quit stays available
+        assert!(press(&mut ui, &mut timer, KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_strict_break_leaves_work_unlocked() {
+        let mut config = Config::default();
+        config.general.strict_break = true;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        assert!(!ui.is_break_locked(&timer));
+        press(&mut ui, &mut timer, KeyCode::Char('p'));
+        assert!(timer.is_paused());
+        press(&mut ui, &mut timer, KeyCode::Char('s'));
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+    }
+
+    fn count_filled(art: &str) -> usize {
+        art.chars().filter(|&c| c == '█').count()
+    }
+
+    #[test]
+    fn test_session_art_filled_segments() {
+        let sessions = [SessionType::Work, SessionType::ShortBreak, SessionType::LongBreak];
+        let chars = ProgressChars::default();
+
+        for session in sessions {
+            assert_eq!(count_filled(&create_session_ascii_art(session, 0.0, chars, ProgressDirection::Elapsed)), 0);
+            assert_eq!(count_filled(&create_session_ascii_art(session, 0.5, chars, ProgressDirection::Elapsed)), 4);
+            assert_eq!(count_filled(&create_session_ascii_art(session, 1.0, chars, ProgressDirection::Elapsed)), 8);
+
+            let art = create_session_ascii_art(session, 0.3, chars, ProgressDirection::Elapsed);
+            assert_eq!(count_filled(&art), 2);
+            assert_eq!(art.chars().filter(|&c| c == '░').count(), 6);
+        }
+    }
+
+    #[test]
+    fn test_remaining_direction_drains_the_bar() {
+        let chars = ProgressChars::default();
+        let filled = |progress: f64, direction| {
+            count_filled(&create_session_ascii_art(SessionType::Work, progress, chars, direction))
+        };
+
+        assert_eq!(filled(0.0, ProgressDirection::Remaining), 8);
+        assert_eq!(filled(1.0, ProgressDirection::Remaining), 0);
+        for progress in [0.0, 0.1, 0.3, 0.5, 0.74, 0.99, 1.0] {
+            let elapsed = filled(progress, ProgressDirection::Elapsed);
+            let remaining = filled(progress, ProgressDirection::Remaining);
+            assert_eq!(elapsed + remaining, PROGRESS_SEGMENTS, "at {}", progress);
+        }
+    }
+
+    #[test]
+    fn test_tomato_fill_drains_with_progress() {
+        let filled_lines = |progress: f64| {
+            create_tomato_fill_art(progress)
+                .lines()
+                .filter(|line| line.contains(TOMATO_FILL))
+                .count()
+        };
+
+        assert_eq!(filled_lines(0.0), 5);
+        assert_eq!(filled_lines(1.0), 0);
+        let mut previous = filled_lines(0.0);
+        for step in 1..=20 {
+            let filled = filled_lines(step as f64 / 20.0);
+            assert!(filled <= previous, "fill grew at step {}", step);
+            previous = filled;
+        }
+
+        // The outline never changes width
+        for line in create_tomato_fill_art(0.4).lines() {
+            assert_eq!(line.chars().count(), 18);
+        }
+    }
+
+    #[test]
+    fn test_custom_progress_chars() {
+        let segment_row = |config: &Config| {
+            let mut ui = test_ui(config);
+            let mut timer = Timer::new(config.clone());
+            timer.start();
+            timer.remaining_time = timer.get_session_length() / 2;
+            ui.draw(&timer, &History::default(), None, false).unwrap();
+
+            let buffer = ui.terminal.backend().buffer();
+            let width = buffer.area.width as usize;
+            let text: Vec<&str> = buffer.content().iter().map(|cell| cell.symbol.as_str()).collect();
+            text.chunks(width)
+                .map(|row| row.concat())
+                .find(|row| row.contains('│') && (row.contains('░') || row.contains('-')))
+                .unwrap()
+        };
+
+        let default_row = segment_row(&Config::default());
+        assert!(default_row.contains("████░░░░"));
+
+        let mut config = Config::default();
+        config.general.fill_char = "#".to_string();
+        config.general.empty_char = "-".to_string();
+        let custom_row = segment_row(&config);
+        assert!(custom_row.contains("│  ####----  │"), "{}", custom_row);
+        // The octagon keeps its shape
+        assert_eq!(custom_row.chars().count(), default_row.chars().count());
+        assert_eq!(custom_row.find('│'), default_row.find('│'));
+    }
+
+    #[test]
+    fn test_session_art_differs_by_session() {
+        let chars = ProgressChars::default();
+        let work = create_session_ascii_art(SessionType::Work, 0.5, chars, ProgressDirection::Elapsed);
+        let short_break = create_session_ascii_art(SessionType::ShortBreak, 0.5, chars, ProgressDirection::Elapsed);
+        let long_break = create_session_ascii_art(SessionType::LongBreak, 0.5, chars, ProgressDirection::Elapsed);
+
+        assert_ne!(work, short_break);
+        assert_ne!(work, long_break);
+        assert_ne!(short_break, long_break);
+    }
+
+    fn screen_text(ui: &AppUI<TestBackend>) -> String {
+        let buffer = ui.terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol.as_str()).collect()
+    }
+
+    fn scroll(ui: &mut AppUI<TestBackend>, timer: &mut Timer, kind: MouseEventKind, area: Rect) {
+        let mouse = MouseEvent {
+            kind,
+            column: area.x + area.width / 2,
+            row: area.y + area.height / 2,
+            modifiers: KeyModifiers::NONE,
+        };
+        ui.handle_mouse(mouse, timer);
+    }
+
+    #[test]
+    fn test_scroll_over_timer_changes_time() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let timer_area = ui.regions.timer;
+
+        scroll(&mut ui, &mut timer, MouseEventKind::ScrollUp, timer_area);
+        assert_eq!(timer.get_display_time(), "26:00");
+        scroll(&mut ui, &mut timer, MouseEventKind::ScrollDown, timer_area);
+        scroll(&mut ui, &mut timer, MouseEventKind::ScrollDown, timer_area);
+        assert_eq!(timer.get_display_time(), "24:00");
+        assert!(ui.take_volume_change().is_none());
+    }
+
+    #[test]
+    fn test_scroll_over_volume_changes_volume() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let volume_area = ui.regions.volume;
+
+        scroll(&mut ui, &mut timer, MouseEventKind::ScrollUp, volume_area);
+        assert_eq!(ui.take_volume_change(), Some(0.75));
+        assert!(ui.take_volume_change().is_none());
+        assert_eq!(timer.get_display_time(), "25:00");
+
+        for _ in 0..30 {
+            scroll(&mut ui, &mut timer, MouseEventKind::ScrollUp, volume_area);
+        }
+        assert_eq!(ui.take_volume_change(), Some(1.0));
+    }
+
+    #[test]
+    fn test_volume_keys_step_and_clamp() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        press(&mut ui, &mut timer, KeyCode::Char(']'));
+        assert_eq!(ui.take_volume_change(), Some(0.8));
+        for _ in 0..5 {
+            press(&mut ui, &mut timer, KeyCode::Char(']'));
+        }
+        assert_eq!(ui.take_volume_change(), Some(1.0));
+
+        for _ in 0..12 {
+            press(&mut ui, &mut timer, KeyCode::Char('['));
+        }
+        assert_eq!(ui.take_volume_change(), Some(0.0));
+        assert!(!ui.should_stop_audio_on_input());
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("0%"));
+        assert!(timer.is_stopped());
+    }
+
+    #[test]
+    fn test_count_keys_adjust_and_report() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        press(&mut ui, &mut timer, KeyCode::Char('+'));
+        press(&mut ui, &mut timer, KeyCode::Char('+'));
+        assert_eq!(timer.get_pomodoros_completed(), 2);
+        assert_eq!(ui.take_count_adjustment(), Some(2));
+        assert_eq!(ui.take_count_adjustment(), None);
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("Completed Pomodoros: 2"));
+
+        // Only the removals that happened are reported
+        for _ in 0..3 {
+            press(&mut ui, &mut timer, KeyCode::Char('-'));
+        }
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+        assert_eq!(ui.take_count_adjustment(), Some(-2));
+        assert!(timer.is_running());
+        assert!(!ui.should_stop_audio_on_input());
+    }
+
+    #[test]
+    fn test_no_color_uses_default_colors() {
+        let mut config = Config::default();
+        config.general.no_color = true;
+        config.general.progress_style = ProgressStyle::Both;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        timer.remaining_time = Duration::from_secs(600);
+
+        ui.draw(&timer, &History::default(), Some("done"), false).unwrap();
+        let buffer = ui.terminal.backend().buffer().clone();
+        let plain = |cell: &ratatui::buffer::Cell| cell.fg == Color::Reset && cell.bg == Color::Reset;
+        // The menu bar spans, including the focused item
+        for x in 0..buffer.area.width {
+            assert!(plain(buffer.get(x, 1)), "menu bar cell {} is colored", x);
+        }
+        assert!(buffer.content().iter().all(plain));
+        let focused = buffer.content().iter().any(|cell| cell.modifier.contains(Modifier::REVERSED));
+        assert!(focused);
+        assert!(screen_text(&ui).contains("[WORK]"));
+
+        // Colors are on by default
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        ui.draw(&Timer::new(config), &History::default(), None, false).unwrap();
+        assert!(!ui.terminal.backend().buffer().content().iter().all(plain));
+        assert!(!screen_text(&ui).contains("[WORK]"));
+    }
+
+    #[test]
+    fn test_paused_and_stopped_cues() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        timer.start();
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(!text.contains("PAUSED"));
+        assert!(!text.contains("STOPPED"));
+
+        timer.pause();
+        assert!(timer.is_paused());
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("⏸ PAUSED"));
+        assert!(!text.contains("STOPPED"));
+        let dimmed = ui
+            .terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .any(|cell| cell.symbol == "⏰" && cell.modifier.contains(Modifier::DIM));
+        assert!(dimmed);
+
+        timer.start();
+        timer.remaining_time = Duration::ZERO;
+        timer.tick();
+        assert!(timer.is_stopped());
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("⏹ STOPPED"));
+    }
+
+    #[test]
+    fn test_standby_screen_before_first_start() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("Press Space to start"));
+        assert!(text.contains("Work · 25 min"));
+        assert!(text.contains("Today: 0 pomodoros"));
+        assert!(!text.contains("STOPPED"));
+        assert!(!text.contains("25:00"));
+
+        press(&mut ui, &mut timer, KeyCode::Char(' '));
+        assert!(timer.is_running());
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(!text.contains("Press Space to start"));
+        assert!(text.contains("⏰"));
+    }
+
+    #[test]
+    fn test_daily_counts_roll_over_at_midnight() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let timer = Timer::new(config);
+        let late = Local.with_ymd_and_hms(2024, 3, 1, 23, 50, 0).unwrap();
+        let mut history = History::default();
+        history.record(HistoryRecord::new(late, 25)).unwrap();
+
+        ui.set_today(late.date_naive());
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert!(screen_text(&ui).contains("Today: 1 pomodoros"));
+
+        // Nothing else changes at midnight, the screen is still drawn again
+        ui.set_today(NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert!(screen_text(&ui).contains("Today: 0 pomodoros"));
+        assert_eq!(ui.frame_counts(), (2, 0));
+
+        ui.show_stats = true;
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert!(screen_text(&ui).contains("Today: 0  ·  Last 7 days: 1"));
+    }
+
+    #[test]
+    fn test_stats_calendar_marks_goal_days() {
+        let mut config = Config::default();
+        config.time.daily_goal = 2;
+        let mut ui = test_ui(&config);
+        let timer = Timer::new(config);
+        let mut history = History::default();
+        let first = Local.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap();
+        history.record(HistoryRecord::new(first, 25)).unwrap();
+        history.record(HistoryRecord::new(first, 25)).unwrap();
+        history.record(HistoryRecord::new(first + chrono::Days::new(1), 25)).unwrap();
+
+        ui.set_today(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        ui.show_stats = true;
+        ui.draw(&timer, &history, None, false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("March 2024"), "{}", text);
+        assert!(text.contains("Mo Tu We Th Fr Sa Su"));
+        // March 2024 starts on a Friday, and only the 1st met the goal
+        assert!(text.contains(" 1* 2  3"), "{}", text);
+        assert!(text.contains(" 4  5  6"), "{}", text);
+    }
+
+    #[test]
+    fn test_summary_stays_until_quit() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        ui.show_summary(RunSummary {
+            pomodoros: 8,
+            focus: Duration::from_secs(3 * 3600 + 5 * 60),
+            interruptions: 2,
+        });
+
+        ui.draw(&timer, &History::default(), Some("done"), false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("Pomodoros: 8"));
+        assert!(text.contains("Focus time: 3h 05m"));
+        assert!(text.contains("Interruptions: 2"));
+
+        // Other keys only silence the alarm
+        for code in [KeyCode::Char('p'), KeyCode::Char('h'), KeyCode::Enter] {
+            assert!(!press(&mut ui, &mut timer, code));
+        }
+        assert!(ui.should_stop_audio_on_input());
+        assert!(timer.is_stopped());
+        assert!(!ui.show_help);
+        assert!(press(&mut ui, &mut timer, KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_transition_prompt_then_start() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        timer.remaining_time = Duration::ZERO;
+        timer.tick();
+        ui.show_transition_prompt(None);
+
+        ui.draw(&timer, &History::default(), Some("Well done"), false).unwrap();
+        let text = screen_text(&ui);
+        assert!(text.contains("Well done"));
+        assert!(text.contains("Work's over — start"));
+        assert!(text.contains("Short Break? [Enter]"));
+
+        // Keys other than Enter leave the next session waiting
+        for code in [KeyCode::Char('p'), KeyCode::Char('s'), KeyCode::Char(' ')] {
+            assert!(!press(&mut ui, &mut timer, code));
+        }
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert!(ui.should_stop_audio_on_input());
+
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert!(timer.is_running());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(ui.focused_menu_item, MenuItem::Pause);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("[Enter]"));
+
+        // Esc closes the prompt without starting
+        timer.pause();
+        ui.show_transition_prompt(None);
+        press(&mut ui, &mut timer, KeyCode::Esc);
+        assert!(!timer.is_running());
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("[Enter]"));
+    }
+
+    #[test]
+    fn test_transition_prompt_times_out() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        let now = Instant::now();
+
+        // Without a deadline the prompt waits for Enter
+        ui.show_transition_prompt(None);
+        assert!(!ui.start_if_prompt_expired(&mut timer, now + Duration::from_secs(3600)));
+
+        ui.show_transition_prompt(Some(now + Duration::from_secs(30)));
+        assert!(!ui.start_if_prompt_expired(&mut timer, now + Duration::from_secs(29)));
+        assert!(timer.is_stopped());
+        assert!(ui.start_if_prompt_expired(&mut timer, now + Duration::from_secs(30)));
+        assert!(timer.is_running());
+        assert!(!ui.start_if_prompt_expired(&mut timer, now + Duration::from_secs(31)));
+    }
+
+    #[test]
+    fn test_break_nag_needs_acknowledgment() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        ui.show_break_nag(3);
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("skipped 3 breaks — take one!"));
+
+        // Other keys don't reach the timer while the reminder is up
+        for code in [KeyCode::Char('s'), KeyCode::Char('p'), KeyCode::Char('q')] {
+            assert!(!press(&mut ui, &mut timer, code));
+        }
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_session_type(), SessionType::Work);
+        assert!(!ui.should_quit);
+
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(!screen_text(&ui).contains("skipped 3 breaks"));
+    }
+
+    #[test]
+    fn test_snooze_key_keeps_ended_state() {
+        let mut config = Config::default();
+        config.time.work_minutes = 1;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        timer.skip_session();
+        let _ = timer.take_completed_work();
+        let pomodoros = timer.get_pomodoros_completed();
+
+        press(&mut ui, &mut timer, KeyCode::Char('z'));
+        assert!(ui.take_snooze_request());
+        assert!(!ui.take_snooze_request());
+        // The break is not started and nothing is counted again
+        assert!(!ui.should_stop_audio_on_input());
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert_eq!(timer.get_pomodoros_completed(), pomodoros);
+        assert!(!timer.take_completed_work());
+    }
+
+    #[test]
+    fn test_scroll_outside_regions_is_ignored() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+
+        // The menu bar at the top doesn't react to the wheel
+        scroll(&mut ui, &mut timer, MouseEventKind::ScrollUp, Rect::new(0, 0, 80, 3));
+        assert_eq!(timer.get_display_time(), "25:00");
+        assert!(ui.take_volume_change().is_none());
+    }
+
+    fn idle_config(auto_resume: bool) -> Config {
+        let mut config = Config::default();
+        config.general.idle_pause_secs = 300;
+        config.general.idle_auto_resume = auto_resume;
+        config
+    }
+
+    #[test]
+    fn test_idle_threshold() {
+        let start = Instant::now();
+        let idle_pause = Duration::from_secs(300);
+
+        assert!(!idle_expired(start, start + Duration::from_secs(299), idle_pause));
+        assert!(idle_expired(start, start + Duration::from_secs(300), idle_pause));
+        assert!(!idle_expired(start, start + Duration::from_secs(3600), Duration::ZERO));
+    }
+
+    #[test]
+    fn test_idle_pauses_work_and_resumes_on_input() {
+        let config = idle_config(true);
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        let start = ui.last_input;
+
+        assert!(!ui.pause_if_idle(&mut timer, start + Duration::from_secs(10)));
+        assert!(ui.pause_if_idle(&mut timer, start + Duration::from_secs(301)));
+        assert!(timer.is_paused());
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("Paused (idle)"));
+
+        assert!(ui.note_input(&mut timer, start + Duration::from_secs(400)));
+        assert!(timer.is_running());
+        // Fresh input restarts the idle countdown
+        assert!(!ui.pause_if_idle(&mut timer, start + Duration::from_secs(500)));
+    }
+
+    #[test]
+    fn test_idle_without_auto_resume_stays_paused() {
+        let config = idle_config(false);
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        let start = ui.last_input;
+
+        assert!(ui.pause_if_idle(&mut timer, start + Duration::from_secs(301)));
+        assert!(!ui.note_input(&mut timer, start + Duration::from_secs(400)));
+        assert!(timer.is_paused());
+    }
+
+    #[test]
+    fn test_only_user_input_ends_an_idle_pause() {
+        let mut config = idle_config(true);
+        config.general.pause_on_blur = true;
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        let start = ui.last_input;
+
+        assert!(ui.pause_if_idle(&mut timer, start + Duration::from_secs(301)));
+        let later = start + Duration::from_secs(400);
+        let moved = MouseEvent { kind: MouseEventKind::Moved, column: 0, row: 0, modifiers: KeyModifiers::NONE };
+        for event in [Event::FocusLost, Event::FocusGained, Event::Resize(100, 40), Event::Mouse(moved)] {
+            ui.handle_event(event, &mut timer, later);
+            assert!(timer.is_paused());
+        }
+        assert_eq!(ui.last_input, start);
+
+        // A key press still resumes it
+        ui.handle_event(Event::Key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)), &mut timer, later);
+        assert!(timer.is_running());
+
+        // And losing focus now is a blur pause, not input
+        ui.handle_event(Event::FocusLost, &mut timer, later);
+        assert!(timer.is_paused());
+        assert!(ui.paused_by_blur);
+    }
+
+    #[test]
+    fn test_idle_leaves_breaks_running() {
+        let config = idle_config(true);
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.skip_session();
+        timer.start();
+
+        assert!(!ui.pause_if_idle(&mut timer, ui.last_input + Duration::from_secs(3600)));
+        assert!(timer.is_running());
+    }
+
+    #[test]
+    fn test_completion_overlay_renders() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+        timer.remaining_time = Duration::ZERO;
+        assert!(timer.tick());
+
+        ui.draw(&timer, &History::default(), Some("🎉 Session completed!"), false)
+            .unwrap();
+        let screen = screen_text(&ui);
+        assert!(screen.contains("Work complete — press any key"));
+        assert!(screen.contains("Session Complete"));
+
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        let screen = screen_text(&ui);
+        assert!(!screen.contains("press any key"));
+        assert!(!screen.contains("Session Complete"));
+    }
+
+    #[test]
+    fn test_cycle_markers() {
+        assert_eq!(cycle_markers((0, 4)), "○○○○");
+        assert_eq!(cycle_markers((2, 4)), "🍅🍅○○");
+        assert_eq!(cycle_markers((4, 4)), "🍅🍅🍅🍅");
+        assert_eq!(cycle_markers((3, 12)), "3/12");
+    }
+
+    #[test]
+    fn test_color_interpolation() {
+        let start = [0, 200, 0];
+        let end = [200, 0, 101];
+
+        assert_eq!(interpolate_color(start, end, 0.0), Color::Rgb(0, 200, 0));
+        assert_eq!(interpolate_color(start, end, 0.5), Color::Rgb(100, 100, 51));
+        assert_eq!(interpolate_color(start, end, 1.0), Color::Rgb(200, 0, 101));
+        // Time added past the session length doesn't overshoot
+        assert_eq!(interpolate_color(start, end, 1.7), Color::Rgb(200, 0, 101));
+    }
+
+    #[test]
+    fn test_gradient_only_applies_to_work() {
+        let mut config = Config::default();
+        config.general.gradient = true;
+        let ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        timer.remaining_time = timer.work_duration / 2;
+        assert_eq!(progress_color(&timer, &ui.render_options), Color::Rgb(110, 100, 0));
+
+        timer.skip_session();
+        assert_eq!(progress_color(&timer, &ui.render_options), Color::Yellow);
+    }
+
+    #[test]
+    fn test_line_input_editing() {
+        let mut input = LineInput::default();
+        for c in "Draft  ".chars() {
+            input.insert(c);
+        }
+        input.backspace();
+        input.insert('x');
+        assert_eq!(input.text, "Draft x");
+
+        let mut empty = LineInput::default();
+        empty.backspace();
+        assert_eq!(empty.text, "");
+
+        assert_eq!(input.submit(), "Draft x");
+    }
+
+    #[test]
+    fn test_number_keys_switch_session() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.start();
+
+        for (key, session, time) in [
+            ('3', SessionType::LongBreak, "15:00"),
+            ('2', SessionType::ShortBreak, "05:00"),
+            ('1', SessionType::Work, "25:00"),
+        ] {
+            press(&mut ui, &mut timer, KeyCode::Char(key));
+            assert_eq!(timer.get_session_type(), session);
+            assert!(timer.is_stopped());
+            ui.draw(&timer, &History::default(), None, false).unwrap();
+            assert!(screen_text(&ui).contains(time), "{}", key);
+        }
+        assert_eq!(timer.get_pomodoros_completed(), 0);
+    }
+
+    #[test]
+    fn test_task_prompt_sets_task() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+
+        press(&mut ui, &mut timer, KeyCode::Char('e'));
+        for c in "Report".chars() {
+            press(&mut ui, &mut timer, KeyCode::Char(c));
+        }
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        // Letters don't reach the estimate or the timer
+        for code in [KeyCode::Char('p'), KeyCode::Char('3'), KeyCode::Char('q')] {
+            press(&mut ui, &mut timer, code);
+        }
+        assert!(timer.is_stopped());
+        assert!(!ui.should_quit);
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("Estimate (pomodoros): 3"));
+
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert_eq!(timer.task().map(|task| (task.name.as_str(), task.estimate)), Some(("Report", 3)));
+        timer.start();
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("Task: Report (0/3"));
+
+        // Reopening starts from the task, and a blank name clears it
+        press(&mut ui, &mut timer, KeyCode::Char('e'));
+        assert_eq!(ui.task_input.as_ref().unwrap().name.text, "Report");
+        for _ in 0.."Report".len() {
+            press(&mut ui, &mut timer, KeyCode::Backspace);
+        }
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert!(timer.task().is_none());
+    }
+
+    #[test]
+    fn test_task_text() {
+        let mut task = Task::new("Report".to_string(), 3);
+        task.actual = 2;
+        assert_eq!(task_text(&task), "Task: Report (2/3 🍅)");
+        task.estimate = 0;
+        assert_eq!(task_text(&task), "Task: Report (2 🍅)");
+
+        let long = Task::new("Write the quarterly report draft".to_string(), 1);
+        assert_eq!(task_text(&long), "Task: Write the quarterly… (0/1 🍅)");
+    }
+
+    #[test]
+    fn test_note_prompt_captures_keys() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        timer.skip_session();
+        ui.open_note_prompt();
+
+        for code in [KeyCode::Char('s'), KeyCode::Char('q'), KeyCode::Backspace, KeyCode::Char('p')] {
+            press(&mut ui, &mut timer, code);
+        }
+        ui.draw(&timer, &History::default(), None, false).unwrap();
+        assert!(screen_text(&ui).contains("> sp"));
+
+        // Letters went to the note, not to the timer
+        assert!(!ui.should_quit);
+        assert!(timer.is_stopped());
+        assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+        assert!(ui.take_submitted_note().is_none());
+
+        press(&mut ui, &mut timer, KeyCode::Enter);
+        assert!(ui.note_input.is_none());
+        assert_eq!(ui.take_submitted_note(), Some("sp".to_string()));
+
+        ui.open_note_prompt();
+        press(&mut ui, &mut timer, KeyCode::Char('x'));
+        press(&mut ui, &mut timer, KeyCode::Esc);
+        assert_eq!(ui.take_submitted_note(), Some(String::new()));
+    }
+
+    #[test]
+    fn test_stats_popup_opens_and_closes() {
+        let config = Config::default();
+        let mut ui = test_ui(&config);
+        let mut timer = Timer::new(config);
+        let history = History::default();
+
+        press(&mut ui, &mut timer, KeyCode::Char('t'));
+        assert!(ui.show_stats);
+        ui.draw(&timer, &history, None, false).unwrap();
+        assert!(screen_text(&ui).contains("No data yet"));
+
+        // Closing must not also act on the key
+        assert!(!press(&mut ui, &mut timer, KeyCode::Char('q')));
+        assert!(!ui.show_stats);
+        assert!(!ui.should_quit);
+    }
+}
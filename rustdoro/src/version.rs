@@ -0,0 +1,62 @@
+use std::sync::OnceLock;
+
+/// Optional features rustdoro can be built with
+const FEATURES: [(&str, bool); 3] = [
+    ("discord", cfg!(feature = "discord")),
+    ("webhooks", cfg!(feature = "webhooks")),
+    ("ipc", cfg!(feature = "ipc")),
+];
+
+/// Get the version with the commit, build date and enabled features, one per line
+/// Shown by `--version` so bug reports say exactly which build they're about.
+pub fn long_version() -> &'static str {
+    static LONG_VERSION: OnceLock<String> = OnceLock::new();
+    LONG_VERSION.get_or_init(|| {
+        format_long_version(
+            env!("CARGO_PKG_VERSION"),
+            env!("RUSTDORO_GIT_HASH"),
+            env!("RUSTDORO_BUILD_DATE"),
+            &FEATURES,
+        )
+    })
+}
+
+/// Assemble the `--version` text from its parts
+fn format_long_version(version: &str, commit: &str, date: &str, features: &[(&str, bool)]) -> String {
+    let enabled: Vec<&str> = features
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect();
+    let features = if enabled.is_empty() {
+        "none".to_string()
+    } else {
+        enabled.join(", ")
+    };
+    format!("{}\ncommit: {}\nbuilt: {}\nfeatures: {}", version, commit, date, features)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_long_version() {
+        let features = [("discord", false), ("webhooks", true), ("ipc", true)];
+        assert_eq!(
+            format_long_version("1.2.3", "abc1234", "2024-05-01", &features),
+            "1.2.3\ncommit: abc1234\nbuilt: 2024-05-01\nfeatures: webhooks, ipc"
+        );
+        assert!(format_long_version("1.2.3", "unknown", "2024-05-01", &[("ipc", false)])
+            .ends_with("features: none"));
+    }
+
+    #[test]
+    fn test_long_version_starts_with_package_version() {
+        let version = long_version();
+        assert!(version.starts_with(&format!("{}\ncommit: ", env!("CARGO_PKG_VERSION"))));
+        let date = version.lines().find_map(|line| line.strip_prefix("built: ")).unwrap();
+        assert!(chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok(), "{}", date);
+        assert_eq!(version.lines().count(), 4);
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::time::Duration;
+use rustdoro::config::WebhooksConfig;
+use rustdoro::timer::SessionType;
+
+/// How long a webhook call may take before it is given up
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Session event reported to a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    WorkStart,
+    BreakStart,
+    Complete,
+}
+
+/// JSON body POSTed to a webhook
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub session_type: SessionType,
+    /// Pomodoros completed so far
+    pub count: u32,
+    pub timestamp: DateTime<Local>,
+}
+
+/// Sends session events to the URLs in the `[Webhooks]` config section
+pub struct Webhooks {
+    client: reqwest::Client,
+    config: WebhooksConfig,
+}
+
+impl Webhooks {
+    /// Create the sender, or None if no webhook is configured
+    pub fn new(config: WebhooksConfig) -> Option<Self> {
+        if config.is_empty() {
+            return None;
+        }
+        Some(Self {
+            client: reqwest::Client::new(),
+            config,
+        })
+    }
+
+    /// Report the start of a session
+    pub fn session_started(&self, session_type: SessionType, count: u32) {
+        let (event, url) = match session_type {
+            SessionType::Work => (WebhookEvent::WorkStart, &self.config.on_work_start),
+            SessionType::ShortBreak | SessionType::LongBreak => {
+                (WebhookEvent::BreakStart, &self.config.on_break_start)
+            }
+        };
+        self.send(url, event, session_type, count);
+    }
+
+    /// Report the end of a session
+    pub fn session_completed(&self, session_type: SessionType, count: u32) {
+        self.send(&self.config.on_complete, WebhookEvent::Complete, session_type, count);
+    }
+
+    /// POST the event in the background so a slow server can't stall the UI
+    fn send(&self, url: &Option<String>, event: WebhookEvent, session_type: SessionType, count: u32) {
+        let Some(url) = url.clone() else {
+            return;
+        };
+        let payload = WebhookPayload {
+            event,
+            session_type,
+            count,
+            timestamp: Local::now(),
+        };
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            if let Err(e) = post(&client, &url, &payload).await {
+                tracing::warn!("Webhook {} failed: {}", url, e);
+            }
+        });
+    }
+}
+
+/// POST the payload as JSON, failing on error responses
+async fn post(client: &reqwest::Client, url: &str, payload: &WebhookPayload) -> Result<()> {
+    client
+        .post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(payload)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answer one HTTP request with `status` and return the request body
+    async fn mock_server(status: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        let response = format!("HTTP/1.1 {}\r\ncontent-length: 0\r\n\r\n", status);
+                        stream.write_all(response.as_bytes()).await.unwrap();
+                        return body.to_string();
+                    }
+                }
+            }
+        });
+        (url, server)
+    }
+
+    fn payload() -> WebhookPayload {
+        WebhookPayload {
+            event: WebhookEvent::BreakStart,
+            session_type: SessionType::LongBreak,
+            count: 4,
+            timestamp: Local::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_sends_payload() {
+        let (url, server) = mock_server("200 OK").await;
+        post(&reqwest::Client::new(), &url, &payload()).await.unwrap();
+
+        let body: serde_json::Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(body["event"], "break_start");
+        assert_eq!(body["session_type"], "long_break");
+        assert_eq!(body["count"], 4);
+        let timestamp = body["timestamp"].as_str().unwrap();
+        assert!(DateTime::parse_from_rfc3339(timestamp).is_ok());
+        assert_eq!(body.as_object().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_on_error_status() {
+        let (url, server) = mock_server("500 Internal Server Error").await;
+        assert!(post(&reqwest::Client::new(), &url, &payload()).await.is_err());
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_no_sender_without_urls() {
+        assert!(Webhooks::new(WebhooksConfig::default()).is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use rustdoro::config::Config;
+use rustdoro::timer::{SessionType, Timer};
+
+/// Run the current session down to zero and tick once
+fn finish_session(timer: &mut Timer) -> bool {
+    timer.remaining_time = Duration::ZERO;
+    timer.tick()
+}
+
+#[test]
+fn work_then_break_cycle() {
+    let mut timer = Timer::new(Config::default());
+    assert_eq!(timer.get_session_type(), SessionType::Work);
+    assert_eq!(timer.get_display_time(), "25:00");
+    assert!(timer.is_stopped());
+
+    // A stopped timer doesn't advance
+    assert!(!timer.tick());
+
+    timer.toggle_pause();
+    assert!(timer.is_running());
+    assert!(finish_session(&mut timer));
+
+    assert_eq!(timer.get_session_type(), SessionType::ShortBreak);
+    assert_eq!(timer.get_pomodoros_completed(), 1);
+    assert_eq!(timer.get_display_time(), "05:00");
+    assert_eq!(timer.get_progress(), 0.0);
+    assert!(timer.is_stopped());
+
+    timer.toggle_pause();
+    assert!(finish_session(&mut timer));
+    assert_eq!(timer.get_session_type(), SessionType::Work);
+    assert_eq!(timer.get_pomodoros_completed(), 1);
+}
+
+#[test]
+fn long_break_after_a_full_set() {
+    let mut config = Config::default();
+    config.time.tomatoes_per_set = 2;
+    let mut timer = Timer::new(config);
+
+    let mut breaks = Vec::new();
+    for _ in 0..2 {
+        timer.toggle_pause();
+        finish_session(&mut timer);
+        breaks.push(timer.get_session_type());
+        timer.skip_session();
+    }
+
+    assert_eq!(breaks, [SessionType::ShortBreak, SessionType::LongBreak]);
+}